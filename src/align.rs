@@ -1,10 +1,13 @@
-use std::cmp::max;
-use std::collections::VecDeque;
+use std::cmp::min;
 
 const DELETION_COST: usize = 2;
 const INSERTION_COST: usize = 2;
 // extra cost for starting a new group of changed tokens
 const INITIAL_MISMATCH_PENALTY: usize = 1;
+// cost of an impossible alignment, which can still be added to
+const IMPOSSIBLE_COST: usize = usize::MAX / 4;
+// number of cells above which a table is split in two rather than filled
+const MAX_TABLE_CELLS: usize = 4096;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Operation {
@@ -15,134 +18,60 @@ pub enum Operation {
 
 use Operation::*;
 
-/// Needleman-Wunsch / Wagner-Fischer table for computation of edit distance and associated
-/// alignment.
-#[derive(Clone, Debug)]
-struct Cell {
-    parent: usize,
-    operation: Operation,
-    cost: usize,
+/// The state of an alignment between two edit operations: whether a further deletion or insertion
+/// would start a new group of changed tokens (after a NoOp, or at the start) or not.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Matched,
+    Changed,
 }
 
+use State::*;
+
+// The costs of the alignments ending in each state.
+type Costs = [usize; 2];
+
+/// Alignment of two sequences of tokens: the edit operations of least cost, as computed by
+/// `levenshtein_distance`, transforming one into the other. They are found in space proportional
+/// to the total number of tokens, by splitting the table of costs in two at its middle row until
+/// the parts are small enough to be filled (Hirschberg's method).
 #[derive(Debug)]
 pub struct Alignment<'a> {
     pub x: Vec<&'a str>,
     pub y: Vec<&'a str>,
-    table: Vec<Cell>,
-    dim: [usize; 2],
+    operations: Vec<Operation>,
 }
 
 impl<'a> Alignment<'a> {
-    /// Fill table for Levenshtein distance / alignment computation
+    /// Compute the edit operations transforming x into y.
     pub fn new(x: Vec<&'a str>, y: Vec<&'a str>) -> Self {
         // TODO: Something downstream of the alignment algorithm requires that the first token in
         // both x and y is "", so this is explicitly inserted in `tokenize()`.
-        let dim = [y.len() + 1, x.len() + 1];
-        let table = vec![
-            Cell {
-                parent: 0,
-                operation: NoOp,
-                cost: 0
-            };
-            dim[0] * dim[1]
-        ];
-        let mut alignment = Self { x, y, table, dim };
-        alignment.fill();
-        alignment
-    }
-
-    /// Fill table for Levenshtein distance / alignment computation
-    pub fn fill(&mut self) {
-        // x is written along the top of the table; y is written down the left side of the
-        // table. Also, we insert a 0 in cell (0, 0) of the table, so x and y are shifted by one
-        // position. Therefore, the element corresponding to (x[i], y[j]) is in column (i + 1) and
-        // row (j + 1); the index of this element is given by index(i, j).
-        for i in 1..self.dim[1] {
-            self.table[i] = Cell {
-                parent: 0,
-                operation: Deletion,
-                cost: i * DELETION_COST + INITIAL_MISMATCH_PENALTY,
-            };
-        }
-        for j in 1..self.dim[0] {
-            self.table[j * self.dim[1]] = Cell {
-                parent: 0,
-                operation: Insertion,
-                cost: j * INSERTION_COST + INITIAL_MISMATCH_PENALTY,
-            };
-        }
-
-        for (i, x_i) in self.x.iter().enumerate() {
-            for (j, y_j) in self.y.iter().enumerate() {
-                let (left, diag, up) =
-                    (self.index(i, j + 1), self.index(i, j), self.index(i + 1, j));
-                // The order of the candidates matters if two of them have the
-                // same cost as in that case we choose the first one. Consider
-                // insertions and deletions before matches in order to group
-                // changes together. Insertions are preferred to deletions in
-                // order to highlight moved tokens as a deletion followed by an
-                // insertion (as the edit sequence is read backwards we need to
-                // choose the insertion first)
-                let candidates = [
-                    Cell {
-                        parent: up,
-                        operation: Insertion,
-                        cost: self.mismatch_cost(up, INSERTION_COST),
-                    },
-                    Cell {
-                        parent: left,
-                        operation: Deletion,
-                        cost: self.mismatch_cost(left, DELETION_COST),
-                    },
-                    Cell {
-                        parent: diag,
-                        operation: NoOp,
-                        cost: if x_i == y_j {
-                            self.table[diag].cost
-                        } else {
-                            usize::MAX
-                        },
-                    },
-                ];
-                let index = self.index(i + 1, j + 1);
-                self.table[index] = candidates
-                    .iter()
-                    .min_by_key(|cell| cell.cost)
-                    .unwrap()
-                    .clone();
-            }
-        }
-    }
-
-    fn mismatch_cost(&self, parent: usize, basic_cost: usize) -> usize {
-        self.table[parent].cost
-            + basic_cost
-            + if self.table[parent].operation == NoOp {
-                INITIAL_MISMATCH_PENALTY
-            } else {
-                0
-            }
+        // Tokens common to the start of x and y are left unchanged. (Those common to their end
+        // are not skipped, as insertions and deletions are preferred after matching tokens.)
+        let prefix_len = x.iter().zip(&y).take_while(|(a, b)| a == b).count();
+        let mut operations = Vec::with_capacity(x.len() + y.len());
+        operations.resize(prefix_len, NoOp);
+        align(
+            &x[prefix_len..],
+            &y[prefix_len..],
+            Matched,
+            None,
+            &mut operations,
+        );
+        Self { x, y, operations }
     }
 
-    /// Read edit operations from the table.
+    /// Return the edit operations.
     pub fn operations(&self) -> Vec<Operation> {
-        let mut ops = VecDeque::with_capacity(max(self.x.len(), self.y.len()));
-        let mut cell = &self.table[self.index(self.x.len(), self.y.len())];
-        loop {
-            ops.push_front(cell.operation);
-            if cell.parent == 0 {
-                break;
-            }
-            cell = &self.table[cell.parent];
-        }
-        Vec::from(ops)
+        self.operations.clone()
     }
 
     pub fn coalesced_operations(&self) -> Vec<(Operation, usize)> {
         run_length_encode(self.operations())
     }
 
-    /// Compute custom distance metric from the filled table. The distance metric is
+    /// Compute custom distance metric from the edit operations. The distance metric is
     ///
     /// (total length of edits) / (total length of longer string)
     ///
@@ -156,8 +85,8 @@ impl<'a> Alignment<'a> {
     #[allow(dead_code)]
     pub fn distance_parts(&self) -> (usize, usize) {
         let (mut numer, mut denom) = (0, 0);
-        for op in self.operations() {
-            if op != NoOp {
+        for op in &self.operations {
+            if *op != NoOp {
                 numer += 1;
             }
             denom += 1;
@@ -165,50 +94,251 @@ impl<'a> Alignment<'a> {
         (numer, denom)
     }
 
-    /// Compute levenshtein distance from the filled table.
+    /// Compute the cost of the edit operations: each deletion and insertion has a cost, and each
+    /// group of consecutive deletions and insertions an additional one.
     #[allow(dead_code)]
     pub fn levenshtein_distance(&self) -> usize {
-        self.table[self.index(self.x.len(), self.y.len())].cost
+        let mut cost = 0;
+        let mut previous = NoOp;
+        for op in &self.operations {
+            cost += match op {
+                NoOp => 0,
+                Deletion => DELETION_COST,
+                Insertion => INSERTION_COST,
+            };
+            if *op != NoOp && previous == NoOp {
+                cost += INITIAL_MISMATCH_PENALTY;
+            }
+            previous = *op;
+        }
+        cost
     }
+}
 
-    // Row-major storage of 2D array.
-    fn index(&self, i: usize, j: usize) -> usize {
-        j * self.dim[1] + i
+/// Append the edit operations of least cost transforming x into y, from the `start` state to the
+/// `end` state if any, to `operations`.
+fn align(
+    x: &[&str],
+    y: &[&str],
+    start: State,
+    end: Option<State>,
+    operations: &mut Vec<Operation>,
+) {
+    let (n, m) = (x.len(), y.len());
+    if m <= 1 || (n + 1) * (m + 1) <= MAX_TABLE_CELLS {
+        Table::fill(x, y, start).append_operations(end, operations);
+        return;
     }
+    // The alignment crosses the middle row in the column and state of least total cost of the
+    // alignments of the top part from the start and of the bottom part to the end.
+    let mid = m / 2;
+    let top = costs_from_start(x, &y[..mid], start);
+    let bottom = costs_to_end(x, &y[mid..], end);
+    let (i, state) = (0..=n)
+        .flat_map(|i| [(i, Changed), (i, Matched)])
+        .min_by_key(|&(i, state)| top[i][state as usize] + bottom[i][state as usize])
+        .unwrap();
+    align(&x[..i], &y[..mid], start, Some(state), operations);
+    align(&x[i..], &y[mid..], state, end, operations);
+}
 
-    #[allow(dead_code)]
-    fn format_cell(&self, cell: &Cell) -> String {
-        let parent = &self.table[cell.parent];
-        let op = match cell.operation {
-            Deletion => "-",
-            Insertion => "+",
-            NoOp => ".",
-        };
-        format!("{}{}{}", parent.cost, op, cell.cost)
+/// The costs of the alignments of x with y from the `start` state, ending in each column of the
+/// last row.
+fn costs_from_start(x: &[&str], y: &[&str], start: State) -> Vec<Costs> {
+    let mut row = vec![[IMPOSSIBLE_COST; 2]; x.len() + 1];
+    row[0][start as usize] = 0;
+    for i in 1..=x.len() {
+        row[i][Changed as usize] = change_cost(&row[i - 1], DELETION_COST);
+    }
+    for y_j in y {
+        let mut diagonal = row[0];
+        row[0] = [IMPOSSIBLE_COST, change_cost(&row[0], INSERTION_COST)];
+        for (i, x_i) in x.iter().enumerate() {
+            let above = row[i + 1];
+            row[i + 1] = [
+                if x_i == y_j {
+                    min(diagonal[Matched as usize], diagonal[Changed as usize])
+                } else {
+                    IMPOSSIBLE_COST
+                },
+                min(
+                    change_cost(&row[i], DELETION_COST),
+                    change_cost(&above, INSERTION_COST),
+                ),
+            ];
+            diagonal = above;
+        }
     }
+    row
+}
 
-    #[allow(dead_code)]
-    fn print(&self) {
-        println!("x: {:?}", self.x);
-        println!("y: {:?}", self.y);
-        println!();
-        print!("      ");
-        for j in 0..self.dim[1] {
-            print!("{}     ", if j > 0 { self.x[j - 1] } else { " " })
+/// The costs of the alignments of x with y from each column of the first row, in each state, to
+/// the `end` state if any.
+fn costs_to_end(x: &[&str], y: &[&str], end: Option<State>) -> Vec<Costs> {
+    let n = x.len();
+    let mut row = vec![[IMPOSSIBLE_COST; 2]; n + 1];
+    for state in [Matched, Changed] {
+        if end.is_none() || end == Some(state) {
+            row[n][state as usize] = 0;
         }
-        println!();
+    }
+    for i in (0..n).rev() {
+        row[i] = costs_before_change(row[i + 1][Changed as usize] + DELETION_COST);
+    }
+    for y_j in y.iter().rev() {
+        let mut diagonal = row[n];
+        row[n] = costs_before_change(row[n][Changed as usize] + INSERTION_COST);
+        for (i, x_i) in x.iter().enumerate().rev() {
+            let below = row[i];
+            let change = min(
+                row[i + 1][Changed as usize] + DELETION_COST,
+                below[Changed as usize] + INSERTION_COST,
+            );
+            let no_op = if x_i == y_j {
+                diagonal[Matched as usize]
+            } else {
+                IMPOSSIBLE_COST
+            };
+            row[i] = [
+                min(no_op, change + INITIAL_MISMATCH_PENALTY),
+                min(no_op, change),
+            ];
+            diagonal = below;
+        }
+    }
+    row
+}
+
+// The cost of a deletion or insertion following an alignment ending in either state.
+fn change_cost(costs: &Costs, basic_cost: usize) -> usize {
+    min(
+        costs[Matched as usize] + basic_cost + INITIAL_MISMATCH_PENALTY,
+        costs[Changed as usize] + basic_cost,
+    )
+}
+
+// The costs, from either state, of an alignment starting with a change of this cost.
+fn costs_before_change(cost: usize) -> Costs {
+    [cost + INITIAL_MISMATCH_PENALTY, cost]
+}
 
-        for i in 0..self.dim[0] {
-            for j in 0..self.dim[1] {
-                if j == 0 {
-                    print!("{}     ", if i > 0 { self.y[i - 1] } else { " " })
+/// Needleman-Wunsch / Wagner-Fischer table for computation of edit distance and associated
+/// alignment, with a cell for each state of each pair of prefixes of x and y.
+struct Table {
+    cells: Vec<[Cell; 2]>,
+    n: usize,
+    m: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Cell {
+    operation: Operation,
+    previous: State,
+    cost: usize,
+}
+
+impl Table {
+    fn fill(x: &[&str], y: &[&str], start: State) -> Self {
+        let (n, m) = (x.len(), y.len());
+        let impossible = Cell {
+            operation: NoOp,
+            previous: Matched,
+            cost: IMPOSSIBLE_COST,
+        };
+        let mut table = Self {
+            cells: vec![[impossible; 2]; (n + 1) * (m + 1)],
+            n,
+            m,
+        };
+        table.cells[0][start as usize].cost = 0;
+        // x is written along the top of the table; y is written down the left side of the table.
+        // The element corresponding to (x[i - 1], y[j - 1]) is in column i and row j.
+        for j in 0..=m {
+            for i in 0..=n {
+                if i == 0 && j == 0 {
+                    continue;
                 }
-                let cell = &self.table[self.index(j, i)];
-                print!("{}   ", self.format_cell(cell));
+                let matched = if i > 0 && j > 0 && x[i - 1] == y[j - 1] {
+                    table.cheapest(NoOp, i - 1, j - 1, 0)
+                } else {
+                    impossible
+                };
+                // Insertions are preferred to deletions of the same cost in order to highlight
+                // moved tokens as a deletion followed by an insertion (as the edit sequence is
+                // read backwards we need to choose the insertion first).
+                let insertion = if j > 0 {
+                    table.cheapest(Insertion, i, j - 1, INSERTION_COST)
+                } else {
+                    impossible
+                };
+                let deletion = if i > 0 {
+                    table.cheapest(Deletion, i - 1, j, DELETION_COST)
+                } else {
+                    impossible
+                };
+                let changed = if deletion.cost < insertion.cost {
+                    deletion
+                } else {
+                    insertion
+                };
+                let index = table.index(i, j);
+                table.cells[index] = [matched, changed];
+            }
+        }
+        table
+    }
+
+    // The cheapest way of reaching a cell by this operation from the cell in column i and row j,
+    // from the changed state if that is cheapest from there, or as cheap.
+    fn cheapest(&self, operation: Operation, i: usize, j: usize, basic_cost: usize) -> Cell {
+        let [matched, changed] = self.cells[self.index(i, j)];
+        let penalty = if operation == NoOp {
+            0
+        } else {
+            INITIAL_MISMATCH_PENALTY
+        };
+        if changed.cost <= matched.cost || changed.cost < matched.cost + penalty {
+            Cell {
+                operation,
+                previous: Changed,
+                cost: changed.cost + basic_cost,
+            }
+        } else {
+            Cell {
+                operation,
+                previous: Matched,
+                cost: matched.cost + penalty + basic_cost,
             }
-            println!();
         }
-        println!();
+    }
+
+    /// Read edit operations ending in the `end` state, if any, from the table and append them to
+    /// `operations`.
+    fn append_operations(&self, end: Option<State>, operations: &mut Vec<Operation>) {
+        let [matched, changed] = self.cells[self.index(self.n, self.m)];
+        let mut state = end.unwrap_or(if changed.cost <= matched.cost {
+            Changed
+        } else {
+            Matched
+        });
+        let start = operations.len();
+        let (mut i, mut j) = (self.n, self.m);
+        while i > 0 || j > 0 {
+            let cell = self.cells[self.index(i, j)][state as usize];
+            operations.push(cell.operation);
+            if cell.operation != Insertion {
+                i -= 1;
+            }
+            if cell.operation != Deletion {
+                j -= 1;
+            }
+            state = cell.previous;
+        }
+        operations[start..].reverse();
+    }
+
+    fn index(&self, i: usize, j: usize) -> usize {
+        j * (self.n + 1) + i
     }
 }
 
@@ -399,6 +529,70 @@ mod tests {
         .run();
     }
 
+    #[test]
+    fn test_7() {
+        // Insertions are moved past matching tokens in order to group them together.
+        TestCase {
+            before: "a.b",
+            after: "(a.c, a.b)",
+            distance: 16,
+            parts: (7, 10),
+            operations: vec![
+                Insertion, // - (
+                Insertion, // - A
+                Insertion, // - .
+                Insertion, // - C
+                Insertion, // - ,
+                Insertion, // - SPACE
+                NoOp,      // A A
+                NoOp,      // . .
+                NoOp,      // B B
+                Insertion, // - )
+            ],
+        }
+        .run();
+    }
+
+    #[test]
+    fn test_split_alignment_costs_the_same_as_filled_table() {
+        // Pseudo-random pairs of lines, long enough for the table to be split.
+        let mut seed = 1_u64;
+        let mut random_line = |len: usize| -> Vec<&str> {
+            (0..len)
+                .map(|_| {
+                    seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                    ["a", "b", " ", "c"][(seed >> 33) as usize % 4]
+                })
+                .collect()
+        };
+        let sizes = (0..200).map(|k| (60 + k % 97, 70 + k % 89));
+        for (n, m) in sizes.chain([(3, 1500), (2000, 2), (257, 129)]) {
+            let (x, y) = (random_line(n), random_line(m));
+            assert!((n + 1) * (m + 1) > MAX_TABLE_CELLS);
+            let alignment = Alignment::new(x.clone(), y.clone());
+            let table = Table::fill(&x, &y, Matched);
+            let [matched, changed] = table.cells[table.index(n, m)];
+            assert_eq!(
+                alignment.levenshtein_distance(),
+                min(matched.cost, changed.cost)
+            );
+            let operations = alignment.operations();
+            let (mut i, mut j) = (0, 0);
+            for op in operations {
+                match op {
+                    NoOp => {
+                        assert_eq!(x[i], y[j]);
+                        i += 1;
+                        j += 1;
+                    }
+                    Deletion => i += 1,
+                    Insertion => j += 1,
+                }
+            }
+            assert_eq!((i, j), (n, m));
+        }
+    }
+
     struct TestCase<'a> {
         before: &'a str,
         after: &'a str,
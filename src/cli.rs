@@ -181,6 +181,14 @@ For a short help summary, please use delta -h.
 "
 )]
 pub struct Opt {
+    #[arg(long = "binary-hexdump")]
+    /// Show a side-by-side hexdump of the bytes that differ between two versions of a binary file.
+    ///
+    /// The file contents are read from the git repository in which delta is running, using the blob
+    /// ids of the "index" line of the diff. Outside of a repository, or when the blobs are not
+    /// available, only git's own description of the change is shown.
+    pub binary_hexdump: bool,
+
     #[arg(long = "blame-code-style", value_name = "STYLE")]
     /// Style string for the code section of a git blame line.
     ///
@@ -196,9 +204,20 @@ pub struct Opt {
     )]
     /// Format string for git blame commit metadata.
     ///
-    /// Available placeholders are "{timestamp}", "{author}", and "{commit}".
+    /// Available placeholders are "{timestamp}", "{author}", and "{commit}". With `git blame
+    /// --porcelain` or `git blame --line-porcelain` input, "{summary}", "{email}", and "{filename}"
+    /// are also available; they are empty otherwise. With `git blame -M` or `git blame -C`,
+    /// "{origin-file}" and "{origin-line}" show the file and the line number that a line was moved
+    /// or copied from, as far as they are known.
     pub blame_format: String,
 
+    #[arg(long = "blame-moved-style", value_name = "STYLE")]
+    /// Style string for the blame metadata of lines moved or copied from another file.
+    ///
+    /// These are the lines which `git blame -M` or `git blame -C` attributes to a file other than
+    /// the file being blamed. By default their metadata is shown in italics.
+    pub blame_moved_style: Option<String>,
+
     #[arg(long = "blame-palette", value_name = "COLORS")]
     /// Background colors used for git blame lines (space-separated string).
     ///
@@ -250,10 +269,61 @@ pub struct Opt {
     /// intended for other tools that use delta.
     pub color_only: bool,
 
+    #[arg(long = "combined-diff-columns")]
+    /// Show the changes of a combined diff as one column per parent of the merge.
+    ///
+    /// Combined diffs are output by e.g. `git show --cc` for merge commits. Each column shows the
+    /// diff from a parent to the merge result, in the order of the parents, so that the
+    /// resolution of a merge can be reviewed relative to each side. Ignored with side-by-side.
+    pub combined_diff_columns: bool,
+
     #[arg(long = "config", default_value = "", value_name = "PATH")]
     /// Load the config file at PATH instead of ~/.gitconfig.
     pub config: String,
 
+    #[arg(
+        long = "commit-author-style",
+        default_value = "raw",
+        value_name = "STYLE"
+    )]
+    /// Style string for the author of commits in git log output.
+    ///
+    /// This styles the value of the Author: (and, with --pretty=fuller, Commit:) lines. See STYLES
+    /// section.
+    pub commit_author_style: String,
+
+    #[arg(
+        long = "commit-branch-style",
+        default_value = "raw",
+        value_name = "STYLE"
+    )]
+    /// Style string for the local branches in the ref names of the commit line.
+    ///
+    /// E.g. HEAD and main in `commit 94907c0f (HEAD -> main, origin/main, tag: v1)`. If any of this,
+    /// commit-remote-style and commit-tag-style is not raw, the ref names are painted in these
+    /// styles and the rest of the commit line in commit-style. See STYLES section.
+    pub commit_branch_style: String,
+
+    #[arg(long = "commit-date-format", value_name = "FORMAT")]
+    /// Format string for the date of commits in git log output.
+    ///
+    /// If set to "relative", dates are shown relative to now, e.g. "3 weeks ago". Otherwise this is
+    /// a strftime format string, see (https://docs.rs/chrono/latest/chrono/format/strftime/index.html).
+    /// Dates are only re-rendered if they are in a format output by git log --date=default, iso,
+    /// iso-strict or rfc.
+    pub commit_date_format: Option<String>,
+
+    #[arg(
+        long = "commit-date-style",
+        default_value = "raw",
+        value_name = "STYLE"
+    )]
+    /// Style string for the date of commits in git log output.
+    ///
+    /// This styles the value of the Date: (and, with --pretty=fuller, AuthorDate: and CommitDate:)
+    /// lines. See STYLES section.
+    pub commit_date_style: String,
+
     #[arg(
         long = "commit-decoration-style",
         default_value = "",
@@ -273,6 +343,51 @@ pub struct Opt {
     /// Regular expression used to identify the commit line when parsing git output.
     pub commit_regex: String,
 
+    #[arg(
+        long = "commit-remote-style",
+        default_value = "raw",
+        value_name = "STYLE"
+    )]
+    /// Style string for the remote-tracking branches in the ref names of the commit line.
+    ///
+    /// E.g. origin/main in `commit 94907c0f (HEAD -> main, origin/main, tag: v1)`. See
+    /// commit-branch-style and STYLES section.
+    pub commit_remote_style: String,
+
+    #[arg(
+        long = "commit-signature-bad-style",
+        default_value = "bold red reverse",
+        value_name = "STYLE"
+    )]
+    /// Style string for the line summarizing a bad commit signature.
+    ///
+    /// With git log --show-signature, the lines output by gpg or ssh-keygen to verify the signature
+    /// of a commit are replaced by a single line, e.g. "✘ BAD signature from Name <email> (RSA key
+    /// 4AEE18F83AFDEB23)". If this style is raw, the lines are output unchanged. See STYLES section.
+    pub commit_signature_bad_style: String,
+
+    #[arg(
+        long = "commit-signature-good-style",
+        default_value = "green",
+        value_name = "STYLE"
+    )]
+    /// Style string for the line summarizing a good commit signature.
+    ///
+    /// A signature is good if it was verified with a trusted key. See
+    /// commit-signature-bad-style and STYLES section.
+    pub commit_signature_good_style: String,
+
+    #[arg(
+        long = "commit-signature-unknown-style",
+        default_value = "yellow",
+        value_name = "STYLE"
+    )]
+    /// Style string for the line summarizing a commit signature which could not be verified.
+    ///
+    /// E.g. because the public key, or its trust, is unknown. See commit-signature-bad-style and
+    /// STYLES section.
+    pub commit_signature_unknown_style: String,
+
     #[arg(long = "commit-style", default_value = "raw", value_name = "STYLE")]
     /// Style string for the commit hash line.
     ///
@@ -280,6 +395,24 @@ pub struct Opt {
     /// output.
     pub commit_style: String,
 
+    #[arg(long = "commit-tag-style", default_value = "raw", value_name = "STYLE")]
+    /// Style string for the tags in the ref names of the commit line.
+    ///
+    /// E.g. "tag: v1" in `commit 94907c0f (HEAD -> main, origin/main, tag: v1)`. See
+    /// commit-branch-style and STYLES section.
+    pub commit_tag_style: String,
+
+    #[arg(
+        long = "commit-trailer-style",
+        default_value = "raw",
+        value_name = "STYLE"
+    )]
+    /// Style string for the trailers of commit messages in git log output.
+    ///
+    /// Trailers are lines such as "Signed-off-by: Name <email>" and "Co-authored-by: Name <email>".
+    /// See STYLES section.
+    pub commit_trailer_style: String,
+
     #[arg(long = "dark")]
     /// Use default colors appropriate for a dark terminal background.
     ///
@@ -293,6 +426,16 @@ pub struct Opt {
     /// set this in per-repository git config (.git/config)
     pub default_language: Option<String>,
 
+    #[arg(long = "detect-moved")]
+    /// Detect blocks of lines moved within a commit, without relying on git's --color-moved.
+    ///
+    /// Moved lines are painted in minus-moved-style and plus-moved-style. A block of at least
+    /// --moved-min-lines lines is detected as moved, even if some of its lines were also edited;
+    /// the edits are emphasized against the lines they were moved from. The input is handled one
+    /// commit at a time, and diffs without commits as a whole. Not supported with `git log
+    /// --graph`.
+    pub detect_moved: bool,
+
     #[arg(long = "diff-highlight")]
     /// Emulate diff-highlight.
     ///
@@ -521,6 +664,14 @@ pub struct Opt {
     /// https://github.com/dandavison/open-in-editor for an example.
     pub hyperlinks_file_link_format: String,
 
+    #[arg(long = "hyperlinks-signature-link-format", value_name = "FMT")]
+    /// Format string for hyperlinks of commit signature keys (requires --hyperlinks).
+    ///
+    /// The placeholder "{key}" will be replaced by the id or fingerprint of the key which made the
+    /// signature, as shown by git log --show-signature. For example:
+    /// --hyperlinks-signature-link-format='https://keys.openpgp.org/search?q={key}'
+    pub hyperlinks_signature_link_format: Option<String>,
+
     #[arg(
         long = "inline-hint-style",
         default_value = "blue",
@@ -544,6 +695,17 @@ pub struct Opt {
     /// Git's --color-moved feature. Set this to "false" to disable this behavior.
     pub inspect_raw_lines: String,
 
+    #[arg(long = "interactive-filter")]
+    /// Emit exactly one output line per input line, for use as interactive.diffFilter.
+    ///
+    /// This is what `git add -p` and the other interactive patch commands require of their diff
+    /// filter. It implies --color-only, which keeps the styles of your delta configuration and
+    /// e.g. line numbers, but drops the decorations that add lines. Should some output
+    /// nevertheless not correspond to its input line for line, the raw input is emitted instead.
+    /// This mode is turned on automatically when delta is called by `git add -p`, unless
+    /// interactive-filter is set (to true or false) in git config.
+    pub interactive_filter: bool,
+
     #[arg(long = "keep-plus-minus-markers")]
     /// Prefix added/removed lines with a +/- character, as git does.
     ///
@@ -557,7 +719,7 @@ pub struct Opt {
     /// For more control, see the style options and --syntax-theme.
     pub light: bool,
 
-    #[arg(long = "line-buffer-size", default_value = "32", value_name = "N")]
+    #[arg(long = "line-buffer-size", default_value = "64", value_name = "N")]
     /// Size of internal line buffer.
     ///
     /// Delta compares the added and removed versions of nearby lines in order to detect and
@@ -677,7 +839,7 @@ pub struct Opt {
     /// insertion operations transforming one into the other.
     pub max_line_distance: f64,
 
-    #[arg(long = "max-line-length", default_value = "512", value_name = "N")]
+    #[arg(long = "max-line-length", default_value = "2048", value_name = "N")]
     /// Truncate lines longer than this.
     ///
     /// To prevent any truncation, set to zero. Note that delta will be slow on very long lines
@@ -770,6 +932,16 @@ pub struct Opt {
     /// See STYLES section.
     pub minus_emph_style: String,
 
+    #[arg(
+        long = "minus-moved-style",
+        default_value = "normal auto",
+        value_name = "STYLE"
+    )]
+    /// Style string for removed lines which were moved, with --detect-moved.
+    ///
+    /// See STYLES section.
+    pub minus_moved_style: String,
+
     #[arg(
         long = "minus-non-emph-style",
         default_value = "minus-style",
@@ -790,6 +962,12 @@ pub struct Opt {
     /// See STYLES section.
     pub minus_style: String,
 
+    #[arg(long = "moved-min-lines", default_value = "3", value_name = "N")]
+    /// Minimum number of lines of a block detected as moved, with --detect-moved.
+    ///
+    /// Only lines containing alphanumeric characters, and not edited, are counted.
+    pub moved_min_lines: usize,
+
     #[arg(long = "navigate")]
     /// Activate diff navigation.
     ///
@@ -807,6 +985,26 @@ pub struct Opt {
     /// See GIT CONFIG section.
     pub no_gitconfig: bool,
 
+    #[arg(
+        long = "notes-decoration-style",
+        default_value = "box",
+        value_name = "STYLE"
+    )]
+    /// Style string for the decoration of the header of commit notes.
+    ///
+    /// With git log --notes, the header of each block of notes, e.g. "Notes (review):", is replaced
+    /// by the name of the notes ref, "commits" for the default ref, in this decoration. See STYLES
+    /// section. The style string should contain one of the special attributes 'box', 'ul'
+    /// (underline), 'ol' (overline), or the combination 'ul ol'.
+    pub notes_decoration_style: String,
+
+    #[arg(long = "notes-style", default_value = "raw", value_name = "STYLE")]
+    /// Style string for commit notes.
+    ///
+    /// This styles the lines of the notes shown by git log after the message of a commit, and the
+    /// name of their notes ref. See STYLES section.
+    pub notes_style: String,
+
     #[arg(long = "pager", value_name = "CMD")]
     /// Which pager to use.
     ///
@@ -852,6 +1050,16 @@ pub struct Opt {
     /// Used only if --plus-style has no background color.
     pub plus_empty_line_marker_style: String,
 
+    #[arg(
+        long = "plus-moved-style",
+        default_value = "normal auto",
+        value_name = "STYLE"
+    )]
+    /// Style string for added lines which were moved, with --detect-moved.
+    ///
+    /// See STYLES section.
+    pub plus_moved_style: String,
+
     #[arg(
         long = "plus-non-emph-style",
         default_value = "plus-style",
@@ -925,8 +1133,36 @@ pub struct Opt {
 
     #[arg(short = 's', long = "side-by-side")]
     /// Display diffs in side-by-side layout.
+    ///
+    /// Merge conflicts which include the ancestral lines (merge.conflictStyle diff3 or zdiff3) are
+    /// displayed in three panels: ours, the ancestral lines, and theirs.
+    ///
+    /// In git config, `side-by-side = auto` uses side-by-side layout only when the terminal is at
+    /// least --side-by-side-min-width columns wide, and unified layout otherwise.
     pub side_by_side: bool,
 
+    #[arg(long = "side-by-side-min-width", default_value = "0", value_name = "N")]
+    /// Minimum width for side-by-side layout.
+    ///
+    /// When the width available to delta is less than this, `side-by-side = auto` falls back to
+    /// unified layout for the whole output. With side-by-side enabled unconditionally, hunks with
+    /// lines that do not fit in the panels are displayed in unified layout, and the other hunks
+    /// stay side-by-side. The default, 0, never falls back.
+    pub side_by_side_min_width: usize,
+
+    #[arg(
+        long = "side-by-side-split",
+        default_value = "50",
+        value_name = "SPLIT"
+    )]
+    /// How to split the width between the panels in side-by-side mode.
+    ///
+    /// Either the percentage of the width given to the left panel, between 20 and 80, or
+    /// "adaptive". With "adaptive", the panels are sized in proportion to the longest minus and
+    /// plus lines of each hunk (including its unchanged lines), each panel being given at least
+    /// 20% of the width. Unchanged lines are wrapped to fit the narrower panel.
+    pub side_by_side_split: String,
+
     #[arg(long = "syntax-theme", value_name = "SYNTAX_THEME")]
     /// The syntax-highlighting theme to use.
     ///
@@ -944,6 +1180,14 @@ pub struct Opt {
     /// incorrect.
     pub tab_width: usize,
 
+    #[arg(long = "tree-summary")]
+    /// Print a tree of the added, removed and modified files at the end of the output.
+    ///
+    /// This is most useful when comparing directories with `diff -r`: the files found in only one
+    /// of the directories are included, as well as binary files which differ. The files are labeled
+    /// with --file-added-label, --file-removed-label and --file-modified-label.
+    pub tree_summary: bool,
+
     #[arg(
         long = "true-color",
         default_value = "auto",
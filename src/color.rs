@@ -141,6 +141,24 @@ pub fn get_plus_emph_background_color_default(is_light_mode: bool, is_true_color
     }
 }
 
+pub fn get_minus_moved_background_color_default(is_light_mode: bool, is_true_color: bool) -> Color {
+    match (is_light_mode, is_true_color) {
+        (true, true) => LIGHT_THEME_MINUS_MOVED_COLOR,
+        (true, false) => LIGHT_THEME_MINUS_MOVED_COLOR_256,
+        (false, true) => DARK_THEME_MINUS_MOVED_COLOR,
+        (false, false) => DARK_THEME_MINUS_MOVED_COLOR_256,
+    }
+}
+
+pub fn get_plus_moved_background_color_default(is_light_mode: bool, is_true_color: bool) -> Color {
+    match (is_light_mode, is_true_color) {
+        (true, true) => LIGHT_THEME_PLUS_MOVED_COLOR,
+        (true, false) => LIGHT_THEME_PLUS_MOVED_COLOR_256,
+        (false, true) => DARK_THEME_PLUS_MOVED_COLOR,
+        (false, false) => DARK_THEME_PLUS_MOVED_COLOR_256,
+    }
+}
+
 const LIGHT_THEME_MINUS_COLOR: Color = Color::RGB(0xff, 0xe0, 0xe0);
 
 const LIGHT_THEME_MINUS_COLOR_256: Color = Color::Fixed(224);
@@ -157,6 +175,14 @@ const LIGHT_THEME_PLUS_EMPH_COLOR: Color = Color::RGB(0xa0, 0xef, 0xa0);
 
 const LIGHT_THEME_PLUS_EMPH_COLOR_256: Color = Color::Fixed(157);
 
+const LIGHT_THEME_MINUS_MOVED_COLOR: Color = Color::RGB(0xf0, 0xe0, 0xff);
+
+const LIGHT_THEME_MINUS_MOVED_COLOR_256: Color = Color::Fixed(225);
+
+const LIGHT_THEME_PLUS_MOVED_COLOR: Color = Color::RGB(0xd0, 0xf0, 0xff);
+
+const LIGHT_THEME_PLUS_MOVED_COLOR_256: Color = Color::Fixed(195);
+
 const DARK_THEME_MINUS_COLOR: Color = Color::RGB(0x3f, 0x00, 0x01);
 
 const DARK_THEME_MINUS_COLOR_256: Color = Color::Fixed(52);
@@ -173,6 +199,14 @@ const DARK_THEME_PLUS_EMPH_COLOR: Color = Color::RGB(0x00, 0x60, 0x00);
 
 const DARK_THEME_PLUS_EMPH_COLOR_256: Color = Color::Fixed(28);
 
+const DARK_THEME_MINUS_MOVED_COLOR: Color = Color::RGB(0x2f, 0x00, 0x3f);
+
+const DARK_THEME_MINUS_MOVED_COLOR_256: Color = Color::Fixed(53);
+
+const DARK_THEME_PLUS_MOVED_COLOR: Color = Color::RGB(0x00, 0x28, 0x3f);
+
+const DARK_THEME_PLUS_MOVED_COLOR_256: Color = Color::Fixed(23);
+
 // blame
 
 pub const LIGHT_THEME_BLAME_PALETTE: &[&str] = &["#FFFFFF", "#DDDDDD", "#BBBBBB"];
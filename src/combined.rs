@@ -0,0 +1,394 @@
+// Combined diffs, as output by `git show --cc` and `git diff --cc` for a merge commit, have one
+// column of markers per parent of the merge, e.g. for an octopus merge of three parents:
+// ```
+// @@@@ -1,3 -1,3 -1,4 +1,4 @@@@
+//    fn main() {
+// -      let x = 10;
+//  --    let x = 1;
+// +++    let x = 11;
+// ++     let z = 33;
+//    }
+// ```
+// In the column of a parent, '-' marks a line of the parent which is not in the result, and '+' a
+// line of the result which is not in the parent. So the columns of a run of changed lines are the
+// unified diffs from each parent to the result. Edits are inferred separately within each of these
+// diffs, and each line is emphasized relative to the first parent in whose column it is marked.
+// With --combined-diff-columns, the diffs are shown next to each other, one column per parent.
+
+use syntect::easy::HighlightLines;
+use syntect::highlighting::Style as SyntectStyle;
+
+use crate::ansi;
+use crate::cli;
+use crate::config::Config;
+use crate::delta::{DiffType, InMergeConflict, MergeParents, State};
+use crate::edits;
+use crate::features::line_numbers::LineNumbersData;
+use crate::features::side_by_side::{Left, Right};
+use crate::minusplus::*;
+use crate::paint::{self, BgFillMethod, BgShouldFill, LineSections, Painter};
+use crate::style::Style;
+
+/// The edits inferred between the lines of a parent and the lines of the result.
+struct ParentEdits<'a> {
+    // The indices, among the buffered lines, of the lines marked in the column of the parent.
+    indices: MinusPlus<Vec<usize>>,
+    diff_style_sections: MinusPlus<Vec<LineSections<'a, Style>>>,
+    line_alignment: Vec<(Option<usize>, Option<usize>)>,
+}
+
+/// Return the number of parents of the merge, if these are the lines of a combined diff of a merge
+/// with at least two parents, outside of a merge conflict.
+pub fn get_n_parents(lines: &MinusPlus<&Vec<(String, State)>>) -> Option<usize> {
+    let mut prefix_lengths = lines[Minus]
+        .iter()
+        .chain(lines[Plus].iter())
+        .map(|(_, state)| get_prefix(state).map(str::len));
+    let n_parents = prefix_lengths.next()??;
+    if n_parents > 1 && prefix_lengths.all(|length| length == Some(n_parents)) {
+        Some(n_parents)
+    } else {
+        None
+    }
+}
+
+fn get_prefix(state: &State) -> Option<&str> {
+    match state {
+        State::HunkMinus(
+            DiffType::Combined(MergeParents::Prefix(prefix), InMergeConflict::No),
+            _,
+        )
+        | State::HunkPlus(
+            DiffType::Combined(MergeParents::Prefix(prefix), InMergeConflict::No),
+            _,
+        ) => Some(prefix),
+        _ => None,
+    }
+}
+
+/// Is the line marked with `marker` in the column of `parent`?
+fn is_marked(state: &State, parent: usize, marker: u8) -> bool {
+    get_prefix(state).and_then(|prefix| prefix.as_bytes().get(parent).copied()) == Some(marker)
+}
+
+/// The parent relative to which a line is emphasized: the first in whose column it is marked.
+fn get_primary_parent(state: &State, marker: u8, n_parents: usize) -> Option<usize> {
+    (0..n_parents).find(|parent| is_marked(state, *parent, marker))
+}
+
+fn infer_edits_of_parent<'a>(
+    lines: &MinusPlus<&'a Vec<(String, State)>>,
+    parent: usize,
+    config: &Config,
+) -> ParentEdits<'a> {
+    let indices = MinusPlus::new(
+        (0..lines[Minus].len())
+            .filter(|i| is_marked(&lines[Minus][*i].1, parent, b'-'))
+            .collect::<Vec<_>>(),
+        (0..lines[Plus].len())
+            .filter(|i| is_marked(&lines[Plus][*i].1, parent, b'+'))
+            .collect::<Vec<_>>(),
+    );
+    let get_lines = |lines: &'a [(String, State)], indices: &[usize]| -> Vec<&'a str> {
+        indices.iter().map(|i| lines[*i].0.as_str()).collect()
+    };
+    let get_styles = |lines: &[(String, State)], indices: &[usize]| -> Vec<Style> {
+        indices
+            .iter()
+            .map(|i| *config.get_style(&lines[*i].1))
+            .collect()
+    };
+    let (minus_diff_style_sections, plus_diff_style_sections, line_alignment) = edits::infer_edits(
+        get_lines(lines[Minus], &indices[Minus]),
+        get_lines(lines[Plus], &indices[Plus]),
+        get_styles(lines[Minus], &indices[Minus]),
+        config.minus_emph_style,
+        get_styles(lines[Plus], &indices[Plus]),
+        config.plus_emph_style,
+        &config.tokenization_regex,
+        config.max_line_distance,
+        config.max_line_distance_for_naively_paired_lines,
+        config.side_by_side,
+    );
+    ParentEdits {
+        indices,
+        diff_style_sections: MinusPlus::new(minus_diff_style_sections, plus_diff_style_sections),
+        line_alignment,
+    }
+}
+
+/// Infer the edits of the lines of a combined diff relative to each parent, and return, like
+/// `edits::infer_edits`, the style sections of the lines and their alignment. Return None if these
+/// are not the lines of a combined diff.
+#[allow(clippy::type_complexity)]
+pub fn infer_edits_relative_to_each_parent<'a>(
+    lines: &MinusPlus<&'a Vec<(String, State)>>,
+    config: &Config,
+) -> Option<(
+    Vec<LineSections<'a, Style>>,
+    Vec<LineSections<'a, Style>>,
+    Vec<(Option<usize>, Option<usize>)>,
+)> {
+    let n_parents = get_n_parents(lines)?;
+    let mut diff_style_sections: MinusPlus<Vec<Option<LineSections<'a, Style>>>> = MinusPlus::new(
+        vec![None; lines[Minus].len()],
+        vec![None; lines[Plus].len()],
+    );
+    let mut homologous_pairs = Vec::new();
+    for parent in 0..n_parents {
+        let mut parent_edits = infer_edits_of_parent(lines, parent, config);
+        let is_primary = |i: usize, minus_or_plus: MinusPlusIndex| {
+            let marker = if minus_or_plus == Minus { b'-' } else { b'+' };
+            get_primary_parent(&lines[minus_or_plus][i].1, marker, n_parents) == Some(parent)
+        };
+        for minus_or_plus in [Minus, Plus] {
+            let sections = std::mem::take(&mut parent_edits.diff_style_sections[minus_or_plus]);
+            for (i, line_sections) in parent_edits.indices[minus_or_plus].iter().zip(sections) {
+                if is_primary(*i, minus_or_plus) {
+                    diff_style_sections[minus_or_plus][*i] = Some(line_sections);
+                }
+            }
+        }
+        for alignment in parent_edits.line_alignment {
+            if let (Some(m), Some(p)) = alignment {
+                let (m, p) = (
+                    parent_edits.indices[Minus][m],
+                    parent_edits.indices[Plus][p],
+                );
+                if is_primary(m, Minus) && is_primary(p, Plus) {
+                    homologous_pairs.push((m, p));
+                }
+            }
+        }
+    }
+    let mut get_diff_style_sections = |minus_or_plus: MinusPlusIndex| -> Vec<_> {
+        std::mem::take(&mut diff_style_sections[minus_or_plus])
+            .into_iter()
+            .zip(lines[minus_or_plus].iter())
+            .map(|(line_sections, (line, state))| {
+                line_sections.unwrap_or_else(|| vec![(*config.get_style(state), line.as_str())])
+            })
+            .collect()
+    };
+    Some((
+        get_diff_style_sections(Minus),
+        get_diff_style_sections(Plus),
+        make_line_alignment(homologous_pairs, lines[Minus].len(), lines[Plus].len()),
+    ))
+}
+
+/// Return the alignment of `n_minus` minus lines and `n_plus` plus lines in which the given pairs
+/// are aligned, as far as they do not cross each other.
+fn make_line_alignment(
+    mut homologous_pairs: Vec<(usize, usize)>,
+    n_minus: usize,
+    n_plus: usize,
+) -> Vec<(Option<usize>, Option<usize>)> {
+    homologous_pairs.sort_unstable();
+    let mut line_alignment = Vec::new();
+    let (mut minus_index, mut plus_index) = (0, 0);
+    for (m, p) in homologous_pairs {
+        if m < minus_index || p < plus_index {
+            continue;
+        }
+        line_alignment.extend((minus_index..m).map(|i| (Some(i), None)));
+        line_alignment.extend((plus_index..p).map(|i| (None, Some(i))));
+        line_alignment.push((Some(m), Some(p)));
+        minus_index = m + 1;
+        plus_index = p + 1;
+    }
+    line_alignment.extend((minus_index..n_minus).map(|i| (Some(i), None)));
+    line_alignment.extend((plus_index..n_plus).map(|i| (None, Some(i))));
+    line_alignment
+}
+
+/// Paint the lines of a combined diff as one column per parent, holding the diff from the parent
+/// to the result: the lines removed from the parent, followed by the lines of the result, of which
+/// those absent from the parent are added lines, and the others are unchanged lines.
+pub fn paint_minus_and_plus_lines_in_parent_columns(
+    lines: MinusPlus<&Vec<(String, State)>>,
+    line_numbers_data: &mut Option<LineNumbersData>,
+    highlighter: &mut Option<HighlightLines>,
+    output_buffer: &mut String,
+    config: &Config,
+) {
+    let n_parents = match get_n_parents(&lines) {
+        Some(n_parents) => n_parents,
+        None => return,
+    };
+    let syntax_style_sections = MinusPlus::new(
+        paint::get_syntax_style_sections_for_lines(lines[Minus], highlighter.as_mut(), config),
+        paint::get_syntax_style_sections_for_lines(lines[Plus], highlighter.as_mut(), config),
+    );
+    let width = match config.decorations_width {
+        cli::Width::Fixed(width) => width,
+        cli::Width::Variable => config.available_terminal_width,
+    };
+    let column_width = width.saturating_sub(n_parents - 1) / n_parents;
+    let columns: Vec<Vec<String>> = (0..n_parents)
+        .map(|parent| {
+            let parent_edits = infer_edits_of_parent(&lines, parent, config);
+            let mut column = Vec::new();
+            for (i, diff_sections) in parent_edits.indices[Minus]
+                .iter()
+                .zip(&parent_edits.diff_style_sections[Minus])
+            {
+                column.push(paint_cell(
+                    &syntax_style_sections[Minus][*i],
+                    diff_sections,
+                    State::HunkMinus(DiffType::Unified, None),
+                    column_width,
+                    parent + 1 < n_parents,
+                    config,
+                ));
+            }
+            let mut plus_diff_style_sections = parent_edits.diff_style_sections[Plus].iter();
+            for (i, (line, state)) in lines[Plus].iter().enumerate() {
+                let (diff_sections, state) = if is_marked(state, parent, b'+') {
+                    (
+                        plus_diff_style_sections.next().unwrap().clone(),
+                        State::HunkPlus(DiffType::Unified, None),
+                    )
+                } else {
+                    (
+                        vec![(config.zero_style, line.as_str())],
+                        State::HunkZero(DiffType::Unified, None),
+                    )
+                };
+                column.push(paint_cell(
+                    &syntax_style_sections[Plus][i],
+                    &diff_sections,
+                    state,
+                    column_width,
+                    parent + 1 < n_parents,
+                    config,
+                ));
+            }
+            column
+        })
+        .collect();
+    let n_rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+    let empty_cell = " ".repeat(column_width);
+    for row in 0..n_rows {
+        let cells: Vec<&str> = columns
+            .iter()
+            .map(|column| column.get(row).map_or(empty_cell.as_str(), String::as_str))
+            .collect();
+        output_buffer.push_str(cells.join("│").trim_end_matches(' '));
+        output_buffer.push('\n');
+    }
+    // The line numbers are not shown, but the following lines are numbered as usual.
+    if let Some(line_numbers_data) = line_numbers_data {
+        line_numbers_data.line_number[Left] += lines[Minus].len();
+        line_numbers_data.line_number[Right] += lines[Plus].len();
+    }
+}
+
+/// Paint a line in a column of the given width, truncating it, and padding it unless it is in the
+/// last column.
+fn paint_cell(
+    syntax_sections: &[(SyntectStyle, &str)],
+    diff_sections: &[(Style, &str)],
+    state: State,
+    width: usize,
+    pad: bool,
+    config: &Config,
+) -> String {
+    let (line, _) = Painter::paint_line(
+        syntax_sections,
+        diff_sections,
+        &state,
+        &mut None,
+        None,
+        paint::painted_prefix(state.clone(), config),
+        config,
+    );
+    let mut cell = ansi::truncate_str(&line, width, &config.truncation_symbol).to_string();
+    if !pad {
+        return cell;
+    }
+    let (bg_fill_mode, fill_style) = Painter::get_should_right_fill_background_color_and_fill_style(
+        diff_sections,
+        None,
+        &state,
+        BgShouldFill::With(BgFillMethod::Spaces),
+        config,
+    );
+    let padding = " ".repeat(width.saturating_sub(ansi::measure_text_width(&cell)));
+    match bg_fill_mode {
+        Some(_) => cell.push_str(&fill_style.paint(padding).to_string()),
+        None => cell.push_str(&padding),
+    }
+    cell
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_make_line_alignment() {
+        assert_eq!(
+            make_line_alignment(vec![(1, 0), (0, 1), (2, 2)], 3, 4),
+            vec![
+                (None, Some(0)),
+                (Some(0), Some(1)),
+                (Some(1), None),
+                (Some(2), Some(2)),
+                (None, Some(3))
+            ]
+        );
+        assert_eq!(
+            make_line_alignment(vec![], 1, 1),
+            vec![(Some(0), None), (None, Some(0))]
+        );
+    }
+
+    #[test]
+    fn test_octopus_merge_edits_relative_to_each_parent() {
+        DeltaTest::with_args(&["--minus-emph-style", "red", "--plus-emph-style", "green"])
+            .explain_ansi()
+            .with_input(GIT_SHOW_OCTOPUS_MERGE)
+            .expect_contains("(normal 52)-      let x = (red)10(normal 52);(normal)")
+            .expect_contains("(normal 52) --    let x = (red)1(normal 52);(normal)")
+            .expect_contains("(normal 22)+++(231)    (81)let(231) x (203)=(231) (green)11");
+    }
+
+    #[test]
+    fn test_combined_diff_columns() {
+        DeltaTest::with_args(&["--combined-diff-columns", "--width", "62"])
+            .with_input(GIT_SHOW_OCTOPUS_MERGE)
+            .expect_after_skip(
+                14,
+                "
+#indent_mark
+   fn main() {
+    let x = 10;     │    let x = 1;      │    let x = 1;
+    let x = 11;     │    let x = 11;     │    let x = 11;
+    let z = 33;     │    let z = 33;     │    let z = 33;
+   }",
+            );
+    }
+
+    const GIT_SHOW_OCTOPUS_MERGE: &str = "\
+commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+Merge: 94907c0 8b13789 e69de29
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Sat Dec 11 17:08:56 2021 -0500
+
+    Octopus
+
+diff --cc src/main.rs
+index 6178079,7898192,27d47c0..0000000
+--- a/src/main.rs
++++ b/src/main.rs
+@@@@ -1,3 -1,3 -1,4 +1,4 @@@@
+   fn main() {
+-      let x = 10;
+ --    let x = 1;
++++    let x = 11;
+++     let z = 33;
+   }
+";
+}
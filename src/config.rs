@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr;
 
 use clap::parser::ValueSource;
 use regex::Regex;
@@ -18,6 +20,7 @@ use crate::git_config::GitConfig;
 use crate::handlers;
 use crate::handlers::blame::parse_blame_line_numbers;
 use crate::handlers::blame::BlameLineNumbers;
+use crate::handlers::diff_header::GitDiffPrefixes;
 use crate::minusplus::MinusPlus;
 use crate::paint::BgFillMethod;
 use crate::parse_styles;
@@ -31,25 +34,39 @@ use crate::wrapping::WrapConfig;
 
 pub const INLINE_SYMBOL_WIDTH_1: usize = 1;
 
-#[cfg_attr(test, derive(Clone))]
+#[derive(Clone)]
 pub struct Config {
     pub available_terminal_width: usize,
     pub background_color_extends_to_terminal_width: bool,
+    pub binary_hexdump: bool,
     pub blame_code_style: Option<Style>,
     pub blame_format: String,
+    pub blame_moved_style: Option<Style>,
     pub blame_separator_format: BlameLineNumbers,
     pub blame_palette: Vec<String>,
     pub blame_separator_style: Option<Style>,
     pub blame_timestamp_format: String,
     pub blame_timestamp_output_format: Option<String>,
     pub color_only: bool,
+    pub combined_diff_columns: bool,
+    pub commit_author_style: Style,
+    pub commit_branch_style: Style,
+    pub commit_date_format: Option<String>,
+    pub commit_date_style: Style,
     pub commit_regex: Regex,
+    pub commit_remote_style: Style,
+    pub commit_signature_bad_style: Style,
+    pub commit_signature_good_style: Style,
+    pub commit_signature_unknown_style: Style,
     pub commit_style: Style,
+    pub commit_tag_style: Style,
+    pub commit_trailer_style: Style,
     pub cwd_of_delta_process: Option<PathBuf>,
     pub cwd_of_user_shell_process: Option<PathBuf>,
     pub cwd_relative_to_repo_root: Option<String>,
     pub decorations_width: cli::Width,
     pub default_language: Option<String>,
+    pub detect_moved: bool,
     pub diff_stat_align_width: usize,
     pub error_exit_code: i32,
     pub file_added_label: String,
@@ -60,9 +77,12 @@ pub struct Config {
     pub file_regex_replacement: Option<RegexReplacement>,
     pub right_arrow: String,
     pub file_style: Style,
-    pub git_config: Option<GitConfig>,
+    // Shared, so that a Config can be cloned (e.g. to adapt its width).
+    pub git_config: Option<Rc<GitConfig>>,
+    pub git_diff_prefixes: GitDiffPrefixes,
     pub git_minus_style: Style,
     pub git_plus_style: Style,
+    pub git_remote_names: Vec<String>,
     pub grep_context_line_style: Style,
     pub grep_file_style: Style,
     pub grep_line_number_style: Style,
@@ -78,9 +98,11 @@ pub struct Config {
     pub hunk_label: String,
     pub hyperlinks_commit_link_format: Option<String>,
     pub hyperlinks_file_link_format: String,
+    pub hyperlinks_signature_link_format: Option<String>,
     pub hyperlinks: bool,
     pub inline_hint_style: Style,
     pub inspect_raw_lines: cli::InspectRawLines,
+    pub interactive_filter: bool,
     pub keep_plus_minus_markers: bool,
     pub line_buffer_size: usize,
     pub line_fill_method: BgFillMethod,
@@ -100,10 +122,13 @@ pub struct Config {
     pub minus_emph_style: Style,
     pub minus_empty_line_marker_style: Style,
     pub minus_file: Option<PathBuf>,
+    pub minus_moved_style: Style,
     pub minus_non_emph_style: Style,
     pub minus_style: Style,
+    pub moved_min_lines: usize,
     pub navigate_regex: Option<String>,
     pub navigate: bool,
+    pub notes_style: Style,
     pub null_style: Style,
     pub null_syntect_style: SyntectStyle,
     pub pager: Option<String>,
@@ -111,17 +136,21 @@ pub struct Config {
     pub plus_emph_style: Style,
     pub plus_empty_line_marker_style: Style,
     pub plus_file: Option<PathBuf>,
+    pub plus_moved_style: Style,
     pub plus_non_emph_style: Style,
     pub plus_style: Style,
     pub relative_paths: bool,
     pub show_themes: bool,
     pub side_by_side_data: side_by_side::SideBySideData,
     pub side_by_side: bool,
+    pub side_by_side_min_width: usize,
+    pub side_by_side_split: side_by_side::PanelSplit,
     pub syntax_dummy_theme: SyntaxTheme,
     pub syntax_set: SyntaxSet,
     pub syntax_theme: Option<SyntaxTheme>,
     pub tab_width: usize,
     pub tokenization_regex: Regex,
+    pub tree_summary: bool,
     pub true_color: bool,
     pub truncation_symbol: String,
     pub whitespace_error_style: Style,
@@ -139,12 +168,13 @@ impl Config {
             State::DiffHeader(_) => &self.file_style,
             State::HunkHeader(_, _, _, _) => &self.hunk_header_style,
             State::SubmoduleLog => &self.file_style,
+            State::RangeDiff => &self.commit_style,
             _ => delta_unreachable("Unreachable code reached in get_style."),
         }
     }
 
     pub fn git_config(&self) -> Option<&GitConfig> {
-        self.git_config.as_ref()
+        self.git_config.as_deref()
     }
 }
 
@@ -180,6 +210,16 @@ impl From<cli::Opt> for Config {
             ));
         });
 
+        let git_remote_names = opt
+            .git_config()
+            .map(GitConfig::get_remote_names)
+            .unwrap_or_default();
+
+        let git_diff_prefixes = opt
+            .git_config()
+            .map(GitDiffPrefixes::from_git_config)
+            .unwrap_or(GitDiffPrefixes::Default);
+
         let blame_palette = make_blame_palette(opt.blame_palette, opt.computed.is_light_mode);
 
         let file_added_label = opt.file_added_label;
@@ -197,9 +237,12 @@ impl From<cli::Opt> for Config {
             _ => fatal("Invalid option for line-fill-method: Expected \"ansi\" or \"spaces\"."),
         };
 
+        let side_by_side_split = side_by_side::PanelSplit::from_str(&opt.side_by_side_split)
+            .unwrap_or_else(|err| fatal(format!("Invalid value for side-by-side-split: {err}")));
         let side_by_side_data = side_by_side::SideBySideData::new_sbs(
             &opt.computed.decorations_width,
             &opt.computed.available_terminal_width,
+            &side_by_side_split,
         );
         let side_by_side_data = ansifill::UseFullPanelWidth::sbs_odd_fix(
             &opt.computed.decorations_width,
@@ -239,8 +282,10 @@ impl From<cli::Opt> for Config {
             background_color_extends_to_terminal_width: opt
                 .computed
                 .background_color_extends_to_terminal_width,
+            binary_hexdump: opt.binary_hexdump,
             blame_format: opt.blame_format,
             blame_code_style: styles.remove("blame-code-style"),
+            blame_moved_style: styles.remove("blame-moved-style"),
             blame_palette,
             blame_separator_format: parse_blame_line_numbers(&opt.blame_separator_format),
             blame_separator_style: styles.remove("blame-separator-style"),
@@ -248,12 +293,24 @@ impl From<cli::Opt> for Config {
             blame_timestamp_output_format: opt.blame_timestamp_output_format,
             commit_style: styles["commit-style"],
             color_only: opt.color_only,
+            combined_diff_columns: opt.combined_diff_columns,
+            commit_author_style: styles["commit-author-style"],
+            commit_branch_style: styles["commit-branch-style"],
+            commit_date_format: opt.commit_date_format,
+            commit_date_style: styles["commit-date-style"],
             commit_regex,
+            commit_remote_style: styles["commit-remote-style"],
+            commit_signature_bad_style: styles["commit-signature-bad-style"],
+            commit_signature_good_style: styles["commit-signature-good-style"],
+            commit_signature_unknown_style: styles["commit-signature-unknown-style"],
+            commit_tag_style: styles["commit-tag-style"],
+            commit_trailer_style: styles["commit-trailer-style"],
             cwd_of_delta_process,
             cwd_of_user_shell_process,
             cwd_relative_to_repo_root,
             decorations_width: opt.computed.decorations_width,
             default_language: opt.default_language,
+            detect_moved: opt.detect_moved,
             diff_stat_align_width: opt.diff_stat_align_width,
             error_exit_code: 2, // Use 2 for error because diff uses 0 and 1 for non-error.
             file_added_label,
@@ -268,7 +325,8 @@ impl From<cli::Opt> for Config {
             right_arrow,
             hunk_label,
             file_style: styles["file-style"],
-            git_config: opt.git_config,
+            git_config: opt.git_config.map(Rc::new),
+            git_diff_prefixes,
             grep_context_line_style: styles["grep-context-line-style"],
             grep_file_style: styles["grep-file-style"],
             grep_line_number_style: styles["grep-line-number-style"],
@@ -290,7 +348,9 @@ impl From<cli::Opt> for Config {
             hyperlinks: opt.hyperlinks,
             hyperlinks_commit_link_format: opt.hyperlinks_commit_link_format,
             hyperlinks_file_link_format: opt.hyperlinks_file_link_format,
+            hyperlinks_signature_link_format: opt.hyperlinks_signature_link_format,
             inspect_raw_lines: opt.computed.inspect_raw_lines,
+            interactive_filter: opt.interactive_filter,
             inline_hint_style: styles["inline-hint-style"],
             keep_plus_minus_markers: opt.keep_plus_minus_markers,
             line_fill_method: if !opt.computed.stdout_is_term && !TESTING {
@@ -301,7 +361,7 @@ impl From<cli::Opt> for Config {
             } else {
                 line_fill_method
             },
-            line_numbers: opt.line_numbers && !handlers::hunk::is_word_diff(),
+            line_numbers: opt.line_numbers && !handlers::word_diff::is_color_words(),
             line_numbers_format: LeftRight::new(
                 opt.line_numbers_left_format,
                 opt.line_numbers_right_format,
@@ -334,10 +394,13 @@ impl From<cli::Opt> for Config {
             minus_emph_style: styles["minus-emph-style"],
             minus_empty_line_marker_style: styles["minus-empty-line-marker-style"],
             minus_file: opt.minus_file,
+            minus_moved_style: styles["minus-moved-style"],
             minus_non_emph_style: styles["minus-non-emph-style"],
             minus_style: styles["minus-style"],
+            moved_min_lines: opt.moved_min_lines,
             navigate: opt.navigate,
             navigate_regex,
+            notes_style: styles["notes-style"],
             null_style: Style::new(),
             null_syntect_style: SyntectStyle::default(),
             pager: opt.pager,
@@ -345,20 +408,25 @@ impl From<cli::Opt> for Config {
             plus_emph_style: styles["plus-emph-style"],
             plus_empty_line_marker_style: styles["plus-empty-line-marker-style"],
             plus_file: opt.plus_file,
+            plus_moved_style: styles["plus-moved-style"],
             plus_non_emph_style: styles["plus-non-emph-style"],
             plus_style: styles["plus-style"],
             git_minus_style: styles["git-minus-style"],
             git_plus_style: styles["git-plus-style"],
+            git_remote_names,
             relative_paths: opt.relative_paths,
             show_themes: opt.show_themes,
-            side_by_side: opt.side_by_side && !handlers::hunk::is_word_diff(),
+            side_by_side: opt.side_by_side && !handlers::word_diff::is_color_words(),
             side_by_side_data,
+            side_by_side_min_width: opt.side_by_side_min_width,
+            side_by_side_split,
             styles_map,
             syntax_dummy_theme: SyntaxTheme::default(),
             syntax_set: opt.computed.syntax_set,
             syntax_theme: opt.computed.syntax_theme,
             tab_width: opt.tab_width,
             tokenization_regex,
+            tree_summary: opt.tree_summary,
             true_color: opt.computed.true_color,
             truncation_symbol: format!("{}→{}", ansi::ANSI_SGR_REVERSE, ansi::ANSI_SGR_RESET),
             wrap_config,
@@ -9,7 +9,16 @@ use crate::ansi;
 use crate::config::delta_unreachable;
 use crate::config::Config;
 use crate::features;
+use crate::handlers::adaptive_panels::AdaptivePanelsHunk;
+use crate::handlers::binary::BinaryPatch;
+use crate::handlers::blame::BlamePorcelain;
+use crate::handlers::context_diff::ContextDiff;
+use crate::handlers::directory_diff::DirectoryDiff;
+use crate::handlers::email_patch::EmailPatch;
 use crate::handlers::hunk_header::ParsedHunkHeader;
+use crate::handlers::lfs::LfsPointerDiff;
+use crate::handlers::signature::Signature;
+use crate::handlers::word_diff::WordDiffFormat;
 use crate::handlers::{self, merge_conflict};
 use crate::paint::Painter;
 use crate::style::DecorationStyle;
@@ -29,6 +38,8 @@ pub enum State {
     Blame(String), // In a line of `git blame` output (key).
     GitShowFile,  // In a line of `git show $revision:./path/to/file.ext` output
     Grep,         // In a line of `git grep` output
+    RangeDiff,    // In a pair line of `git range-diff` output, or the diff of diffs following it
+    WordDiff(WordDiffFormat), // In a hunk of `--word-diff=plain` or `--word-diff=porcelain` output
     Unknown,
     // The following elements are created when a line is wrapped to display it:
     HunkZeroWrapped,  // Wrapped unchanged line
@@ -72,7 +83,11 @@ impl DiffType {
 #[derive(Debug, PartialEq, Eq)]
 pub enum Source {
     GitDiff,     // Coming from a `git diff` command
-    DiffUnified, // Coming from a `diff -u` command
+    DiffUnified, // Coming from a `diff -u` command (or `diff -c`, or `diff`)
+    Mercurial,   // Coming from a `hg diff` command
+    Subversion,  // Coming from a `svn diff` command
+    Perforce,    // Coming from a `p4 diff -du` command
+    GitReject,   // Coming from a `.rej` file written by `git apply --reject`
     Unknown,
 }
 
@@ -110,13 +125,63 @@ pub struct StateMachine<'a> {
     pub current_file_pair: Option<(String, String)>,
     pub handled_diff_header_header_line_file_pair: Option<(String, String)>,
     pub blame_key_colors: HashMap<String, String>,
+    // Once word diff output has been detected, all subsequent hunks are handled as word diff.
+    pub word_diff_format: Option<WordDiffFormat>,
+    // The lines of the first hunk are held back until the format of the input is decided: the
+    // first line of `--word-diff=porcelain` output is only recognized at its terminating '~'.
+    pub word_diff_lookahead_lines: Option<Vec<(String, String)>>,
+    pub is_word_diff_format_decided: bool,
+    // `diff -c` hunks are buffered until they can be converted to unified diff hunks.
+    pub context_diff: Option<ContextDiff>,
+    pub in_normal_diff_hunk: bool,
+    pub email_patch: Option<EmailPatch>,
+    pub blame_porcelain: BlamePorcelain,
+    pub blame_filename: Option<String>,
+    // The blob ids of the "index" line of the diff header, and the binary patch being handled.
+    pub blob_ids: Option<(String, String)>,
+    pub binary_patch: Option<BinaryPatch>,
+    // The lines verifying the signature of a commit are buffered until they can be summarized.
+    pub signature: Option<Signature>,
+    pub in_notes: bool,
+    // The directories compared by `diff -r`, and the files changed, for the tree summary.
+    pub directory_diff: DirectoryDiff,
+    // Whether the commits of the current submodule have been listed from its repository.
+    pub in_resolved_submodule_log: bool,
+    // The "+++" and hunk header lines held back until it is known whether the file is tracked by
+    // Git LFS, and the pointer files of such a file.
+    pub lfs_lookahead_lines: Vec<(String, String)>,
+    pub lfs_pointer_diff: Option<LfsPointerDiff>,
+    // With `side-by-side-split = adaptive`, the lines of the current hunk are held back until it
+    // ends, to size the panels to them.
+    pub adaptive_panels_hunk: Option<AdaptivePanelsHunk>,
 }
 
-pub fn delta<I>(lines: ByteLines<I>, writer: &mut dyn Write, config: &Config) -> std::io::Result<()>
+pub fn delta<I>(
+    mut lines: ByteLines<I>,
+    writer: &mut dyn Write,
+    config: &Config,
+) -> std::io::Result<()>
 where
     I: BufRead,
 {
-    StateMachine::new(writer, config).consume(lines)
+    let first_line = match lines.next() {
+        Some(Ok(raw_line_bytes)) => raw_line_bytes.to_vec(),
+        _ => return Ok(()),
+    };
+    if config.interactive_filter {
+        return crate::interactive_filter::delta_as_interactive_filter(
+            first_line, lines, writer, config,
+        );
+    }
+    if !config.color_only && handlers::graph::is_graph_commit_line(&first_line) {
+        return handlers::graph::delta_with_graph(first_line, lines, writer, config);
+    }
+    if config.detect_moved && !config.color_only {
+        return crate::moved::delta_with_moved_line_detection(first_line, lines, writer, config);
+    }
+    let mut state_machine = StateMachine::new(writer, config);
+    state_machine.consume_line(&first_line)?;
+    state_machine.consume(lines)
 }
 
 impl<'a> StateMachine<'a> {
@@ -137,6 +202,23 @@ impl<'a> StateMachine<'a> {
             painter: Painter::new(writer, config),
             config,
             blame_key_colors: HashMap::new(),
+            word_diff_format: None,
+            word_diff_lookahead_lines: None,
+            is_word_diff_format_decided: false,
+            context_diff: None,
+            in_normal_diff_hunk: false,
+            email_patch: None,
+            blame_porcelain: BlamePorcelain::default(),
+            blame_filename: None,
+            blob_ids: None,
+            binary_patch: None,
+            signature: None,
+            in_notes: false,
+            directory_diff: DirectoryDiff::default(),
+            in_resolved_submodule_log: false,
+            lfs_lookahead_lines: Vec::new(),
+            lfs_pointer_diff: None,
+            adaptive_panels_hunk: None,
         }
     }
 
@@ -145,39 +227,72 @@ impl<'a> StateMachine<'a> {
         I: BufRead,
     {
         while let Some(Ok(raw_line_bytes)) = lines.next() {
-            self.ingest_line(raw_line_bytes);
+            self.consume_line(raw_line_bytes)?;
+        }
+        self.finish()
+    }
 
-            if self.source == Source::Unknown {
-                self.source = detect_source(&self.line);
-            }
+    pub fn consume_line(&mut self, raw_line_bytes: &[u8]) -> std::io::Result<()> {
+        self.ingest_line(raw_line_bytes);
 
-            // Every method named handle_* must return std::io::Result<bool>.
-            // The bool indicates whether the line has been handled by that
-            // method (in which case no subsequent handlers are permitted to
-            // handle it).
-            let _ = self.handle_commit_meta_header_line()?
-                || self.handle_diff_stat_line()?
-                || self.handle_diff_header_diff_line()?
-                || self.handle_diff_header_file_operation_line()?
-                || self.handle_diff_header_minus_line()?
-                || self.handle_diff_header_plus_line()?
-                || self.handle_hunk_header_line()?
-                || self.handle_diff_header_mode_line()?
-                || self.handle_diff_header_misc_line()?
-                || self.handle_submodule_log_line()?
-                || self.handle_submodule_short_line()?
-                || self.handle_merge_conflict_line()?
-                || self.handle_hunk_line()?
-                || self.handle_git_show_file_line()?
-                || self.handle_blame_line()?
-                || self.handle_grep_line()?
-                || self.should_skip_line()
-                || self.emit_line_unchanged()?;
+        if self.source == Source::Unknown {
+            self.source = detect_source(&self.line);
         }
 
+        self.handle_line()
+    }
+
+    /// Handle the current line.
+    pub fn handle_line(&mut self) -> std::io::Result<()> {
+        // Every method named handle_* must return std::io::Result<bool>.
+        // The bool indicates whether the line has been handled by that
+        // method (in which case no subsequent handlers are permitted to
+        // handle it).
+        let _ = self.handle_adaptive_panels_line()?
+            || self.handle_word_diff_lookahead_line()?
+            || self.handle_commit_meta_header_line()?
+            || self.handle_email_patch_line()?
+            || self.handle_signature_line()?
+            || self.handle_notes_line()?
+            || self.handle_commit_meta_line()?
+            || self.handle_range_diff_line()?
+            || self.handle_context_diff_line()?
+            || self.handle_normal_diff_line()?
+            || self.handle_diff_stat_line()?
+            || self.handle_diff_header_diff_line()?
+            || self.handle_diff_header_file_operation_line()?
+            || self.handle_diff_header_minus_line()?
+            || self.handle_lfs_line()?
+            || self.handle_diff_header_plus_line()?
+            || self.handle_hunk_header_line()?
+            || self.handle_diff_header_mode_line()?
+            || self.handle_binary_line()?
+            || self.handle_diff_header_misc_line()?
+            || self.handle_submodule_log_line()?
+            || self.handle_submodule_short_line()?
+            || self.handle_merge_conflict_line()?
+            || self.handle_word_diff_line()?
+            || self.handle_hunk_line()?
+            || self.handle_git_show_file_line()?
+            || self.handle_blame_line()?
+            || self.handle_grep_line()?
+            || self.should_skip_line()
+            || self.emit_line_unchanged()?;
+        Ok(())
+    }
+
+    /// Paint and emit everything that is still buffered at the end of the input.
+    pub fn finish(&mut self) -> std::io::Result<()> {
+        self.flush_adaptive_panels_hunk()?;
+        self.flush_word_diff_lookahead_lines()?;
+        self.flush_signature()?;
+        self.flush_context_diff_hunk()?;
+        self.flush_binary_patch()?;
+        self.flush_lfs()?;
         self.handle_pending_line_with_diff_name()?;
         self.painter.paint_buffered_minus_and_plus_lines();
         self.painter.emit()?;
+        self.write_tree_summary()?;
         Ok(())
     }
 
@@ -269,22 +384,44 @@ pub fn format_raw_line<'a>(line: &'a str, config: &Config) -> Cow<'a, str> {
 ///
 /// Currently can detect:
 /// * git diff
-/// * diff -u
+/// * git format-patch
+/// * diff -u, diff -c and diff
+/// * hg diff
+/// * svn diff
+/// * p4 diff -du
 fn detect_source(line: &str) -> Source {
     if line.starts_with("commit ")
         || line.starts_with("diff --git ")
         || line.starts_with("diff --cc ")
         || line.starts_with("diff --combined ")
+        || handlers::email_patch::is_email_patch_from_line(line)
     {
         Source::GitDiff
+    } else if handlers::diff_header::is_git_reject_diff_line(line) {
+        Source::GitReject
+    } else if handlers::diff_header::is_mercurial_diff_line(line) {
+        Source::Mercurial
     } else if line.starts_with("diff -u")
         || line.starts_with("diff -ru")
-        || line.starts_with("diff -r -u")
+        || line.starts_with("diff -r ")
         || line.starts_with("diff -U")
+        || line.starts_with("diff -c")
+        || line.starts_with("diff -rc")
+        || line.starts_with("diff -C")
         || line.starts_with("--- ")
+        || line.starts_with("*** ")
+        // A `.rej` file written by patch(1) may start with a context diff hunk.
+        || line.starts_with("***************")
         || line.starts_with("Only in ")
+        || line.starts_with("Common subdirectories: ")
+        || line.starts_with("Binary files ")
+        || handlers::normal_diff::is_normal_diff_hunk_header(line)
     {
         Source::DiffUnified
+    } else if line.starts_with("Index: ") {
+        Source::Subversion
+    } else if handlers::diff_header::is_perforce_diff_line(line) {
+        Source::Perforce
     } else {
         Source::Unknown
     }
@@ -19,6 +19,11 @@ use crate::minusplus::MinusPlus;
 /// `noop_deletions[i]` is the appropriate deletion operation tag to be used for `minus_lines[i]`;
 /// `noop_deletions` is guaranteed to be the same length as `minus_lines`. The equivalent statements
 /// hold for `plus_insertions` and `plus_lines`.
+///
+/// By default each minus line is paired greedily with the first homologous plus line following
+/// the last pair. If `align_lines` is true, the pairs are instead those of the line-level alignment
+/// of the minus and plus lines with the greatest total similarity, so that a line inserted or
+/// deleted among edited lines does not shift the pairing of the lines after it.
 #[allow(clippy::too_many_arguments)]
 #[allow(clippy::type_complexity)]
 pub fn infer_edits<'a, EditOperation>(
@@ -31,6 +36,7 @@ pub fn infer_edits<'a, EditOperation>(
     tokenization_regex: &Regex,
     max_line_distance: f64,
     max_line_distance_for_naively_paired_lines: f64,
+    align_lines: bool,
 ) -> (
     Vec<Vec<(EditOperation, &'a str)>>,  // annotated minus lines
     Vec<Vec<(EditOperation, &'a str)>>,  // annotated plus lines
@@ -39,6 +45,20 @@ pub fn infer_edits<'a, EditOperation>(
 where
     EditOperation: Copy + PartialEq + std::fmt::Debug,
 {
+    if align_lines && minus_lines.len() * plus_lines.len() <= MAX_ALIGNED_LINE_PAIRS {
+        return infer_edits_of_aligned_lines(
+            minus_lines,
+            plus_lines,
+            noop_deletions,
+            deletion,
+            noop_insertions,
+            insertion,
+            tokenization_regex,
+            max_line_distance,
+            max_line_distance_for_naively_paired_lines,
+        );
+    }
+
     let mut annotated_minus_lines = Vec::<Vec<(EditOperation, &str)>>::new();
     let mut annotated_plus_lines = Vec::<Vec<(EditOperation, &str)>>::new();
     let mut line_alignment = Vec::<(Option<usize>, Option<usize>)>::new();
@@ -98,6 +118,133 @@ where
     (annotated_minus_lines, annotated_plus_lines, line_alignment)
 }
 
+// Every minus line is compared with every plus line when the lines are aligned, so larger blocks
+// of lines are paired greedily.
+const MAX_ALIGNED_LINE_PAIRS: usize = 1024;
+
+/// Infer the edits between the minus and plus lines as `infer_edits` does, but pairing the lines
+/// according to the order-preserving alignment of homologous lines that maximizes the sum of
+/// their similarities (1 - distance). Unpaired minus lines are placed before unpaired plus lines
+/// between consecutive pairs.
+#[allow(clippy::too_many_arguments)]
+#[allow(clippy::type_complexity)]
+fn infer_edits_of_aligned_lines<'a, EditOperation>(
+    minus_lines: Vec<&'a str>,
+    plus_lines: Vec<&'a str>,
+    noop_deletions: Vec<EditOperation>,
+    deletion: EditOperation,
+    noop_insertions: Vec<EditOperation>,
+    insertion: EditOperation,
+    tokenization_regex: &Regex,
+    max_line_distance: f64,
+    max_line_distance_for_naively_paired_lines: f64,
+) -> (
+    Vec<Vec<(EditOperation, &'a str)>>,
+    Vec<Vec<(EditOperation, &'a str)>>,
+    Vec<(Option<usize>, Option<usize>)>,
+)
+where
+    EditOperation: Copy + PartialEq + std::fmt::Debug,
+{
+    let (m, n) = (minus_lines.len(), plus_lines.len());
+    let plus_tokens: Vec<_> = plus_lines
+        .iter()
+        .map(|plus_line| tokenize(plus_line, tokenization_regex))
+        .collect();
+
+    // homologs[i][j] holds the annotated lines and their similarity if minus line i and plus
+    // line j are homologous.
+    let mut homologs = Vec::with_capacity(m);
+    for (minus_index, minus_line) in minus_lines.iter().enumerate() {
+        let minus_tokens = tokenize(minus_line, tokenization_regex);
+        let mut row = Vec::with_capacity(n);
+        for (plus_index, plus_line) in plus_lines.iter().enumerate() {
+            let alignment =
+                align::Alignment::new(minus_tokens.clone(), plus_tokens[plus_index].clone());
+            let (annotated_minus_line, annotated_plus_line, distance) = annotate(
+                alignment,
+                noop_deletions[minus_index],
+                deletion,
+                noop_insertions[plus_index],
+                insertion,
+                minus_line,
+                plus_line,
+            );
+            if m == n && distance <= max_line_distance_for_naively_paired_lines
+                || distance <= max_line_distance
+            {
+                row.push(Some((
+                    annotated_minus_line,
+                    annotated_plus_line,
+                    1.0 - distance,
+                )));
+            } else {
+                row.push(None);
+            }
+        }
+        homologs.push(row);
+    }
+
+    // similarity[i][j] is the greatest total similarity of an alignment of the first i minus
+    // lines with the first j plus lines.
+    let mut similarity = vec![vec![0.0_f64; n + 1]; m + 1];
+    for i in 1..=m {
+        for j in 1..=n {
+            let mut best = similarity[i - 1][j].max(similarity[i][j - 1]);
+            if let Some((_, _, s)) = &homologs[i - 1][j - 1] {
+                best = best.max(similarity[i - 1][j - 1] + s);
+            }
+            similarity[i][j] = best;
+        }
+    }
+
+    // Trace the alignment back from its end. Unpaired plus lines are emitted first, so that they
+    // follow the unpaired minus lines preceding them.
+    let mut reversed_line_alignment = Vec::with_capacity(m + n);
+    let (mut i, mut j) = (m, n);
+    while i > 0 || j > 0 {
+        let paired = i > 0
+            && j > 0
+            && matches!(
+                &homologs[i - 1][j - 1],
+                Some((_, _, s)) if similarity[i][j] == similarity[i - 1][j - 1] + s
+            );
+        if paired {
+            i -= 1;
+            j -= 1;
+            reversed_line_alignment.push((Some(i), Some(j)));
+        } else if j > 0 && (i == 0 || similarity[i][j] == similarity[i][j - 1]) {
+            j -= 1;
+            reversed_line_alignment.push((None, Some(j)));
+        } else {
+            i -= 1;
+            reversed_line_alignment.push((Some(i), None));
+        }
+    }
+
+    let mut annotated_minus_lines = Vec::with_capacity(m);
+    let mut annotated_plus_lines = Vec::with_capacity(n);
+    let line_alignment: Vec<_> = reversed_line_alignment.into_iter().rev().collect();
+    for &(minus_index, plus_index) in &line_alignment {
+        match (minus_index, plus_index) {
+            (Some(i), Some(j)) => {
+                let (annotated_minus_line, annotated_plus_line, _) = homologs[i][j].take().unwrap();
+                annotated_minus_lines.push(annotated_minus_line);
+                annotated_plus_lines.push(annotated_plus_line);
+            }
+            (Some(i), None) => {
+                annotated_minus_lines.push(vec![(noop_deletions[i], minus_lines[i])]);
+            }
+            (None, Some(j)) => {
+                annotated_plus_lines.push(vec![(noop_insertions[j], plus_lines[j])]);
+            }
+            (None, None) => unreachable!(),
+        }
+    }
+
+    (annotated_minus_lines, annotated_plus_lines, line_alignment)
+}
+
 // Return boolean arrays indicating whether each line has a homolog (is "paired").
 pub fn make_lines_have_homolog(
     line_alignment: &[(Option<usize>, Option<usize>)],
@@ -844,6 +991,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_infer_edits_of_aligned_lines() {
+        let minus_lines = vec!["a = compute(1)", "b = compute(2)", "c = compute(3)"];
+        let plus_lines = vec![
+            "a = compute(10)",
+            "d = compute(4)",
+            "b = compute(20)",
+            "c = compute(30)",
+        ];
+        let line_alignment = |align_lines| {
+            infer_edits(
+                minus_lines.clone(),
+                plus_lines.clone(),
+                vec![MinusNoop; 3],
+                Deletion,
+                vec![PlusNoop; 4],
+                Insertion,
+                &DEFAULT_TOKENIZATION_REGEXP,
+                0.6,
+                0.0,
+                align_lines,
+            )
+            .2
+        };
+        // Greedily, "b" is paired with the inserted line "d", and "c" with "b".
+        assert_eq!(
+            line_alignment(false),
+            vec![
+                (Some(0), Some(0)),
+                (Some(1), Some(1)),
+                (Some(2), Some(2)),
+                (None, Some(3)),
+            ]
+        );
+        assert_eq!(
+            line_alignment(true),
+            vec![
+                (Some(0), Some(0)),
+                (None, Some(1)),
+                (Some(1), Some(2)),
+                (Some(2), Some(3)),
+            ]
+        );
+    }
+
     fn assert_edits(
         minus_lines: Vec<&str>,
         plus_lines: Vec<&str>,
@@ -864,6 +1056,7 @@ mod tests {
             &DEFAULT_TOKENIZATION_REGEXP,
             max_line_distance,
             0.0,
+            false,
         );
         // compare_annotated_lines(actual_edits, expected_edits);
         // TODO: test line alignment
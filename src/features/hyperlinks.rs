@@ -41,6 +41,32 @@ pub fn format_commit_line_with_osc8_commit_hyperlink<'a>(
     }
 }
 
+/// Create a hyperlink for a commit of the repository whose remote is `repo`, displaying `text`.
+pub fn format_osc8_commit_hyperlink<'a>(
+    commit: &str,
+    text: &'a str,
+    repo: Option<&GitRemoteRepo>,
+    config: &Config,
+) -> Cow<'a, str> {
+    match repo {
+        Some(repo) if config.hyperlinks => {
+            Cow::from(format_osc8_hyperlink(&repo.format_commit_url(commit), text))
+        }
+        _ => Cow::from(text),
+    }
+}
+
+/// Create a hyperlink for the key of a commit signature, if --hyperlinks-signature-link-format is
+/// set.
+pub fn format_osc8_signature_key_hyperlink<'a>(key: &'a str, config: &Config) -> Cow<'a, str> {
+    match &config.hyperlinks_signature_link_format {
+        Some(format) if config.hyperlinks => {
+            Cow::from(format_osc8_hyperlink(&format.replace("{key}", key), key))
+        }
+        _ => Cow::from(key),
+    }
+}
+
 /// Create a file hyperlink, displaying `text`.
 pub fn format_osc8_file_hyperlink<'a, P>(
     absolute_path: P,
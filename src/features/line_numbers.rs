@@ -7,7 +7,7 @@ use crate::config;
 use crate::delta::State;
 use crate::features::hyperlinks;
 use crate::features::side_by_side::ansifill::{self, ODD_PAD_CHAR};
-use crate::features::side_by_side::{Left, PanelSide, Right};
+use crate::features::side_by_side::{Left, PanelSide, Right, SideBySideData};
 use crate::features::OptionValueFunction;
 use crate::format::{self, Align, Placeholder};
 use crate::minusplus::*;
@@ -112,11 +112,12 @@ pub fn format_and_paint_line_numbers<'a>(
 ) -> Vec<ansi_term::ANSIGenericString<'a, str>> {
     let mut formatted_numbers = Vec::new();
 
+    // A hunk painted in unified layout in side-by-side mode (see --side-by-side-min-width) has no
+    // panel and shows both fields.
     let (emit_left, emit_right) = match (config.side_by_side, side_by_side_panel) {
-        (false, _) => (true, true),
+        (false, _) | (true, None) => (true, true),
         (true, Some(Left)) => (true, false),
         (true, Some(Right)) => (false, true),
-        (true, None) => unreachable!(),
     };
 
     if emit_left {
@@ -142,7 +143,8 @@ pub fn format_and_paint_line_numbers<'a>(
 }
 
 lazy_static! {
-    static ref LINE_NUMBERS_PLACEHOLDER_REGEX: Regex = format::make_placeholder_regex(&["nm", "np"]);
+    static ref LINE_NUMBERS_PLACEHOLDER_REGEX: Regex =
+        format::make_placeholder_regex(&["nm", "np"]);
 }
 
 #[derive(Default, Debug)]
@@ -151,6 +153,10 @@ pub struct LineNumbersData<'a> {
     pub line_number: MinusPlus<usize>,
     pub hunk_max_line_number_width: usize,
     pub plus_file: String,
+    // With `side-by-side-split = adaptive`, the widths of the panels of the current hunk.
+    pub hunk_panel_widths: Option<SideBySideData>,
+    // With --side-by-side-min-width, whether the current hunk is displayed in unified layout.
+    pub hunk_is_unified: bool,
 }
 
 pub type SideBySideLineWidth = MinusPlus<usize>;
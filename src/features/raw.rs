@@ -14,6 +14,24 @@ pub fn make_feature() -> Vec<(String, OptionValueFunction)> {
             None,
             _opt => "raw"
         ),
+        (
+            "commit-signature-bad-style",
+            String,
+            None,
+            _opt => "raw"
+        ),
+        (
+            "commit-signature-good-style",
+            String,
+            None,
+            _opt => "raw"
+        ),
+        (
+            "commit-signature-unknown-style",
+            String,
+            None,
+            _opt => "raw"
+        ),
         (
             "file-decoration-style",
             String,
@@ -38,6 +56,12 @@ pub fn make_feature() -> Vec<(String, OptionValueFunction)> {
             None,
             _opt => "raw"
         ),
+        (
+            "notes-decoration-style",
+            String,
+            None,
+            _opt => "none"
+        ),
         (
             "minus-style",
             String,
@@ -1,4 +1,7 @@
+use std::str::FromStr;
+
 use itertools::Itertools;
+use syntect::easy::HighlightLines;
 use syntect::highlighting::Style as SyntectStyle;
 use unicode_width::UnicodeWidthStr;
 
@@ -9,10 +12,16 @@ use crate::delta::DiffType;
 use crate::delta::State;
 use crate::edits;
 use crate::features::{line_numbers, OptionValueFunction};
+use crate::handlers::merge_conflict::{
+    Ancestral, MergeConflictCommit, MergeConflictCommitNames, MergeConflictCommits,
+    MergeConflictLines, Ours, Theirs,
+};
 use crate::minusplus::*;
-use crate::paint::{BgFillMethod, BgShouldFill, LineSections, Painter};
+use crate::paint::{
+    self, get_syntax_style_sections_for_lines, BgFillMethod, BgShouldFill, LineSections, Painter,
+};
 use crate::style::Style;
-use crate::wrapping::{wrap_minusplus_block, wrap_zero_block};
+use crate::wrapping::{wrap_line_sections, wrap_minusplus_block, wrap_zero_block};
 
 pub fn make_feature() -> Vec<(String, OptionValueFunction)> {
     builtin_feature!([
@@ -45,17 +54,141 @@ pub type LeftRight<T> = MinusPlus<T>;
 
 pub type SideBySideData = LeftRight<Panel>;
 
+/// How the width is split between the panels, see --side-by-side-split.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PanelSplit {
+    // The percentage of the width given to the left panel.
+    Percent(usize),
+    // Panels sized to the lines of each hunk.
+    Adaptive,
+}
+
+// The least and the greatest percentage of the width given to the left panel.
+const MIN_SPLIT_PERCENT: usize = 20;
+const MAX_SPLIT_PERCENT: usize = 100 - MIN_SPLIT_PERCENT;
+
+impl FromStr for PanelSplit {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "adaptive" => Ok(Self::Adaptive),
+            s => match s.trim_end_matches('%').parse::<usize>() {
+                Ok(percent) if (MIN_SPLIT_PERCENT..=MAX_SPLIT_PERCENT).contains(&percent) => {
+                    Ok(Self::Percent(percent))
+                }
+                _ => Err(format!(
+                    "{s:?} is neither a percentage between {MIN_SPLIT_PERCENT} and \
+                     {MAX_SPLIT_PERCENT} nor \"adaptive\""
+                )),
+            },
+        }
+    }
+}
+
 impl SideBySideData {
     /// Create a [`LeftRight<Panel>`](LeftRight<Panel>) named [`SideBySideData`].
-    pub fn new_sbs(decorations_width: &cli::Width, available_terminal_width: &usize) -> Self {
-        let panel_width = match decorations_width {
-            cli::Width::Fixed(w) => w / 2,
-            _ => available_terminal_width / 2,
+    pub fn new_sbs(
+        decorations_width: &cli::Width,
+        available_terminal_width: &usize,
+        split: &PanelSplit,
+    ) -> Self {
+        // The panels always sum up to an even width, see `ansifill`.
+        let width = total_width(decorations_width, available_terminal_width) / 2 * 2;
+        let left_width = match split {
+            PanelSplit::Percent(percent) => width * percent / 100,
+            PanelSplit::Adaptive => width / 2,
         };
-        SideBySideData::new(Panel { width: panel_width }, Panel { width: panel_width })
+        SideBySideData::new(
+            Panel { width: left_width },
+            Panel {
+                width: width - left_width,
+            },
+        )
+    }
+}
+
+/// Return the width which the two panels share between them.
+pub fn total_width(decorations_width: &cli::Width, available_terminal_width: &usize) -> usize {
+    match decorations_width {
+        cli::Width::Fixed(w) => *w,
+        _ => *available_terminal_width,
     }
 }
 
+/// Return whether the width available to delta is less than `side-by-side-min-width`.
+pub fn is_narrower_than_min_width(
+    decorations_width: &cli::Width,
+    available_terminal_width: &usize,
+    min_width: usize,
+) -> bool {
+    total_width(decorations_width, available_terminal_width) < min_width
+}
+
+/// Return whether a hunk whose longest minus and plus lines, unchanged lines included, are
+/// `text_widths` wide should be displayed in unified layout instead: this is the case when delta
+/// is narrower than `side-by-side-min-width` and some of the lines do not fit in their panel.
+pub fn hunk_needs_unified_layout(
+    text_widths: &LeftRight<usize>,
+    data: &line_numbers::LineNumbersData,
+    config: &Config,
+) -> bool {
+    let line_width = available_line_width(config, data);
+    is_narrower_than_min_width(
+        &config.decorations_width,
+        &config.available_terminal_width,
+        config.side_by_side_min_width,
+    ) && (text_widths[Left] > line_width[Left] || text_widths[Right] > line_width[Right])
+}
+
+/// Return whether the lines of the current hunk are displayed in unified layout in side-by-side
+/// mode (see [`hunk_needs_unified_layout`]).
+pub fn should_paint_unified(line_numbers_data: &Option<LineNumbersData>) -> bool {
+    line_numbers_data
+        .as_ref()
+        .is_some_and(|data| data.hunk_is_unified)
+}
+
+/// Return the width of a panel, which with `side-by-side-split = adaptive` is that of the panels
+/// of the current hunk.
+pub fn panel_width(
+    config: &Config,
+    data: Option<&line_numbers::LineNumbersData>,
+    side: PanelSide,
+) -> usize {
+    data.and_then(|data| data.hunk_panel_widths.as_ref())
+        .unwrap_or(&config.side_by_side_data)[side]
+        .width
+}
+
+/// Return the widths of the panels of a hunk whose longest minus and plus lines, unchanged lines
+/// included, are `text_widths` wide: each panel is given a share of the width proportional to the
+/// width its lines need, within the bounds of --side-by-side-split.
+pub fn get_adaptive_panel_widths(
+    text_widths: LeftRight<usize>,
+    data: &line_numbers::LineNumbersData,
+    config: &Config,
+) -> SideBySideData {
+    let width = config.side_by_side_data[Left].width + config.side_by_side_data[Right].width;
+    let line_numbers_width = data.formatted_width();
+    let needed_width = |side: PanelSide| {
+        text_widths[side] + line_numbers_width[side] + config.keep_plus_minus_markers as usize
+    };
+    let (left_needed_width, right_needed_width) = (needed_width(Left), needed_width(Right));
+    let left_width = (width * left_needed_width)
+        .checked_div(left_needed_width + right_needed_width)
+        .unwrap_or(width / 2);
+    let left_width = left_width.clamp(
+        width * MIN_SPLIT_PERCENT / 100,
+        width * MAX_SPLIT_PERCENT / 100,
+    );
+    SideBySideData::new(
+        Panel { width: left_width },
+        Panel {
+            width: width - left_width,
+        },
+    )
+}
+
 pub fn available_line_width(
     config: &Config,
     data: &line_numbers::LineNumbersData,
@@ -65,8 +198,7 @@ pub fn available_line_width(
     // The width can be reduced by the line numbers and/or
     // a possibly added/restored 1-wide "+/-/ " prefix.
     let line_width = |side: PanelSide| {
-        config.side_by_side_data[side]
-            .width
+        panel_width(config, Some(data), side)
             .saturating_sub(line_numbers_width[side])
             .saturating_sub(config.keep_plus_minus_markers as usize)
     };
@@ -221,6 +353,315 @@ pub fn paint_minus_and_plus_lines_side_by_side(
     }
 }
 
+/// Paint the lines of a merge conflict in three panels: ours on the left, the ancestral (base)
+/// lines in the middle, and theirs on the right. The lines of ours and theirs are aligned with
+/// the base lines they derive from, and their changes relative to the base are emphasized. Each
+/// panel has its line number field, and lines which are too long for their panel are wrapped as
+/// in the two panels of side-by-side mode.
+pub fn paint_merge_conflict_lines_in_three_panels(
+    lines: &MergeConflictLines,
+    commit_names: &MergeConflictCommitNames,
+    highlighter: &mut Option<HighlightLines>,
+    line_numbers_data: &mut Option<LineNumbersData>,
+    output_buffer: &mut String,
+    config: &Config,
+) {
+    let line_numbers_data = line_numbers_data
+        .as_mut()
+        .unwrap_or_else(|| delta_unreachable("side-by-side requires Some(line_numbers_data)"));
+    let base_lines = &lines[Ancestral];
+    let no_moved_lines = MinusPlus::default();
+    // The base lines are compared with each of ours and theirs.
+    let (ours_diff_sections, ours_alignment, ours_have_homolog) =
+        paint::get_diff_style_sections_and_homologs(
+            &MinusPlus::new(base_lines, &lines[Ours]),
+            &no_moved_lines,
+            config,
+        );
+    let (theirs_diff_sections, theirs_alignment, theirs_have_homolog) =
+        paint::get_diff_style_sections_and_homologs(
+            &MinusPlus::new(base_lines, &lines[Theirs]),
+            &no_moved_lines,
+            config,
+        );
+    let syntax_sections = MergeConflictCommits {
+        ours: get_syntax_style_sections_for_lines(&lines[Ours], highlighter.as_mut(), config),
+        ancestral: get_syntax_style_sections_for_lines(base_lines, highlighter.as_mut(), config),
+        theirs: get_syntax_style_sections_for_lines(&lines[Theirs], highlighter.as_mut(), config),
+    };
+
+    let panel_widths = get_three_panel_widths(config);
+    let separator = config.line_numbers_zero_style.paint("│").to_string();
+
+    // The line numbers are those of the file in the working tree, which holds the lines of all
+    // three commits, each preceded by a conflict marker line.
+    let first_line_numbers = MergeConflictCommits {
+        ours: line_numbers_data.line_number[Right] + 1,
+        ancestral: line_numbers_data.line_number[Right] + lines[Ours].len() + 2,
+        theirs: line_numbers_data.line_number[Right] + lines[Ours].len() + base_lines.len() + 3,
+    };
+    let line_numbers_width = line_numbers_data.formatted_width();
+    let line_widths = MergeConflictCommits {
+        ours: panel_widths[Ours].saturating_sub(line_numbers_width[Left]),
+        ancestral: panel_widths[Ancestral].saturating_sub(line_numbers_width[Left]),
+        theirs: panel_widths[Theirs].saturating_sub(line_numbers_width[Right]),
+    };
+
+    let mut header_line = String::new();
+    for (commit, style) in [
+        (Ours, config.merge_conflict_ours_diff_header_style),
+        (Ancestral, config.zero_style),
+        (Theirs, config.merge_conflict_theirs_diff_header_style),
+    ] {
+        if commit != Ours {
+            header_line.push_str(&separator);
+        }
+        let name = commit_names[&commit].as_deref().unwrap_or("?");
+        let mut title = style.paint(name).to_string();
+        pad_or_truncate_three_panel_line(
+            &mut title,
+            panel_widths[&commit],
+            (commit != Theirs).then_some(BgFillMethod::Spaces),
+            config.null_style,
+            config,
+        );
+        header_line.push_str(&title);
+    }
+    output_buffer.push_str(&header_line);
+    output_buffer.push('\n');
+
+    for row in align_with_base_lines(&ours_alignment, &theirs_alignment) {
+        // The lines of each panel in this row: more than one if the line of the panel is wrapped.
+        let mut panel_lines = MergeConflictCommits {
+            ours: Vec::new(),
+            ancestral: Vec::new(),
+            theirs: Vec::new(),
+        };
+        for commit in [Ours, Ancestral, Theirs] {
+            let index = match row[&commit] {
+                Some(index) => index,
+                None => continue,
+            };
+            // The base line is emphasized relative to the line of ours it is aligned with, if
+            // any, and otherwise relative to the line of theirs.
+            let (diff_sections, have_homolog, side) = match commit {
+                Ours => (&ours_diff_sections, &ours_have_homolog, Plus),
+                Theirs => (&theirs_diff_sections, &theirs_have_homolog, Plus),
+                Ancestral if row[&Ours].is_some() || row[&Theirs].is_none() => {
+                    (&ours_diff_sections, &ours_have_homolog, Minus)
+                }
+                Ancestral => (&theirs_diff_sections, &theirs_have_homolog, Minus),
+            };
+            let (line, state) = &lines[&commit][index];
+            let (wrapped_syntax_sections, wrapped_diff_sections) = if config.wrap_config.max_lines
+                != 1
+                && line_is_too_long(line, line_widths[&commit])
+            {
+                wrap_line_sections(
+                    config,
+                    syntax_sections[&commit][index].clone(),
+                    diff_sections[side][index].clone(),
+                    line_widths[&commit],
+                    match side {
+                        Minus => &config.minus_style,
+                        Plus => &config.plus_style,
+                    },
+                )
+            } else {
+                (
+                    vec![syntax_sections[&commit][index].clone()],
+                    vec![diff_sections[side][index].clone()],
+                )
+            };
+            for (i, (syntax_sections, diff_sections)) in wrapped_syntax_sections
+                .iter()
+                .zip_eq(&wrapped_diff_sections)
+                .enumerate()
+            {
+                let (line, line_is_empty) = Painter::paint_line(
+                    syntax_sections,
+                    diff_sections,
+                    state,
+                    &mut None,
+                    None,
+                    None,
+                    config,
+                );
+                let line_number = (i == 0).then_some(first_line_numbers[&commit] + index);
+                let mut panel_line =
+                    paint_three_panel_line_numbers(line_numbers_data, &commit, line_number, config);
+                panel_line.push_str(&line);
+                if line_is_empty {
+                    let empty_line_marker_style = match side {
+                        Minus => &config.minus_empty_line_marker_style,
+                        Plus => &config.plus_empty_line_marker_style,
+                    };
+                    Painter::mark_empty_line(empty_line_marker_style, &mut panel_line, Some(" "));
+                }
+                let (bg_fill_mode, fill_style) =
+                    Painter::get_should_right_fill_background_color_and_fill_style(
+                        diff_sections,
+                        Some(have_homolog[side][index]),
+                        state,
+                        BgShouldFill::With(if commit == Theirs {
+                            config.line_fill_method
+                        } else {
+                            BgFillMethod::Spaces
+                        }),
+                        config,
+                    );
+                // The left and middle panels are padded even if not filled with a color.
+                let (bg_fill_mode, fill_style) = match bg_fill_mode {
+                    None if commit != Theirs => (Some(BgFillMethod::Spaces), config.null_style),
+                    _ => (bg_fill_mode, fill_style),
+                };
+                pad_or_truncate_three_panel_line(
+                    &mut panel_line,
+                    panel_widths[&commit],
+                    bg_fill_mode,
+                    fill_style,
+                    config,
+                );
+                panel_lines[commit.clone()].push(panel_line);
+            }
+        }
+        let n_lines = [Ours, Ancestral, Theirs]
+            .iter()
+            .map(|commit| panel_lines[commit].len())
+            .max()
+            .unwrap_or(0);
+        for i in 0..n_lines {
+            for commit in [Ours, Ancestral, Theirs] {
+                if commit != Ours {
+                    output_buffer.push_str(&separator);
+                }
+                match panel_lines[&commit].get(i) {
+                    Some(panel_line) => output_buffer.push_str(panel_line),
+                    None => {
+                        let mut panel_line = paint_three_panel_line_numbers(
+                            line_numbers_data,
+                            &commit,
+                            None,
+                            config,
+                        );
+                        if commit != Theirs {
+                            pad_or_truncate_three_panel_line(
+                                &mut panel_line,
+                                panel_widths[&commit],
+                                Some(BgFillMethod::Spaces),
+                                config.null_style,
+                                config,
+                            );
+                        }
+                        output_buffer.push_str(&panel_line);
+                    }
+                }
+            }
+            output_buffer.push('\n');
+        }
+    }
+
+    // The lines of ours are those of the first parent, and the working tree file also holds the
+    // ancestral lines, the lines of theirs, and the four conflict marker lines.
+    line_numbers_data.line_number[Left] += lines[Ours].len();
+    line_numbers_data.line_number[Right] +=
+        lines[Ours].len() + base_lines.len() + lines[Theirs].len() + 4;
+}
+
+/// Paint the line number field of a panel of a merge conflict: the left field for ours and the
+/// ancestral lines, and the right field for theirs.
+fn paint_three_panel_line_numbers(
+    line_numbers_data: &LineNumbersData,
+    commit: &MergeConflictCommit,
+    line_number: Option<usize>,
+    config: &Config,
+) -> String {
+    let (panel_side, style) = match commit {
+        Ours => (Left, config.line_numbers_style_minusplus[Plus]),
+        Ancestral => (Left, config.line_numbers_style_minusplus[Minus]),
+        Theirs => (Right, config.line_numbers_style_minusplus[Plus]),
+    };
+    ansi_term::ANSIStrings(&line_numbers::format_and_paint_line_numbers(
+        line_numbers_data,
+        Some(panel_side),
+        MinusPlus::new(style, style),
+        MinusPlus::new(line_number, line_number),
+        config,
+    ))
+    .to_string()
+}
+
+/// The widths of the three panels, which share the width of the two side-by-side panels with the
+/// two separators between them.
+fn get_three_panel_widths(config: &Config) -> MergeConflictCommits<usize> {
+    let width = (config.side_by_side_data[Left].width + config.side_by_side_data[Right].width)
+        .saturating_sub(2);
+    MergeConflictCommits {
+        ours: width / 3,
+        ancestral: width / 3,
+        theirs: width - 2 * (width / 3),
+    }
+}
+
+fn pad_or_truncate_three_panel_line(
+    panel_line: &mut String,
+    panel_width: usize,
+    bg_fill_mode: Option<BgFillMethod>,
+    fill_style: Style,
+    config: &Config,
+) {
+    let text_width = ansi::measure_text_width(panel_line);
+    if text_width > panel_width {
+        *panel_line =
+            ansi::truncate_str(panel_line, panel_width, &config.truncation_symbol).to_string();
+    }
+    match bg_fill_mode {
+        Some(BgFillMethod::TryAnsiSequence) => {
+            Painter::right_fill_background_color(panel_line, fill_style)
+        }
+        Some(BgFillMethod::Spaces) if text_width < panel_width => panel_line.push_str(
+            &fill_style
+                .paint(" ".repeat(panel_width - text_width))
+                .to_string(),
+        ),
+        _ => {}
+    }
+}
+
+/// Combine the alignments of the base lines with the lines of ours and with those of theirs into
+/// rows of the three panels. A line of ours and a line of theirs which both have no homolog in the
+/// base share a row.
+fn align_with_base_lines(
+    ours_alignment: &[(Option<usize>, Option<usize>)],
+    theirs_alignment: &[(Option<usize>, Option<usize>)],
+) -> Vec<MergeConflictCommits<Option<usize>>> {
+    let mut ours_alignment = ours_alignment.iter().peekable();
+    let mut theirs_alignment = theirs_alignment.iter().peekable();
+    let mut rows = Vec::new();
+    loop {
+        let ours = ours_alignment.next_if(|(base, _)| base.is_none());
+        let theirs = theirs_alignment.next_if(|(base, _)| base.is_none());
+        if ours.is_none() && theirs.is_none() {
+            // Both alignments are now at the same base line, or have ended.
+            match (ours_alignment.next(), theirs_alignment.next()) {
+                (None, None) => break,
+                (ours, theirs) => rows.push(MergeConflictCommits {
+                    ours: ours.and_then(|(_, line)| *line),
+                    ancestral: ours.or(theirs).and_then(|(base, _)| *base),
+                    theirs: theirs.and_then(|(_, line)| *line),
+                }),
+            }
+        } else {
+            rows.push(MergeConflictCommits {
+                ours: ours.and_then(|(_, line)| *line),
+                ancestral: None,
+                theirs: theirs.and_then(|(_, line)| *line),
+            });
+        }
+    }
+    rows
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn paint_zero_lines_side_by_side<'a>(
     line: &str,
@@ -267,6 +708,7 @@ pub fn paint_zero_lines_side_by_side<'a>(
                 None,
                 &state,
                 *panel_side,
+                panel_width(config, line_numbers_data.as_deref(), *panel_side),
                 background_color_extends_to_terminal_width,
                 config,
             );
@@ -304,6 +746,7 @@ fn paint_left_panel_minus_line<'a>(
         Some(lines_have_homolog),
         state,
         Left,
+        panel_width(config, line_numbers_data.as_deref(), Left),
         background_color_extends_to_terminal_width,
         config,
     );
@@ -340,6 +783,7 @@ fn paint_right_panel_plus_line<'a>(
         Some(lines_have_homolog),
         state,
         Right,
+        panel_width(config, line_numbers_data.as_deref(), Right),
         background_color_extends_to_terminal_width,
         config,
     );
@@ -476,6 +920,7 @@ fn pad_panel_line_to_width(
     lines_have_homolog: Option<&[bool]>,
     state: &State,
     panel_side: PanelSide,
+    panel_width: usize,
     background_color_extends_to_terminal_width: BgShouldFill,
     config: &Config,
 ) {
@@ -500,7 +945,6 @@ fn pad_panel_line_to_width(
     };
 
     let text_width = ansi::measure_text_width(panel_line);
-    let panel_width = config.side_by_side_data[panel_side].width;
 
     if text_width > panel_width {
         *panel_line =
@@ -590,9 +1034,14 @@ pub mod ansifill {
 
 #[cfg(test)]
 pub mod tests {
+    use std::fs::remove_file;
+
+    use super::PanelSplit;
     use crate::ansi::strip_ansi_codes;
     use crate::features::line_numbers::tests::*;
-    use crate::tests::integration_test_utils::{make_config_from_args, run_delta, DeltaTest};
+    use crate::tests::integration_test_utils::{
+        make_config_from_args, make_config_from_args_and_git_config, run_delta, DeltaTest,
+    };
 
     #[test]
     fn test_two_minus_lines() {
@@ -605,6 +1054,87 @@ pub mod tests {
             );
     }
 
+    #[test]
+    fn test_two_minus_lines_narrower_than_min_width() {
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--side-by-side-min-width",
+            "100",
+            "--width",
+            "28",
+            "--line-fill-method=spaces",
+        ])
+        .with_input(TWO_MINUS_LINES_DIFF)
+        .expect_after_header(
+            r#"
+            │  1 ││    │a = 1
+            │  2 ││    │b = 23456"#,
+        );
+
+        // Lines which fit in the panels stay side-by-side.
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--side-by-side-min-width",
+            "100",
+            "--width",
+            "40",
+            "--line-fill-method=spaces",
+        ])
+        .with_input(TWO_MINUS_LINES_DIFF)
+        .expect_after_header(
+            r#"
+            │  1 │a = 1         │    │
+            │  2 │b = 23456     │    │"#,
+        );
+    }
+
+    #[test]
+    fn test_hunk_narrower_than_min_width_in_one_layout() {
+        // The last minus line does not fit in its panel, so the whole hunk is displayed in
+        // unified layout.
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--side-by-side-min-width",
+            "100",
+            "--width",
+            "28",
+            "--line-fill-method=spaces",
+        ])
+        .with_input(HUNK_WITH_ONE_LONG_LINE_DIFF)
+        .expect_after_header(
+            r#"
+            │  1 ││  1 │x = 1
+            │  2 ││    │y = 2
+            │    ││  2 │y = 3
+            │  3 ││  3 │z = 4
+            │  4 ││    │a_long_line = 1234567
+            │    ││  4 │w = 5"#,
+        );
+    }
+
+    #[test]
+    fn test_side_by_side_auto_narrower_than_min_width() {
+        let git_config_contents = b"
+[delta]
+    side-by-side = auto
+    side-by-side-min-width = 100
+";
+        let git_config_path = "delta__test_side_by_side_auto_narrower_than_min_width.gitconfig";
+        let config = make_config_from_args_and_git_config(
+            &["--width", "60"],
+            Some(git_config_contents),
+            Some(git_config_path),
+        );
+        DeltaTest::with_config(&config)
+            .with_input(TWO_MINUS_LINES_DIFF)
+            .expect_after_header(
+                r#"
+                a = 1
+                b = 23456"#,
+            );
+        remove_file(git_config_path).unwrap();
+    }
+
     #[test]
     fn test_two_minus_lines_truncated() {
         DeltaTest::with_args(&[
@@ -714,6 +1244,76 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_one_minus_one_plus_line_split() {
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--side-by-side-split",
+            "30",
+            "--width",
+            "40",
+            "--line-fill-method=spaces",
+        ])
+        .with_input(ONE_MINUS_ONE_PLUS_LINE_DIFF)
+        .expect_after_header(
+            r#"
+            │  1 │a = 1 │  1 │a = 1
+            │  2 │b = 2 │  2 │bb = 2                "#,
+        );
+    }
+
+    #[test]
+    fn test_line_inserted_among_edited_lines() {
+        // The inserted line faces blank space, and the lines after it stay paired.
+        DeltaTest::with_args(&["--side-by-side", "--width", "60"])
+            .with_input(LINE_INSERTED_AMONG_EDITED_LINES_DIFF)
+            .expect_after_header(
+                r#"
+            │  1 │a = compute(1)          │  1 │a = compute(10)
+            │    │                        │  2 │d = compute(4)
+            │  2 │b = compute(2)          │  3 │b = compute(20)
+            │  3 │c = compute(3)          │  4 │c = compute(30)"#,
+            );
+    }
+
+    const HUNK_WITH_ONE_LONG_LINE_DIFF: &str = "\
+diff --git i/a.py w/a.py
+index 223ca50..e69de29 100644
+--- i/a.py
++++ w/a.py
+@@ -1,4 +1,4 @@
+ x = 1
+-y = 2
++y = 3
+ z = 4
+-a_long_line = 1234567
++w = 5
+";
+
+    const LINE_INSERTED_AMONG_EDITED_LINES_DIFF: &str = "\
+diff --git i/a.py w/a.py
+index 223ca50..367a6f6 100644
+--- i/a.py
++++ w/a.py
+@@ -1,3 +1,4 @@
+-a = compute(1)
+-b = compute(2)
+-c = compute(3)
++a = compute(10)
++d = compute(4)
++b = compute(20)
++c = compute(30)
+";
+
+    #[test]
+    fn test_parse_panel_split() {
+        assert_eq!("50".parse(), Ok(PanelSplit::Percent(50)));
+        assert_eq!("30%".parse(), Ok(PanelSplit::Percent(30)));
+        assert_eq!("adaptive".parse(), Ok(PanelSplit::Adaptive));
+        assert!("10".parse::<PanelSplit>().is_err());
+        assert!("wide".parse::<PanelSplit>().is_err());
+    }
+
     #[test]
     fn test_two_minus_lines_unicode_truncated() {
         DeltaTest::with_args(&[
@@ -767,4 +1367,31 @@ pub mod tests {
             │    │三 │    │"#,
         );
     }
+
+    #[test]
+    fn test_align_with_base_lines() {
+        let rows = |ours_alignment, theirs_alignment| {
+            super::align_with_base_lines(ours_alignment, theirs_alignment)
+                .into_iter()
+                .map(|row| (row.ours, row.ancestral, row.theirs))
+                .collect::<Vec<_>>()
+        };
+        // Base lines 0 and 1; ours replaces line 0 and inserts a line, theirs removes line 1.
+        assert_eq!(
+            rows(
+                &[(Some(0), Some(0)), (None, Some(1)), (Some(1), Some(2))],
+                &[(Some(0), Some(0)), (Some(1), None)],
+            ),
+            vec![
+                (Some(0), Some(0), Some(0)),
+                (Some(1), None, None),
+                (Some(2), Some(1), None),
+            ]
+        );
+        // Lines inserted by both share rows.
+        assert_eq!(
+            rows(&[(None, Some(0)), (None, Some(1))], &[(None, Some(0))],),
+            vec![(Some(0), None, Some(0)), (Some(1), None, None)]
+        );
+    }
 }
@@ -5,6 +5,7 @@ pub use remote::GitRemoteRepo;
 use crate::env::DeltaEnv;
 use regex::Regex;
 use std::collections::HashMap;
+use std::io::Read;
 use std::path::Path;
 use std::str::FromStr;
 
@@ -109,12 +110,63 @@ impl GitConfig {
     }
 
     pub fn get_remote_url(&self) -> Option<GitRemoteRepo> {
+        get_repository_remote_url(self.repo.as_ref()?)
+    }
+
+    pub fn get_remote_names(&self) -> Vec<String> {
         self.repo
-            .as_ref()?
-            .find_remote("origin")
-            .ok()?
-            .url()
-            .and_then(|url| GitRemoteRepo::from_str(url).ok())
+            .as_ref()
+            .and_then(|repo| repo.remotes().ok())
+            .map(|remotes| remotes.iter().flatten().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Return the size and the first `max_len` bytes, or all the bytes, of the blob with this
+    /// (possibly abbreviated) id, from the object database or, if the file at `path` has unstaged
+    /// changes, from the working tree.
+    pub fn get_blob(
+        &self,
+        id: &str,
+        path: &str,
+        max_len: Option<usize>,
+    ) -> Option<(usize, Vec<u8>)> {
+        let repo = self.repo.as_ref()?;
+        let max_len = max_len.map_or(u64::MAX, |max_len| max_len as u64);
+        let odb = repo.odb().ok()?;
+        let blob = git2::Oid::from_str(id)
+            .and_then(|oid| odb.exists_prefix(oid, id.len()))
+            .and_then(|oid| Ok((oid, odb.read_header(oid)?)));
+        if let Ok((oid, (size, git2::ObjectType::Blob))) = blob {
+            let mut bytes = Vec::new();
+            match odb.reader(oid) {
+                Ok((reader, _, _)) => reader.take(max_len).read_to_end(&mut bytes).ok()?,
+                // Objects in packs cannot be streamed.
+                Err(_) => odb
+                    .read(oid)
+                    .ok()?
+                    .data()
+                    .take(max_len)
+                    .read_to_end(&mut bytes)
+                    .ok()?,
+            };
+            return Some((size, bytes));
+        }
+        let file_path = repo.workdir()?.join(path);
+        let oid = git2::Oid::hash_file(git2::ObjectType::Blob, &file_path).ok()?;
+        if !oid.to_string().starts_with(id) {
+            return None;
+        }
+        let file = std::fs::File::open(file_path).ok()?;
+        let size = file.metadata().ok()?.len() as usize;
+        let mut bytes = Vec::new();
+        file.take(max_len).read_to_end(&mut bytes).ok()?;
+        Some((size, bytes))
+    }
+
+    /// Open the repository of the submodule at `path`, relative to the root of the working tree.
+    pub fn open_submodule(&self, path: &str) -> Option<git2::Repository> {
+        let workdir = self.repo.as_ref()?.workdir()?;
+        git2::Repository::open(workdir.join(path)).ok()
     }
 
     pub fn for_each<F>(&self, regex: &str, mut f: F)
@@ -130,6 +182,13 @@ impl GitConfig {
     }
 }
 
+pub fn get_repository_remote_url(repo: &git2::Repository) -> Option<GitRemoteRepo> {
+    repo.find_remote("origin")
+        .ok()?
+        .url()
+        .and_then(|url| GitRemoteRepo::from_str(url).ok())
+}
+
 fn parse_config_from_env_var(env: &DeltaEnv) -> HashMap<String, String> {
     if let Some(s) = &env.git_config_parameters {
         parse_config_from_env_var_value(s)
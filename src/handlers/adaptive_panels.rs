@@ -0,0 +1,193 @@
+// A module for sizing the side-by-side panels to the lines of each hunk, with
+// `side-by-side-split = adaptive`, and for choosing the layout of each hunk when delta is narrower
+// than --side-by-side-min-width.
+//
+// The lines of a hunk are held back until the hunk ends, which is known from the lengths in its
+// header. The panels of the hunk are then given shares of the width proportional to the widths of
+// its longest minus and plus lines (unchanged lines count for both), the whole hunk is displayed
+// in unified layout if some of these do not fit in their panel, and the lines are handled as if
+// they had just been read. The lines of combined diffs and of diffs converted to unified diffs
+// (`diff -c` and `diff` output) are not held back, and use the default split.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::Config;
+use crate::delta::StateMachine;
+use crate::features::side_by_side::{self, PanelSplit};
+use crate::handlers::hunk_header::RemainingHunkLines;
+use crate::minusplus::*;
+use crate::paint::expand_tabs;
+
+/// Return whether the lines of each hunk are held back until the hunk ends.
+pub fn holds_back_hunks(config: &Config) -> bool {
+    config.side_by_side
+        && (config.side_by_side_split == PanelSplit::Adaptive
+            || side_by_side::is_narrower_than_min_width(
+                &config.decorations_width,
+                &config.available_terminal_width,
+                config.side_by_side_min_width,
+            ))
+}
+
+/// The lines of a hunk held back until the hunk ends.
+#[derive(Debug)]
+pub struct AdaptivePanelsHunk {
+    line_numbers_and_hunk_lengths: Vec<(usize, usize)>,
+    lines: Vec<(String, String)>,
+    remaining_lines: RemainingHunkLines,
+    // The widths of the longest lines on the minus and plus sides, without their prefix.
+    text_widths: MinusPlus<usize>,
+}
+
+impl AdaptivePanelsHunk {
+    /// Return the hunk whose lines are to be held back, if hunks are held back and the hunk is
+    /// not part of a combined diff.
+    pub fn new(line_numbers_and_hunk_lengths: &[(usize, usize)], config: &Config) -> Option<Self> {
+        if !holds_back_hunks(config) {
+            return None;
+        }
+        Some(Self {
+            line_numbers_and_hunk_lengths: line_numbers_and_hunk_lengths.to_vec(),
+            lines: Vec::new(),
+            remaining_lines: RemainingHunkLines::new(line_numbers_and_hunk_lengths)?,
+            text_widths: MinusPlus::default(),
+        })
+    }
+
+    /// Hold back `line` if it belongs to the hunk, returning false otherwise.
+    fn push(&mut self, line: &str, raw_line: &str, config: &Config) -> bool {
+        if !self.remaining_lines.count_line(line) {
+            return false;
+        }
+        let text_width = || expand_tabs(line[1..].graphemes(true), config.tab_width).width();
+        if matches!(line.chars().next(), Some('-') | Some(' ')) {
+            self.text_widths[Minus] = self.text_widths[Minus].max(text_width());
+        }
+        if matches!(line.chars().next(), Some('+') | Some(' ')) {
+            self.text_widths[Plus] = self.text_widths[Plus].max(text_width());
+        }
+        self.lines.push((line.to_string(), raw_line.to_string()));
+        true
+    }
+
+    fn is_complete(&self) -> bool {
+        self.remaining_lines.is_complete()
+    }
+}
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_adaptive_panels_line(&self) -> bool {
+        self.adaptive_panels_hunk.is_some()
+    }
+
+    /// Hold back the lines of a hunk until it ends, and then handle them with the panels sized
+    /// to them, in the layout they fit in.
+    pub fn handle_adaptive_panels_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_adaptive_panels_line() {
+            return Ok(false);
+        }
+        let hunk = self.adaptive_panels_hunk.as_mut().unwrap();
+        if !hunk.push(&self.line, &self.raw_line, self.config) {
+            self.flush_adaptive_panels_hunk()?;
+            return Ok(false);
+        }
+        // Don't let the buffer become arbitrarily large: the rest of a long hunk is painted with
+        // the panels sized to its beginning.
+        if hunk.is_complete() || hunk.lines.len() > self.config.line_buffer_size {
+            self.flush_adaptive_panels_hunk()?;
+        }
+        Ok(true)
+    }
+
+    /// Size the panels to the lines held back, if any, choose their layout, and handle them.
+    pub fn flush_adaptive_panels_hunk(&mut self) -> std::io::Result<()> {
+        let hunk = match self.adaptive_panels_hunk.take() {
+            Some(hunk) => hunk,
+            None => return Ok(()),
+        };
+        if let Some(line_numbers_data) = self.painter.line_numbers_data.as_mut() {
+            // The line numbers of the hunk determine the width of the line number columns.
+            if self.config.line_numbers {
+                line_numbers_data.initialize_hunk(
+                    &hunk.line_numbers_and_hunk_lengths,
+                    self.plus_file.to_string(),
+                );
+            }
+            if self.config.side_by_side_split == PanelSplit::Adaptive {
+                line_numbers_data.hunk_panel_widths =
+                    Some(side_by_side::get_adaptive_panel_widths(
+                        hunk.text_widths.clone(),
+                        line_numbers_data,
+                        self.config,
+                    ));
+            }
+            line_numbers_data.hunk_is_unified = side_by_side::hunk_needs_unified_layout(
+                &hunk.text_widths,
+                line_numbers_data,
+                self.config,
+            );
+        }
+        let current_line = (
+            std::mem::take(&mut self.line),
+            std::mem::take(&mut self.raw_line),
+        );
+        for (line, raw_line) in hunk.lines {
+            self.line = line;
+            self.raw_line = raw_line;
+            self.handle_line()?;
+        }
+        self.line = current_line.0;
+        self.raw_line = current_line.1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    const DIFF: &str = "\
+diff --git a/a.py b/a.py
+index 1..2 100644
+--- a/a.py
++++ b/a.py
+@@ -1,2 +1,3 @@
+ x = 1
+-y = 2
++y = 2 + a_longer_expression
++z = 3
+@@ -10,1 +11,1 @@
+-short
++a very long line, much longer than the line it replaces
+";
+
+    #[test]
+    fn test_adaptive_panels() {
+        // The panels are given shares of the width in proportion to 6 + 5 and 6 + 27 in the first
+        // hunk, and the left panel is given the least share of the width in the second hunk.
+        DeltaTest::with_args(&[
+            "--side-by-side",
+            "--side-by-side-split",
+            "adaptive",
+            "--width",
+            "80",
+            "--line-fill-method=spaces",
+        ])
+        .with_input(DIFF)
+        .expect_after_header(
+            r#"#partial
+            │  1 │x = 1         │  1 │x = 1
+            │  2 │y = 2         │    │
+            │    │              │  2 │y = 2 + a_longer_expression                           
+            │    │              │  3 │z = 3                                                 "#,
+        )
+        .expect_contains(
+            "
+│ 10 │short     │    │
+│    │          │ 11 │a very long line, much longer than the line it replaces   
+",
+        );
+    }
+}
@@ -0,0 +1,423 @@
+// A module for handling binary files.
+//
+// `git diff` does not show the changes of a binary file, but only
+// ```
+// Binary files a/logo.png and b/logo.png differ
+// ```
+// With `--binary`, it shows instead a patch which git can apply, consisting of two blocks of base85
+// encoded, zlib compressed data, the first for the change and the second for its reverse:
+// ```
+// GIT binary patch
+// literal 4
+// LcmeZB;9>v(0BZmR
+//
+// literal 0
+// HcmV?d00001
+//
+// ```
+// Each block is summarized by its size. If delta is running in the repository, the two versions of
+// the file are also read from its object database, using the blob ids of the "index" line, and
+// described. With --binary-hexdump, the bytes which differ are shown in a side-by-side hexdump.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::config::Config;
+use crate::delta::{State, StateMachine};
+use crate::handlers::diff_header::{
+    get_git_diff_prefixes, get_repeated_file_path_from_diff_line, FileEvent,
+};
+use crate::style::Style;
+
+#[derive(Debug, Default)]
+pub struct BinaryPatch {
+    blocks: Vec<BinaryPatchBlock>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct BinaryPatchBlock {
+    kind: String,           // "literal" or "delta"
+    size: usize,            // The size of the data once decompressed
+    compressed_size: usize, // The size of the data once base85 decoded
+}
+
+lazy_static! {
+    // E.g. "index 0000000..b572921" and "index 8b13789..e69de29 100644"
+    static ref INDEX_LINE_REGEX: Regex =
+        Regex::new(r"^index ([0-9a-f]+)\.\.([0-9a-f]+)(?: [0-7]+)?$").unwrap();
+    // E.g. "literal 4" and "delta 14"
+    static ref BINARY_PATCH_BLOCK_HEADER_REGEX: Regex =
+        Regex::new(r"^(literal|delta) (\d+)$").unwrap();
+}
+
+const HEXDUMP_ROW_LENGTH: usize = 8;
+const HEXDUMP_MAX_ROWS: usize = 32;
+
+const MAGIC_NUMBERS: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "PNG image"),
+    (b"\xff\xd8\xff", "JPEG image"),
+    (b"GIF87a", "GIF image"),
+    (b"GIF89a", "GIF image"),
+    (b"%PDF-", "PDF document"),
+    (b"PK\x03\x04", "zip archive"),
+    (b"\x1f\x8b", "gzip compressed data"),
+    (b"\x7fELF", "ELF executable"),
+    (b"\0asm", "WebAssembly module"),
+];
+
+// The number of bytes read from the start of a file to guess its type: the length of the longest
+// magic number.
+const MAGIC_NUMBER_MAX_LEN: usize = 8;
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_binary_line(&self) -> bool {
+        !self.config.color_only
+            && (matches!(self.state, State::DiffHeader(_)) || self.binary_patch.is_some())
+    }
+
+    /// Handle the lines of a diff header which describe the change of a binary file.
+    pub fn handle_binary_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_binary_line() {
+            return Ok(false);
+        }
+        if self.binary_patch.is_some() {
+            return self.handle_binary_patch_line();
+        }
+        if let Some(caps) = INDEX_LINE_REGEX.captures(&self.line) {
+            // The line itself is skipped, like the other lines of the diff header.
+            self.blob_ids = Some((caps[1].to_string(), caps[2].to_string()));
+            Ok(false)
+        } else if self.line == "GIT binary patch" {
+            self.set_file_pair_of_binary_patch();
+            self.painter.emit()?;
+            self.handle_pending_line_with_diff_name()?;
+            self.binary_patch = Some(BinaryPatch::default());
+            Ok(true)
+        } else if self.line.starts_with("Binary files ") {
+            let handled_line = self.handle_diff_header_misc_line()?;
+            if handled_line {
+                self.write_binary_file_description()?;
+            }
+            Ok(handled_line)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn handle_binary_patch_line(&mut self) -> std::io::Result<bool> {
+        let binary_patch = self.binary_patch.as_mut().unwrap();
+        if let Some(caps) = BINARY_PATCH_BLOCK_HEADER_REGEX.captures(&self.line) {
+            binary_patch.blocks.push(BinaryPatchBlock {
+                kind: caps[1].to_string(),
+                size: caps[2].parse().unwrap_or(0),
+                compressed_size: 0,
+            });
+            return Ok(true);
+        }
+        if let (Some(block), Some(length)) = (
+            binary_patch.blocks.last_mut(),
+            get_base85_line_length(&self.line),
+        ) {
+            block.compressed_size += length;
+            return Ok(true);
+        }
+        // Each block is followed by an empty line.
+        if self.line.is_empty() && binary_patch.blocks.len() == 1 {
+            return Ok(true);
+        }
+        self.flush_binary_patch()?;
+        Ok(self.line.is_empty())
+    }
+
+    /// Write the summary of the binary patch being handled, if any.
+    pub fn flush_binary_patch(&mut self) -> std::io::Result<()> {
+        if let Some(binary_patch) = self.binary_patch.take() {
+            writeln!(
+                self.painter.writer,
+                "{}",
+                format_binary_patch_summary(&binary_patch)
+            )?;
+            self.write_binary_file_description()?;
+        }
+        Ok(())
+    }
+
+    /// A binary patch has no "---" and "+++" lines, so the file is named by the "diff --git"
+    /// line, unless it has been renamed.
+    fn set_file_pair_of_binary_patch(&mut self) {
+        if let Some(name) = get_repeated_file_path_from_diff_line(
+            &self.diff_line,
+            &get_git_diff_prefixes(self.config),
+        ) {
+            let (minus_file, plus_file) = match &self.blob_ids {
+                Some((minus_id, _)) if is_null_id(minus_id) => ("/dev/null".to_string(), name),
+                Some((_, plus_id)) if is_null_id(plus_id) => (name, "/dev/null".to_string()),
+                _ => (name.clone(), name),
+            };
+            self.minus_file = minus_file;
+            self.plus_file = plus_file;
+            self.minus_file_event = FileEvent::Change;
+            self.plus_file_event = FileEvent::Change;
+            self.current_file_pair = Some((self.minus_file.clone(), self.plus_file.clone()));
+        }
+    }
+
+    /// If delta is running in the repository, describe the two versions of the binary file and,
+    /// with --binary-hexdump, show the bytes which differ. Otherwise only the sizes and the first
+    /// bytes of the two versions are read.
+    fn write_binary_file_description(&mut self) -> std::io::Result<()> {
+        let config = self.config;
+        let (blob_ids, git_config) = match (self.blob_ids.take(), config.git_config()) {
+            (Some(blob_ids), Some(git_config)) => (blob_ids, git_config),
+            _ => return Ok(()),
+        };
+        let path = get_repeated_file_path_from_diff_line(
+            &self.diff_line,
+            &get_git_diff_prefixes(self.config),
+        )
+        .unwrap_or_else(|| self.plus_file.clone());
+        let max_len = (!config.binary_hexdump).then_some(MAGIC_NUMBER_MAX_LEN);
+        let get_blob = |id: &str| match is_null_id(id) {
+            true => Some(None),
+            false => git_config.get_blob(id, &path, max_len).map(Some),
+        };
+        let (minus_blob, plus_blob) = match (get_blob(&blob_ids.0), get_blob(&blob_ids.1)) {
+            (Some(minus_blob), Some(plus_blob)) => (minus_blob, plus_blob),
+            _ => return Ok(()),
+        };
+        writeln!(
+            self.painter.writer,
+            "{} ⟶   {}",
+            describe_blob(minus_blob.as_ref()),
+            describe_blob(plus_blob.as_ref())
+        )?;
+        if config.binary_hexdump {
+            let minus_bytes = minus_blob.as_ref().map_or(&[][..], |(_, bytes)| bytes);
+            let plus_bytes = plus_blob.as_ref().map_or(&[][..], |(_, bytes)| bytes);
+            for line in get_hexdump_diff_lines(minus_bytes, plus_bytes, config) {
+                writeln!(self.painter.writer, "{line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The id of the blob of a file which does not exist, e.g. "0000000".
+fn is_null_id(id: &str) -> bool {
+    id.bytes().all(|b| b == b'0')
+}
+
+/// Return the number of bytes encoded by a line of base85 data, which is given by its first
+/// character: 'A' to 'Z' for 1 to 26 bytes, 'a' to 'z' for 27 to 52 bytes.
+fn get_base85_line_length(line: &str) -> Option<usize> {
+    let length = match line.bytes().next()? {
+        b @ b'A'..=b'Z' => (b - b'A') as usize + 1,
+        b @ b'a'..=b'z' => (b - b'a') as usize + 27,
+        _ => return None,
+    };
+    // Every 4 bytes are encoded as 5 characters.
+    if line.len() - 1 == length.div_ceil(4) * 5 {
+        Some(length)
+    } else {
+        None
+    }
+}
+
+fn format_binary_patch_summary(binary_patch: &BinaryPatch) -> String {
+    let format_block = |block: &BinaryPatchBlock| {
+        format!(
+            "{} {} (compressed to {})",
+            block.kind,
+            format_size(block.size),
+            format_size(block.compressed_size)
+        )
+    };
+    let mut blocks = binary_patch.blocks.iter().map(format_block);
+    match (blocks.next(), blocks.next()) {
+        (Some(forward), Some(reverse)) => {
+            format!("GIT binary patch: {forward}; reverse: {reverse}")
+        }
+        (Some(forward), None) => format!("GIT binary patch: {forward}"),
+        _ => "GIT binary patch".to_string(),
+    }
+}
+
+fn format_size(size: usize) -> String {
+    match size {
+        1 => "1 byte".to_string(),
+        _ => format!("{size} bytes"),
+    }
+}
+
+/// Describe a blob by its size and, from its first bytes, its type.
+fn describe_blob(blob: Option<&(usize, Vec<u8>)>) -> String {
+    match blob {
+        Some((size, bytes)) => format!("{}, {}", format_size(*size), guess_binary_type(bytes)),
+        None => "none".to_string(),
+    }
+}
+
+/// Guess the type of a binary file from its magic number.
+fn guess_binary_type(bytes: &[u8]) -> &'static str {
+    MAGIC_NUMBERS
+        .iter()
+        .find(|(magic_number, _)| bytes.starts_with(magic_number))
+        .map(|(_, binary_type)| *binary_type)
+        .unwrap_or("data")
+}
+
+/// Return the rows of a side-by-side hexdump of the two versions of a file, which contain
+/// differing bytes. Non-contiguous rows are separated by a "⋮" line.
+fn get_hexdump_diff_lines(minus_bytes: &[u8], plus_bytes: &[u8], config: &Config) -> Vec<String> {
+    let n_rows = minus_bytes
+        .len()
+        .max(plus_bytes.len())
+        .div_ceil(HEXDUMP_ROW_LENGTH);
+    let differing_rows: Vec<usize> = (0..n_rows)
+        .filter(|row| {
+            (row * HEXDUMP_ROW_LENGTH..(row + 1) * HEXDUMP_ROW_LENGTH)
+                .any(|i| minus_bytes.get(i) != plus_bytes.get(i))
+        })
+        .collect();
+    let mut lines = Vec::new();
+    let mut previous_row = None;
+    for &row in differing_rows.iter().take(HEXDUMP_MAX_ROWS) {
+        if matches!(previous_row, Some(previous_row) if previous_row + 1 != row) {
+            lines.push("⋮".to_string());
+        }
+        lines.push(format!(
+            "{:08x} │ {} │ {}",
+            row * HEXDUMP_ROW_LENGTH,
+            format_hexdump_row(minus_bytes, plus_bytes, row, config.minus_emph_style),
+            format_hexdump_row(plus_bytes, minus_bytes, row, config.plus_emph_style),
+        ));
+        previous_row = Some(row);
+    }
+    if differing_rows.len() > HEXDUMP_MAX_ROWS {
+        lines.push(format!(
+            "⋮ {} more rows with differences",
+            differing_rows.len() - HEXDUMP_MAX_ROWS
+        ));
+    }
+    lines
+}
+
+/// Format a row of the hexdump of `bytes`, as hex and as text, with the bytes which differ from
+/// those of `other_bytes` painted in `emph_style`.
+fn format_hexdump_row(bytes: &[u8], other_bytes: &[u8], row: usize, emph_style: Style) -> String {
+    let mut hex = Vec::new();
+    let mut text = String::new();
+    for i in row * HEXDUMP_ROW_LENGTH..(row + 1) * HEXDUMP_ROW_LENGTH {
+        let (byte_hex, byte_text) = match bytes.get(i) {
+            Some(b) if b.is_ascii_graphic() || *b == b' ' => (format!("{b:02x}"), *b as char),
+            Some(b) => (format!("{b:02x}"), '.'),
+            None => ("  ".to_string(), ' '),
+        };
+        if bytes.get(i).is_some() && bytes.get(i) != other_bytes.get(i) {
+            hex.push(emph_style.paint(byte_hex).to_string());
+            text.push_str(&emph_style.paint(byte_text.to_string()).to_string());
+        } else {
+            hex.push(byte_hex);
+            text.push(byte_text);
+        }
+    }
+    format!("{}  {}", hex.join(" "), text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ansi::strip_ansi_codes;
+    use crate::tests::integration_test_utils::{self, DeltaTest};
+
+    #[test]
+    fn test_get_base85_line_length() {
+        for (line, expected) in [
+            ("LcmeZB;9>v(0BZmR", Some(12)),
+            ("HcmV?d00001", Some(8)),
+            ("Hcm", None),
+            ("", None),
+            ("literal 4", None),
+        ] {
+            assert_eq!(get_base85_line_length(line), expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_guess_binary_type() {
+        assert_eq!(guess_binary_type(b"\x89PNG\r\n\x1a\n\0\0"), "PNG image");
+        assert_eq!(guess_binary_type(b"%PDF-1.7"), "PDF document");
+        assert_eq!(guess_binary_type(b"\x48\x00\x49\x00"), "data");
+        assert_eq!(describe_blob(Some(&(1, b"\x48".to_vec()))), "1 byte, data");
+        assert_eq!(describe_blob(None), "none");
+    }
+
+    #[test]
+    fn test_get_hexdump_diff_lines() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let minus_bytes = [b"0123456789abcdef".as_slice(), &[0; 16], b"xyz"].concat();
+        let plus_bytes = [b"0123456789abcdeF".as_slice(), &[0; 16], b"xyZ!"].concat();
+        let lines: Vec<String> = get_hexdump_diff_lines(&minus_bytes, &plus_bytes, &config)
+            .iter()
+            .map(|line| strip_ansi_codes(line))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "00000008 │ 38 39 61 62 63 64 65 66  89abcdef │ 38 39 61 62 63 64 65 46  89abcdeF",
+                "⋮",
+                "00000020 │ 78 79 7a                 xyz      │ 78 79 5a 21              xyZ!    ",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_binary_patch() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_DIFF_BINARY)
+            .expect_after_skip(
+                0,
+                "
+#indent_mark
+
+foo
+────────────────────────────────────────────────────────────────────────────────
+GIT binary patch: literal 4 bytes (compressed to 12 bytes); reverse: literal 4 bytes (compressed to 12 bytes)
+
+added: bar
+────────────────────────────────────────────────────────────────────────────────
+GIT binary patch: literal 3 bytes (compressed to 11 bytes); reverse: literal 0 bytes (compressed to 8 bytes)
+",
+            );
+    }
+
+    #[test]
+    fn test_binary_patch_color_only() {
+        DeltaTest::with_args(&["--color-only"])
+            .with_input(GIT_DIFF_BINARY)
+            .expect_contains("GIT binary patch\nliteral 4\nLcmeZB;9>v(0BZmR\n");
+    }
+
+    const GIT_DIFF_BINARY: &str = "\
+diff --git a/foo b/foo
+index 8c6d7d4..1ce8ac2 100644
+GIT binary patch
+literal 4
+LcmeZB;9>v(0BZmR
+
+literal 4
+LcmeZB;9>v(0BZmR
+
+diff --git a/bar b/bar
+new file mode 100644
+index 0000000..4e8e6ff
+GIT binary patch
+literal 3
+KcmZQzVE_OH00IC2
+
+literal 0
+HcmV?d00001
+
+";
+}
@@ -1,7 +1,9 @@
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, FixedOffset, TimeZone};
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 
 use crate::ansi::measure_text_width;
 use crate::color;
@@ -11,6 +13,7 @@ use crate::delta::{self, State, StateMachine};
 use crate::fatal;
 use crate::format::{self, FormatStringSimple, Placeholder};
 use crate::format::{make_placeholder_regex, parse_line_number_format};
+use crate::handlers::diff_header;
 use crate::paint::{self, BgShouldFill, StyleSectionSpecifier};
 use crate::style::Style;
 use crate::utils;
@@ -40,63 +43,168 @@ impl<'a> StateMachine<'a> {
         if try_parse {
             let line = self.line.to_owned();
             if let Some(blame) = parse_git_blame_line(&line, &self.config.blame_timestamp_format) {
-                // Format blame metadata
-                let format_data = format::parse_line_number_format(
-                    &self.config.blame_format,
-                    &BLAME_PLACEHOLDER_REGEX,
-                    false,
-                );
-                let mut formatted_blame_metadata =
-                    format_blame_metadata(&format_data, &blame, self.config);
-                let key = formatted_blame_metadata.clone();
-                let is_repeat = previous_key.as_deref() == Some(&key);
-                if is_repeat {
-                    formatted_blame_metadata =
-                        " ".repeat(measure_text_width(&formatted_blame_metadata))
-                };
-                let metadata_style =
-                    self.blame_metadata_style(&key, previous_key.as_deref(), is_repeat);
-                let code_style = self.config.blame_code_style.unwrap_or(metadata_style);
-                let separator_style = self.config.blame_separator_style.unwrap_or(code_style);
-
-                let (nr_prefix, line_number, nr_suffix) = format_blame_line_number(
-                    &self.config.blame_separator_format,
-                    blame.line_number,
-                    is_repeat,
-                );
-
-                write!(
-                    self.painter.writer,
-                    "{}{}{}{}",
-                    metadata_style.paint(&formatted_blame_metadata),
-                    separator_style.paint(nr_prefix),
-                    metadata_style.paint(&line_number),
-                    separator_style.paint(nr_suffix),
-                )?;
-
-                // Emit syntax-highlighted code
-                if matches!(self.state, State::Unknown) {
-                    if let Some(lang) = utils::process::git_blame_filename_extension()
-                        .as_ref()
-                        .or(self.config.default_language.as_ref())
-                    {
-                        self.painter.set_syntax(Some(lang));
-                        self.painter.set_highlighter();
-                    }
-                }
-                self.state = State::Blame(key);
-                self.painter.syntax_highlight_and_paint_line(
-                    &format!("{}\n", blame.code),
-                    StyleSectionSpecifier::Style(code_style),
-                    self.state.clone(),
-                    BgShouldFill::default(),
-                );
+                self.write_blame_line(&blame, previous_key)?;
                 handled_line = true
+            } else {
+                handled_line = self.handle_blame_porcelain_line(previous_key)?;
             }
         }
         Ok(handled_line)
     }
 
+    /// Handle a line of `git blame --porcelain` or `git blame --line-porcelain` output. The
+    /// header lines describing a line of code are recorded, and the line of code is written like a
+    /// line of `git blame` output.
+    fn handle_blame_porcelain_line(
+        &mut self,
+        previous_key: Option<String>,
+    ) -> std::io::Result<bool> {
+        if let Some(caps) = BLAME_PORCELAIN_HEADER_LINE_REGEX.captures(&self.line) {
+            self.blame_porcelain.line = Some(BlamePorcelainLine {
+                commit: caps[1].to_string(),
+                origin_line_number: caps[2].parse().unwrap_or(0),
+                line_number: caps[3].parse().unwrap_or(0),
+            });
+            return Ok(true);
+        }
+        let porcelain_line = match self.blame_porcelain.line.take() {
+            Some(porcelain_line) => porcelain_line,
+            None => return Ok(false),
+        };
+        if let Some(code) = self.line.strip_prefix('\t') {
+            let code = code.to_string();
+            let commit = self
+                .blame_porcelain
+                .commits
+                .get(&porcelain_line.commit)
+                .cloned()
+                .unwrap_or_default();
+            // Abbreviate the commit hash as `git blame` does, the boundary marker taking the
+            // place of a digit.
+            let abbreviated_commit = if commit.is_boundary {
+                format!("^{}", &porcelain_line.commit[..7])
+            } else {
+                porcelain_line.commit[..8].to_string()
+            };
+            let blame = BlameLine {
+                commit: &abbreviated_commit,
+                author: &commit.author,
+                time: commit.time(),
+                line_number: porcelain_line.line_number,
+                code: &code,
+                summary: Some(&commit.summary),
+                email: Some(&commit.email),
+                filename: Some(&commit.filename),
+                origin_file: Some(&commit.filename),
+                origin_line_number: Some(porcelain_line.origin_line_number),
+            };
+            self.write_blame_line(&blame, previous_key)?;
+        } else {
+            let commit = self
+                .blame_porcelain
+                .commits
+                .entry(porcelain_line.commit.clone())
+                .or_default();
+            let (key, value) = self.line.split_once(' ').unwrap_or((&self.line, ""));
+            match key {
+                "author" => commit.author = value.to_string(),
+                "author-mail" => {
+                    commit.email = value
+                        .trim_start_matches('<')
+                        .trim_end_matches('>')
+                        .to_string()
+                }
+                "author-time" => commit.timestamp = value.parse().unwrap_or(0),
+                "author-tz" => commit.timezone = value.to_string(),
+                "summary" => commit.summary = value.to_string(),
+                "filename" => commit.filename = value.to_string(),
+                "boundary" => commit.is_boundary = true,
+                _ => {}
+            }
+            self.blame_porcelain.line = Some(porcelain_line);
+        }
+        Ok(true)
+    }
+
+    fn write_blame_line(
+        &mut self,
+        blame: &BlameLine,
+        previous_key: Option<String>,
+    ) -> std::io::Result<()> {
+        // Format blame metadata
+        let format_data = format::parse_line_number_format(
+            &self.config.blame_format,
+            &BLAME_PLACEHOLDER_REGEX,
+            false,
+        );
+        let mut formatted_blame_metadata = format_blame_metadata(&format_data, blame, self.config);
+        if matches!(self.state, State::Unknown) && blame.origin_file.is_some() {
+            self.blame_filename = utils::process::git_blame_filename();
+        }
+        let moved_from = blame
+            .origin_file
+            .filter(|origin_file| match &self.blame_filename {
+                Some(blame_filename) => !is_same_file(origin_file, blame_filename),
+                None => false,
+            });
+        // A moved line does not continue a block of lines which were not moved.
+        let mut key = formatted_blame_metadata.clone();
+        if let Some(origin_file) = moved_from {
+            key.push_str(origin_file);
+        }
+        let is_repeat = previous_key.as_deref() == Some(&key);
+        if is_repeat {
+            formatted_blame_metadata = " ".repeat(measure_text_width(&formatted_blame_metadata))
+        };
+        let mut metadata_style =
+            self.blame_metadata_style(&key, previous_key.as_deref(), is_repeat);
+        let code_style = self.config.blame_code_style.unwrap_or(metadata_style);
+        let separator_style = self.config.blame_separator_style.unwrap_or(code_style);
+        if moved_from.is_some() {
+            metadata_style = blame_moved_style(metadata_style, self.config);
+        }
+
+        let (nr_prefix, line_number, nr_suffix) = format_blame_line_number(
+            &self.config.blame_separator_format,
+            blame.line_number,
+            is_repeat,
+        );
+
+        write!(
+            self.painter.writer,
+            "{}{}{}{}",
+            metadata_style.paint(&formatted_blame_metadata),
+            separator_style.paint(nr_prefix),
+            metadata_style.paint(&line_number),
+            separator_style.paint(nr_suffix),
+        )?;
+
+        // Emit syntax-highlighted code
+        if matches!(self.state, State::Unknown) {
+            if let Some(lang) = utils::process::git_blame_filename_extension()
+                .or_else(|| {
+                    blame
+                        .filename
+                        .or(blame.origin_file)
+                        .and_then(diff_header::get_extension)
+                        .map(str::to_string)
+                })
+                .or_else(|| self.config.default_language.clone())
+            {
+                self.painter.set_syntax(Some(&lang));
+                self.painter.set_highlighter();
+            }
+        }
+        self.state = State::Blame(key);
+        self.painter.syntax_highlight_and_paint_line(
+            &format!("{}\n", blame.code),
+            StyleSectionSpecifier::Style(code_style),
+            self.state.clone(),
+            BgShouldFill::default(),
+        );
+        Ok(())
+    }
+
     fn blame_metadata_style(
         &mut self,
         key: &str,
@@ -191,6 +299,52 @@ pub struct BlameLine<'a> {
     pub time: DateTime<FixedOffset>,
     pub line_number: usize,
     pub code: &'a str,
+    // Only known for `git blame --porcelain` and `git blame --line-porcelain` input
+    pub summary: Option<&'a str>,
+    pub email: Option<&'a str>,
+    pub filename: Option<&'a str>,
+    // The file and line number that the line comes from, if given by git, e.g. with -f and -n
+    pub origin_file: Option<&'a str>,
+    pub origin_line_number: Option<usize>,
+}
+
+/// The state of `git blame --porcelain` and `git blame --line-porcelain` handling. With
+/// `--porcelain` the information about a commit is only given for the first line attributed to it,
+/// so it is kept for subsequent lines.
+#[derive(Debug, Default)]
+pub struct BlamePorcelain {
+    commits: HashMap<String, BlameCommit>,
+    line: Option<BlamePorcelainLine>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct BlameCommit {
+    author: String,
+    email: String,
+    timestamp: i64,
+    timezone: String, // E.g. "-0700"
+    summary: String,
+    filename: String,
+    is_boundary: bool,
+}
+
+impl BlameCommit {
+    fn time(&self) -> DateTime<FixedOffset> {
+        let offset = DateTime::parse_from_str(&format!("0 {}", self.timezone), "%s %z")
+            .map(|time| *time.offset())
+            .unwrap_or_else(|_| FixedOffset::east_opt(0).unwrap());
+        offset
+            .timestamp_opt(self.timestamp, 0)
+            .single()
+            .unwrap_or_else(|| offset.timestamp_opt(0, 0).unwrap())
+    }
+}
+
+#[derive(Debug)]
+struct BlamePorcelainLine {
+    commit: String,
+    origin_line_number: usize,
+    line_number: usize,
 }
 
 // E.g.
@@ -203,8 +357,15 @@ lazy_static! {
 (
     \^?[0-9a-f]{4,40} # commit hash (^ is 'boundary commit' marker)
 )
-(?: [^(]+)?        # optional file name (unused; present if file has been renamed; TODO: inefficient?)
-[\ ]
+(?:                # optional original file name, present with -f, or if a line comes from
+    [\ ]            # another file (-M, -C) or the file has been renamed
+    ([^(]*?[^(\ ])
+)??
+(?:                # optional original line number, present with -n
+    [\ ]+
+    ([0-9]+)
+)?
+[\ ]+
 \(                 # open ( which the previous file name may not contain in case a name does (which is more likely)
 (
     [^\ ].*[^\ ]   # author name
@@ -225,20 +386,27 @@ $
 "
     )
     .unwrap();
+
+    // E.g. "ea82f2d0c4ff6a5e1b0a6b5a8f6b4e8d1b5b9a6c 120 120 3", which is followed by the header
+    // lines of the commit, if not yet given, and the line of code, prefixed by a tab.
+    static ref BLAME_PORCELAIN_HEADER_LINE_REGEX: Regex =
+        Regex::new(r"^([0-9a-f]{40}) (\d+) (\d+)(?: \d+)?$").unwrap();
 }
 
 pub fn parse_git_blame_line<'a>(line: &'a str, timestamp_format: &str) -> Option<BlameLine<'a>> {
     let caps = BLAME_LINE_REGEX.captures(line)?;
 
     let commit = caps.get(1).unwrap().as_str();
-    let author = caps.get(2).unwrap().as_str();
-    let timestamp = caps.get(3).unwrap().as_str();
+    let origin_file = caps.get(2).map(|m| m.as_str());
+    let origin_line_number = caps.get(3).and_then(|m| m.as_str().parse::<usize>().ok());
+    let author = caps.get(4).unwrap().as_str();
+    let timestamp = caps.get(5).unwrap().as_str();
 
     let time = DateTime::parse_from_str(timestamp, timestamp_format).ok()?;
 
-    let line_number = caps.get(4).unwrap().as_str().parse::<usize>().ok()?;
+    let line_number = caps.get(6).unwrap().as_str().parse::<usize>().ok()?;
 
-    let code = caps.get(5).unwrap().as_str();
+    let code = caps.get(7).unwrap().as_str();
 
     Some(BlameLine {
         commit,
@@ -246,13 +414,27 @@ pub fn parse_git_blame_line<'a>(line: &'a str, timestamp_format: &str) -> Option
         time,
         line_number,
         code,
+        summary: None,
+        email: None,
+        filename: None,
+        origin_file,
+        origin_line_number,
     })
 }
 
 lazy_static! {
     // line numbers (`{n}`) change with every line and are set separately via `blame-separator-format`
     pub static ref BLAME_PLACEHOLDER_REGEX: Regex =
-        format::make_placeholder_regex(&["timestamp", "author", "commit"]);
+        format::make_placeholder_regex(&[
+            "timestamp",
+            "author",
+            "commit",
+            "summary",
+            "email",
+            "filename",
+            "origin-file",
+            "origin-line",
+        ]);
 }
 
 pub fn format_blame_metadata(
@@ -277,6 +459,17 @@ pub fn format_blame_metadata(
             }
             Some(Placeholder::Str("author")) => Some(Cow::from(blame.author)),
             Some(Placeholder::Str("commit")) => Some(delta::format_raw_line(blame.commit, config)),
+            Some(Placeholder::Str("summary")) => Some(Cow::from(blame.summary.unwrap_or(""))),
+            Some(Placeholder::Str("email")) => Some(Cow::from(blame.email.unwrap_or(""))),
+            Some(Placeholder::Str("filename")) => Some(Cow::from(blame.filename.unwrap_or(""))),
+            Some(Placeholder::Str("origin-file")) => {
+                Some(Cow::from(blame.origin_file.unwrap_or("")))
+            }
+            Some(Placeholder::Str("origin-line")) => Some(Cow::from(
+                blame
+                    .origin_line_number
+                    .map_or_else(String::new, |n| n.to_string()),
+            )),
             None => None,
             _ => unreachable!("Unexpected `git blame` input"),
         };
@@ -294,6 +487,38 @@ pub fn format_blame_metadata(
     s
 }
 
+/// Return the style of the metadata of a line moved or copied from another file, given the style
+/// it would have otherwise.
+fn blame_moved_style(metadata_style: Style, config: &config::Config) -> Style {
+    match config.blame_moved_style {
+        Some(mut style) => {
+            if style.ansi_term_style.background.is_none() {
+                style.ansi_term_style.background = metadata_style.ansi_term_style.background;
+            }
+            style
+        }
+        None => {
+            let mut style = metadata_style;
+            style.ansi_term_style.is_italic = true;
+            style
+        }
+    }
+}
+
+/// Do the two paths name the same file? The file being blamed may be given relative to the
+/// current directory, whereas git gives the original file relative to the repository root.
+fn is_same_file(origin_file: &str, blame_filename: &str) -> bool {
+    // Keep the components following any "." or "..".
+    let mut components: Vec<_> = Path::new(blame_filename)
+        .components()
+        .rev()
+        .take_while(|component| matches!(component, Component::Normal(_)))
+        .collect();
+    components.reverse();
+    !components.is_empty()
+        && Path::new(origin_file).ends_with(components.iter().collect::<PathBuf>())
+}
+
 pub fn format_blame_line_number(
     format: &BlameLineNumbers,
     line_number: usize,
@@ -385,6 +610,7 @@ mod tests {
     use std::{collections::HashMap, io::Cursor};
 
     use crate::tests::integration_test_utils;
+    use crate::tests::integration_test_utils::DeltaTest;
 
     use super::*;
 
@@ -401,12 +627,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_blame_line_with_origin() {
+        for (line, expected) in [
+            (
+                "^fd6a87f a.rs  2 (Ann Other  2021-08-22 18:20:19 -0700 3)     let x = 1;",
+                (Some("a.rs"), Some(2), 3),
+            ),
+            (
+                "6ed6140a src/b c.rs   (Dan Davison 2021-08-22 18:20:19 -0700 1) fn b() {}",
+                (Some("src/b c.rs"), None, 1),
+            ),
+            (
+                "6ed6140a 12 (Dan Davison 2021-08-22 18:20:19 -0700 14) fn b() {}",
+                (None, Some(12), 14),
+            ),
+        ] {
+            let blame = parse_git_blame_line(line, "%Y-%m-%d %H:%M:%S %z").unwrap();
+            assert_eq!(
+                (
+                    blame.origin_file,
+                    blame.origin_line_number,
+                    blame.line_number
+                ),
+                expected,
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_same_file() {
+        assert!(is_same_file("src/main.rs", "src/main.rs"));
+        assert!(is_same_file("src/main.rs", "main.rs"));
+        assert!(is_same_file("src/main.rs", "./main.rs"));
+        assert!(is_same_file("src/main.rs", "../src/main.rs"));
+        assert!(!is_same_file("src/lib.rs", "src/main.rs"));
+        assert!(!is_same_file("main.rs", "src/main.rs"));
+        assert!(!is_same_file("src/main.rs", ".."));
+    }
+
+    #[test]
+    fn test_blame_moved_lines() {
+        DeltaTest::with_args(&[
+            "--blame-format",
+            "{author:<10} {origin-file:<4} {origin-line:>2}",
+            "--blame-palette",
+            "black",
+            "--blame-code-style",
+            "normal",
+            "--blame-separator-format",
+            "│",
+        ])
+        .with_calling_process("git blame -C -C -n b.rs")
+        .explain_ansi()
+        .with_input(GIT_BLAME_MOVED)
+        .expect_after_skip(
+            0,
+            "
+#indent_mark
+(normal black)Dan Davison b.rs  1(normal)│ fn b() {}
+(italic normal black)Ann Other  a.rs  2(normal)│     let x = 1;
+(italic normal black)Ann Other  a.rs  3(normal)│     let y = 2;",
+        );
+    }
+
+    #[test]
+    fn test_blame_moved_style() {
+        DeltaTest::with_args(&[
+            "--blame-format",
+            "{author:<10}",
+            "--blame-palette",
+            "black",
+            "--blame-moved-style",
+            "red",
+        ])
+        .with_calling_process("git blame -C -C -n b.rs")
+        .explain_ansi()
+        .with_input(GIT_BLAME_MOVED)
+        .expect_contains("(red black)Ann Other (normal black)│(red black)  2 ")
+        .expect_contains("(normal black)Dan Davison(normal black)│(normal black)  1 ");
+    }
+
     #[test]
     fn test_blame_line_with_parens_in_name() {
         let line =
             "61f180c8 (Kangwook Lee (이강욱) 2021-06-09 23:33:59 +0900 130)     let mut output_type =";
         let caps = BLAME_LINE_REGEX.captures(line).unwrap();
-        assert_eq!(caps.get(2).unwrap().as_str(), "Kangwook Lee (이강욱)");
+        assert_eq!(caps.get(4).unwrap().as_str(), "Kangwook Lee (이강욱)");
     }
 
     #[test]
@@ -518,6 +826,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_blame_porcelain() {
+        DeltaTest::with_args(&[
+            "--blame-format",
+            "{commit:<8} {author:<12} {timestamp:<10}",
+            "--blame-timestamp-output-format",
+            "%Y-%m-%d",
+        ])
+        .with_input(GIT_BLAME_PORCELAIN)
+        .expect_after_skip(
+            0,
+            "
+#indent_mark
+ea82f2d0 Dan Davison  2021-08-22│  1 │fn main() {
+                                │  2 │    let x = 1;
+^b2257cf Ann Other    2020-07-18│  3 │}",
+        );
+    }
+
+    #[test]
+    fn test_blame_line_porcelain_placeholders() {
+        DeltaTest::with_args(&[
+            "--blame-format",
+            "{author:<12} {email:<22} {summary:<12} {filename}",
+        ])
+        .with_input(GIT_BLAME_LINE_PORCELAIN)
+        .expect_after_skip(
+            0,
+            "
+Dan Davison  dandavison7@gmail.com  Change x     src/main.rs    │  1 │fn main() {",
+        );
+    }
+
+    #[test]
+    fn test_blame_commit_time() {
+        let commit = BlameCommit {
+            timestamp: 1629681619,
+            timezone: "-0700".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            commit.time(),
+            DateTime::parse_from_rfc3339("2021-08-22T18:20:19-07:00").unwrap()
+        );
+    }
+
     fn hashmap_items(hashmap: &HashMap<String, String>) -> Vec<(&str, &str)> {
         hashmap
             .iter()
@@ -534,6 +888,62 @@ mod tests {
             time,
             line_number: 0,
             code: "",
+            summary: None,
+            email: None,
+            filename: None,
+            origin_file: None,
+            origin_line_number: None,
         }
     }
+
+    const GIT_BLAME_MOVED: &str = "\
+6ed6140a b.rs 1 (Dan Davison 2021-08-22 18:20:19 -0700 1) fn b() {}
+^fd6a87f a.rs 2 (Ann Other   2020-07-18 15:34:43 -0400 2)     let x = 1;
+^fd6a87f a.rs 3 (Ann Other   2020-07-18 15:34:43 -0400 3)     let y = 2;
+";
+
+    const GIT_BLAME_PORCELAIN: &str = "\
+ea82f2d0c4ff6a5e1b0a6b5a8f6b4e8d1b5b9a6c 1 1 2
+author Dan Davison
+author-mail <dandavison7@gmail.com>
+author-time 1629681619
+author-tz -0700
+committer Dan Davison
+committer-mail <dandavison7@gmail.com>
+committer-time 1629681619
+committer-tz -0700
+summary Change x
+filename src/main.rs
+\tfn main() {
+ea82f2d0c4ff6a5e1b0a6b5a8f6b4e8d1b5b9a6c 2 2
+\t    let x = 1;
+b2257cfa3e2c07b1d2a0f2e5f7c9b5d8a1e4c3f6 3 3 1
+author Ann Other
+author-mail <ann@example.com>
+author-time 1595100883
+author-tz -0400
+committer Ann Other
+committer-mail <ann@example.com>
+committer-time 1595100883
+committer-tz -0400
+summary Initial commit
+boundary
+filename src/main.rs
+\t}
+";
+
+    const GIT_BLAME_LINE_PORCELAIN: &str = "\
+ea82f2d0c4ff6a5e1b0a6b5a8f6b4e8d1b5b9a6c 1 1 1
+author Dan Davison
+author-mail <dandavison7@gmail.com>
+author-time 1629681619
+author-tz -0700
+committer Dan Davison
+committer-mail <dandavison7@gmail.com>
+committer-time 1629681619
+committer-tz -0700
+summary Change x
+filename src/main.rs
+\tfn main() {
+";
 }
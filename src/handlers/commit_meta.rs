@@ -1,8 +1,26 @@
 use std::borrow::Cow;
 
+use chrono::{DateTime, FixedOffset};
+use lazy_static::lazy_static;
+use regex::Regex;
+
 use super::draw;
+use crate::ansi;
+use crate::config::Config;
 use crate::delta::{State, StateMachine};
 use crate::features;
+use crate::style::Style;
+
+lazy_static! {
+    // E.g. "Author: Dan Davison <dandavison7@gmail.com>" and "Date:   Sat Dec 11 17:08:56 2021 -0500"
+    static ref COMMIT_META_FIELD_REGEX: Regex =
+        Regex::new(r"^(Author|AuthorDate|Commit|CommitDate|Date):\s+(.+)$").unwrap();
+    // E.g. "    Signed-off-by: Dan Davison <dandavison7@gmail.com>"
+    static ref COMMIT_TRAILER_REGEX: Regex =
+        Regex::new(r"(?i)^    (?:(?:[a-z]+-)+by|cc|change-id|closes|fixes|link): \S").unwrap();
+    // E.g. " (HEAD -> main, origin/main, tag: v1)" at the end of the commit line.
+    static ref COMMIT_REF_NAMES_REGEX: Regex = Regex::new(r" \(([^()]+)\)$").unwrap();
+}
 
 impl<'a> StateMachine<'a> {
     #[inline]
@@ -18,7 +36,7 @@ impl<'a> StateMachine<'a> {
         self.painter.paint_buffered_minus_and_plus_lines();
         self.handle_pending_line_with_diff_name()?;
         self.state = State::CommitMeta;
-        if self.should_handle() {
+        if self.should_handle() || self.should_paint_commit_line_ref_names() {
             self.painter.emit()?;
             self._handle_commit_meta_header_line()?;
             handled_line = true
@@ -26,7 +44,7 @@ impl<'a> StateMachine<'a> {
         Ok(handled_line)
     }
 
-    fn _handle_commit_meta_header_line(&mut self) -> std::io::Result<()> {
+    pub fn _handle_commit_meta_header_line(&mut self) -> std::io::Result<()> {
         if self.config.commit_style.is_omitted {
             return Ok(());
         }
@@ -46,6 +64,17 @@ impl<'a> StateMachine<'a> {
         } else {
             (Cow::from(&self.line), Cow::from(&self.raw_line))
         };
+        let (text_style, formatted_raw_line) =
+            match self.paint_commit_line_ref_names(&formatted_line, &formatted_raw_line) {
+                Some(painted_line) => (
+                    Style {
+                        is_raw: true,
+                        ..self.config.commit_style
+                    },
+                    Cow::from(painted_line),
+                ),
+                None => (self.config.commit_style, formatted_raw_line),
+            };
 
         draw_fn(
             self.painter.writer,
@@ -53,9 +82,253 @@ impl<'a> StateMachine<'a> {
             &format!("{}{}", formatted_raw_line, if pad { " " } else { "" }),
             "",
             &self.config.decorations_width,
-            self.config.commit_style,
+            text_style,
             decoration_ansi_term_style,
         )?;
         Ok(())
     }
+
+    fn should_paint_commit_line_ref_names(&self) -> bool {
+        let config = self.config;
+        !(config.commit_branch_style.is_raw
+            && config.commit_remote_style.is_raw
+            && config.commit_tag_style.is_raw)
+    }
+
+    /// Paint the ref names ending the commit line, e.g. "(HEAD -> main, origin/main, tag: v1)", in
+    /// commit-branch-style, commit-remote-style and commit-tag-style, and the rest of the line in
+    /// commit-style. `line` and `raw_line` are the commit line, possibly with hyperlinks.
+    fn paint_commit_line_ref_names(&self, line: &str, raw_line: &str) -> Option<String> {
+        let config = self.config;
+        if !self.should_paint_commit_line_ref_names() {
+            return None;
+        }
+        let ref_names = COMMIT_REF_NAMES_REGEX.captures(&self.line)?.get(1)?;
+        // The text of the commit line before " (".
+        let prefix_len = ref_names.start() - 2;
+        let other_style = if config.commit_style.is_raw {
+            Style::new()
+        } else {
+            config.commit_style
+        };
+        let mut painted_line = if config.commit_style.is_raw {
+            let end = ansi::ansi_preserving_index(raw_line, prefix_len)?;
+            format!("{}\x1b[0m", &raw_line[..end])
+        } else {
+            let end = ansi::ansi_preserving_index(line, prefix_len)?;
+            other_style.paint(&line[..end]).to_string()
+        };
+        painted_line.push_str(&other_style.paint(" (").to_string());
+        for (i, ref_name) in ref_names.as_str().split(", ").enumerate() {
+            if i > 0 {
+                painted_line.push_str(&other_style.paint(", ").to_string());
+            }
+            // E.g. "HEAD -> main"
+            if let Some((head, branch)) = ref_name.split_once(" -> ") {
+                painted_line.push_str(&paint_ref_name(head, other_style, config));
+                painted_line.push_str(&other_style.paint(" -> ").to_string());
+                painted_line.push_str(&paint_ref_name(branch, other_style, config));
+            } else {
+                painted_line.push_str(&paint_ref_name(ref_name, other_style, config));
+            }
+        }
+        painted_line.push_str(&other_style.paint(")").to_string());
+        Some(painted_line)
+    }
+
+    #[inline]
+    fn test_commit_meta_line(&self) -> bool {
+        self.state == State::CommitMeta
+    }
+
+    /// Handle a line following the commit line, painting the author and date of the commit and the
+    /// trailers of its message.
+    pub fn handle_commit_meta_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_commit_meta_line() {
+            return Ok(false);
+        }
+        let line = if let Some(value) = COMMIT_META_FIELD_REGEX
+            .captures(&self.line)
+            .and_then(|caps| caps.get(2))
+        {
+            let (style, formatted_value) =
+                if self.line.starts_with("Author") || self.line.starts_with("Commit:") {
+                    (self.config.commit_author_style, None)
+                } else {
+                    (
+                        self.config.commit_date_style,
+                        format_commit_date(value.as_str(), self.config),
+                    )
+                };
+            if style.is_raw && formatted_value.is_none() {
+                return Ok(false);
+            }
+            format!(
+                "{}{}",
+                &self.line[..value.start()],
+                style.paint(formatted_value.as_deref().unwrap_or(value.as_str()))
+            )
+        } else if COMMIT_TRAILER_REGEX.is_match(&self.line)
+            && !self.config.commit_trailer_style.is_raw
+        {
+            format!(
+                "    {}",
+                self.config.commit_trailer_style.paint(&self.line[4..])
+            )
+        } else {
+            return Ok(false);
+        };
+        self.painter.emit()?;
+        writeln!(self.painter.writer, "{line}")?;
+        Ok(true)
+    }
+}
+
+/// Paint a ref name of the commit line in the style of its type, or in `other_style` if that is
+/// raw.
+fn paint_ref_name(ref_name: &str, other_style: Style, config: &Config) -> String {
+    let style = if ref_name.starts_with("tag: ") {
+        config.commit_tag_style
+    } else if is_remote_ref_name(ref_name, config) {
+        config.commit_remote_style
+    } else {
+        config.commit_branch_style
+    };
+    if style.is_raw {
+        other_style.paint(ref_name).to_string()
+    } else {
+        style.paint(ref_name).to_string()
+    }
+}
+
+/// Is this ref name a remote-tracking branch? Without a repository, ref names containing a slash
+/// are assumed to be.
+fn is_remote_ref_name(ref_name: &str, config: &Config) -> bool {
+    if config.git_remote_names.is_empty() {
+        return ref_name.contains('/');
+    }
+    config
+        .git_remote_names
+        .iter()
+        .any(|remote| ref_name.starts_with(&format!("{remote}/")))
+}
+
+/// Re-render a date output by git log in commit-date-format, if that is set.
+fn format_commit_date(date: &str, config: &Config) -> Option<String> {
+    let format = config.commit_date_format.as_deref()?;
+    let date = parse_commit_date(date)?;
+    if format == "relative" {
+        Some(chrono_humanize::HumanTime::from(date).to_string())
+    } else {
+        Some(date.format(format).to_string())
+    }
+}
+
+/// Parse a date in one of the formats of git log --date=default, iso, rfc or iso-strict.
+fn parse_commit_date(date: &str) -> Option<DateTime<FixedOffset>> {
+    ["%a %b %e %H:%M:%S %Y %z", "%Y-%m-%d %H:%M:%S %z"]
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(date, format).ok())
+        .or_else(|| DateTime::parse_from_rfc2822(date).ok())
+        .or_else(|| DateTime::parse_from_rfc3339(date).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::{make_config_from_args, DeltaTest};
+
+    #[test]
+    fn test_parse_commit_date() {
+        for date in [
+            "Sat Dec 11 17:08:56 2021 -0500",
+            "2021-12-11 17:08:56 -0500",
+            "Sat, 11 Dec 2021 17:08:56 -0500",
+            "2021-12-11T17:08:56-05:00",
+        ] {
+            assert_eq!(
+                parse_commit_date(date).map(|date| date.timestamp()),
+                Some(1639260536),
+                "{date}"
+            );
+        }
+        assert_eq!(
+            parse_commit_date("Thu Jan 5 10:00:00 2023 +0100").map(|date| date.to_rfc3339()),
+            Some("2023-01-05T10:00:00+01:00".to_string())
+        );
+        assert_eq!(parse_commit_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_is_remote_ref_name() {
+        let mut config = make_config_from_args(&[]);
+        assert!(is_remote_ref_name("origin/main", &config));
+        config.git_remote_names = vec!["upstream".to_string()];
+        assert!(is_remote_ref_name("upstream/main", &config));
+        assert!(!is_remote_ref_name("feature/x", &config));
+    }
+
+    #[test]
+    fn test_commit_meta_styles() {
+        DeltaTest::with_args(&[
+            "--commit-style",
+            "yellow",
+            "--commit-branch-style",
+            "green",
+            "--commit-remote-style",
+            "red",
+            "--commit-tag-style",
+            "blue",
+            "--commit-author-style",
+            "cyan",
+            "--commit-date-style",
+            "magenta",
+            "--commit-date-format",
+            "%Y-%m-%d",
+            "--commit-trailer-style",
+            "bold",
+        ])
+        .explain_ansi()
+        .with_input(GIT_LOG)
+        .expect_after_skip(
+            0,
+            "
+#indent_mark
+(yellow)commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e(yellow) ((green)HEAD(yellow) -> (green)main(yellow), (red)origin/main(yellow), (blue)tag: v1(yellow))(normal)
+Author: (cyan)Dan Davison <dandavison7@gmail.com>(normal)
+Date:   (purple)2021-12-11(normal)
+
+    Change x to 2.
+
+    (bold normal)Signed-off-by: Dan Davison <dandavison7@gmail.com>(normal)
+    Note: not a trailer
+",
+        );
+    }
+
+    #[test]
+    fn test_commit_meta_styles_raw() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_LOG)
+            .expect_contains("commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e (HEAD -> main, origin/main, tag: v1)\nAuthor: Dan Davison <dandavison7@gmail.com>\nDate:   Sat Dec 11 17:08:56 2021 -0500\n");
+    }
+
+    #[test]
+    fn test_commit_date_relative() {
+        DeltaTest::with_args(&["--commit-date-format", "relative"])
+            .with_input(GIT_LOG)
+            .expect_contains("Date:   ")
+            .expect_contains(" years ago\n");
+    }
+
+    const GIT_LOG: &str = "\
+commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e (HEAD -> main, origin/main, tag: v1)
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Sat Dec 11 17:08:56 2021 -0500
+
+    Change x to 2.
+
+    Signed-off-by: Dan Davison <dandavison7@gmail.com>
+    Note: not a trailer
+";
 }
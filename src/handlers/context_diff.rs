@@ -0,0 +1,297 @@
+// A module for handling context format diffs, as output by `diff -c`.
+//
+// A context diff hunk lists the old and the new lines of the hunk in two separate sections:
+// ```
+// ***************
+// *** 1,4 ****
+//   fn main() {
+// !     let x = 1;
+// -     let y = 2;
+//   }
+// --- 1,4 ----
+//   fn main() {
+// !     let x = 3;
+// +     let z = 4;
+//   }
+// ```
+// A section is omitted when it contains no changed lines. The hunk is buffered until it ends, and
+// is then converted to the equivalent unified diff hunk, which is handled like any other. The file
+// header lines `*** old` and `--- new` are handled as `--- old` and `+++ new`.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::delta::{Source, StateMachine};
+
+#[derive(Debug)]
+pub enum ContextDiff {
+    FileHeader, // After the `*** old` line
+    Hunk(ContextDiffHunk),
+}
+
+#[derive(Debug, Default)]
+pub struct ContextDiffHunk {
+    minus_start: usize,
+    plus_start: usize,
+    minus_lines: Vec<String>,
+    plus_lines: Vec<String>,
+    in_plus_section: bool,
+}
+
+lazy_static! {
+    // E.g. "*** 1,4 ****" and "--- 1,4 ----"
+    static ref CONTEXT_DIFF_RANGE_REGEX: Regex =
+        Regex::new(r"^(?:\*\*\* (\d+)(?:,\d+)? \*\*\*\*|--- (\d+)(?:,\d+)? ----)$").unwrap();
+}
+
+const CONTEXT_DIFF_HUNK_SEPARATOR: &str = "***************";
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_context_diff_line(&self) -> bool {
+        !self.config.color_only && self.source == Source::DiffUnified
+    }
+
+    /// Handle a file header line or a hunk line of `diff -c` output.
+    pub fn handle_context_diff_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_context_diff_line() {
+            return Ok(false);
+        }
+        if let Some(ContextDiff::Hunk(hunk)) = &mut self.context_diff {
+            if let Some(caps) = CONTEXT_DIFF_RANGE_REGEX.captures(&self.line) {
+                match (caps.get(1), caps.get(2)) {
+                    (Some(start), _) => hunk.minus_start = start.as_str().parse().unwrap_or(0),
+                    (_, Some(start)) => {
+                        hunk.plus_start = start.as_str().parse().unwrap_or(0);
+                        hunk.in_plus_section = true;
+                    }
+                    _ => {}
+                }
+                return Ok(true);
+            }
+            if is_context_diff_hunk_line(&self.line) {
+                if hunk.in_plus_section {
+                    hunk.plus_lines.push(self.line.clone());
+                } else {
+                    hunk.minus_lines.push(self.line.clone());
+                }
+                return Ok(true);
+            }
+            self.flush_context_diff_hunk()?;
+        }
+        if self.line.starts_with(CONTEXT_DIFF_HUNK_SEPARATOR) {
+            self.context_diff = Some(ContextDiff::Hunk(ContextDiffHunk::default()));
+            return Ok(true);
+        }
+        if let Some(path) = self.line.strip_prefix("*** ") {
+            let line = format!("--- {path}");
+            self.context_diff = Some(ContextDiff::FileHeader);
+            return self.handle_unified_line(line, Self::handle_diff_header_minus_line);
+        }
+        if let Some(ContextDiff::FileHeader) = self.context_diff {
+            self.context_diff = None;
+            if let Some(path) = self.line.strip_prefix("--- ") {
+                let line = format!("+++ {path}");
+                return self.handle_unified_line(line, Self::handle_diff_header_plus_line);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Handle the buffered context diff hunk, if any, as a unified diff hunk.
+    pub fn flush_context_diff_hunk(&mut self) -> std::io::Result<()> {
+        if let Some(ContextDiff::Hunk(hunk)) = self.context_diff.take() {
+            let line = std::mem::take(&mut self.line);
+            let raw_line = std::mem::take(&mut self.raw_line);
+            let (hunk_header, hunk_lines) = hunk.to_unified();
+            self.handle_unified_line(hunk_header, Self::handle_hunk_header_line)?;
+            for hunk_line in hunk_lines {
+                self.handle_unified_line(hunk_line, Self::handle_hunk_line)?;
+            }
+            self.line = line;
+            self.raw_line = raw_line;
+        }
+        Ok(())
+    }
+
+    /// Handle `line`, the unified diff equivalent of the current line, using `handle`.
+    pub fn handle_unified_line(
+        &mut self,
+        line: String,
+        handle: fn(&mut Self) -> std::io::Result<bool>,
+    ) -> std::io::Result<bool> {
+        self.raw_line = line.clone();
+        self.line = line;
+        handle(self)?;
+        Ok(true)
+    }
+}
+
+impl ContextDiffHunk {
+    /// Return the header and the lines of the equivalent unified diff hunk.
+    fn to_unified(&self) -> (String, Vec<String>) {
+        let lines: Vec<String> = if self.minus_lines.is_empty() {
+            self.plus_lines
+                .iter()
+                .map(|line| get_unified_line(line, '+'))
+                .collect()
+        } else if self.plus_lines.is_empty() {
+            self.minus_lines
+                .iter()
+                .map(|line| get_unified_line(line, '-'))
+                .collect()
+        } else {
+            merge_sections(&self.minus_lines, &self.plus_lines)
+        };
+        let count = |marker| {
+            lines
+                .iter()
+                .filter(|line| line.starts_with([' ', marker]))
+                .count()
+        };
+        let hunk_header = format!(
+            "@@ -{},{} +{},{} @@",
+            self.minus_start,
+            count('-'),
+            self.plus_start,
+            count('+')
+        );
+        (hunk_header, lines)
+    }
+}
+
+fn is_context_diff_hunk_line(line: &str) -> bool {
+    ["  ", "! ", "- ", "+ ", "\\"]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+/// Convert a line of a context diff section to a unified diff line, in which a changed ('!') line
+/// has the marker of its section.
+fn get_unified_line(line: &str, marker: char) -> String {
+    match line.chars().next() {
+        Some('\\') => line.to_string(),
+        Some('!') => format!("{marker}{}", &line[2..]),
+        Some(c) => format!("{c}{}", &line[2..]),
+        None => " ".to_string(),
+    }
+}
+
+/// Interleave the two sections of a hunk: between two context lines, which are common to both
+/// sections, the changed lines of the old section precede those of the new section.
+fn merge_sections(minus_lines: &[String], plus_lines: &[String]) -> Vec<String> {
+    let is_changed = |line: &&String| !line.starts_with(' ');
+    let mut minus_lines = minus_lines.iter().peekable();
+    let mut plus_lines = plus_lines.iter().peekable();
+    let mut lines = Vec::new();
+    loop {
+        while let Some(line) = minus_lines.next_if(is_changed) {
+            lines.push(get_unified_line(line, '-'));
+        }
+        while let Some(line) = plus_lines.next_if(is_changed) {
+            lines.push(get_unified_line(line, '+'));
+        }
+        match (minus_lines.next(), plus_lines.next()) {
+            (None, None) => break,
+            (_, Some(line)) | (Some(line), None) => lines.push(get_unified_line(line, ' ')),
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_context_diff_hunk_to_unified() {
+        let to_lines = |lines: &[&str]| lines.iter().map(|line| line.to_string()).collect();
+        let hunk = ContextDiffHunk {
+            minus_start: 1,
+            plus_start: 1,
+            minus_lines: to_lines(&["  a", "! b", "- c", "  d"]),
+            plus_lines: to_lines(&["  a", "! B", "  d", "+ e"]),
+            in_plus_section: true,
+        };
+        assert_eq!(
+            hunk.to_unified(),
+            (
+                "@@ -1,4 +1,4 @@".to_string(),
+                to_lines(&[" a", "-b", "-c", "+B", " d", "+e"])
+            )
+        );
+        let hunk = ContextDiffHunk {
+            minus_start: 4,
+            plus_start: 4,
+            minus_lines: vec![],
+            plus_lines: to_lines(&["  a", "+ b", "  c"]),
+            in_plus_section: true,
+        };
+        assert_eq!(
+            hunk.to_unified(),
+            ("@@ -4,2 +4,3 @@".to_string(), to_lines(&[" a", "+b", " c"]))
+        );
+    }
+
+    #[test]
+    fn test_context_diff() {
+        DeltaTest::with_args(&["--file-modified-label", "comparing:"])
+            .with_input(DIFF_CONTEXT)
+            .expect_after_skip(
+                1,
+                "
+#indent_mark
+comparing: one.rs ⟶   two.rs
+────────────────────────────────────────────────────────────────────────────────
+
+───┐
+1: │
+───┘
+fn main() {
+    let x = 1;
+    let y = 2;
+    let x = 3;
+}
+    let z = 4;
+
+───┐
+9: │
+───┘
+    // end
+    // the end
+",
+            );
+    }
+
+    #[test]
+    fn test_context_diff_line_numbers() {
+        DeltaTest::with_args(&["--line-numbers"])
+            .with_input(DIFF_CONTEXT)
+            .expect_contains("  2 ⋮    │    let x = 1;")
+            .expect_contains("    ⋮  2 │    let x = 3;")
+            .expect_contains("    ⋮  4 │    let z = 4;")
+            .expect_contains("  9 ⋮    │    // end");
+    }
+
+    const DIFF_CONTEXT: &str = "\
+*** one.rs	2023-01-01 00:00:00.000000000 +0000
+--- two.rs	2023-01-01 00:00:00.000000000 +0000
+***************
+*** 1,4 ****
+  fn main() {
+!     let x = 1;
+!     let y = 2;
+  }
+--- 1,4 ----
+  fn main() {
+!     let x = 3;
+  }
++     let z = 4;
+***************
+*** 9 ****
+!     // end
+--- 9 ----
+!     // the end
+";
+}
@@ -1,18 +1,51 @@
 use std::borrow::Cow;
 use std::path::Path;
 
+use lazy_static::lazy_static;
+use regex::Regex;
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::draw;
 use crate::config::Config;
 use crate::delta::{DiffType, Source, State, StateMachine};
+use crate::git_config::GitConfig;
+use crate::minusplus::MinusPlusIndex::{self, *};
 use crate::paint::Painter;
+use crate::utils::process::{self, CallingProcess};
 use crate::{features, utils};
 
 // https://git-scm.com/docs/git-config#Documentation/git-config.txt-diffmnemonicPrefix
 const DIFF_PREFIXES: [&str; 6] = ["a/", "b/", "c/", "i/", "o/", "w/"];
 
-#[derive(Debug, PartialEq, Eq)]
+/// The prefixes of the old and the new file paths in git output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GitDiffPrefixes {
+    /// "a/" and "b/", or those of diff.mnemonicPrefix
+    Default,
+    /// diff.srcPrefix and diff.dstPrefix, or the --src-prefix and --dst-prefix options
+    Custom(String, String),
+    /// diff.noprefix, or the --no-prefix option
+    NoPrefix,
+}
+
+lazy_static! {
+    // E.g. "diff -r 82e55d328c8c -r 2a5f3c6f9b2e src/main.rs"
+    static ref MERCURIAL_DIFF_LINE_REGEX: Regex =
+        Regex::new(r"^diff(?: -r [0-9a-f]{12,40})+ (.+)$").unwrap();
+    // E.g. "Binary files a/logo.png and b/logo.png differ"
+    static ref BINARY_FILES_LINE_REGEX: Regex =
+        Regex::new(r"^Binary files (.+) and (.+) differ$").unwrap();
+    // E.g. "diff a/src/main.rs b/src/main.rs\t(rejected hunks)"
+    static ref GIT_REJECT_DIFF_LINE_REGEX: Regex =
+        Regex::new(r"^diff a/(.+) b/(.+)\t\(rejected hunks\)$").unwrap();
+    // E.g. "==== //depot/src/main.rs#4 - /home/user/src/main.rs ====" (p4 diff),
+    // "==== //depot/src/main.rs#4 (text) ====" (p4 describe) and
+    // "==== //depot/a.rs#1 (text) - //depot/b.rs#2 (text) ==== content" (p4 diff2)
+    static ref PERFORCE_DIFF_LINE_REGEX: Regex =
+        Regex::new(r"^==== (.+?)(?: - (.+?))? ====(?: \w+)?$").unwrap();
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FileEvent {
     Added,
     Change,
@@ -84,8 +117,11 @@ impl<'a> StateMachine<'a> {
             return Ok(false);
         }
 
-        let (path_or_mode, file_event) =
-            parse_diff_header_line(&self.line, self.source == Source::GitDiff);
+        let (path_or_mode, file_event) = parse_diff_header_line(
+            &self.line,
+            &self.source,
+            &get_git_diff_prefixes(self.config),
+        );
 
         self.minus_file = utils::path::relativize_path_maybe(&path_or_mode, self.config)
             .map(|p| p.to_string_lossy().into_owned())
@@ -94,8 +130,11 @@ impl<'a> StateMachine<'a> {
 
         if self.source == Source::DiffUnified {
             self.state = State::DiffHeader(DiffType::Unified);
-            self.painter
-                .set_syntax(get_file_extension_from_marker_line(&self.line));
+            let line = &self.line;
+            self.painter.set_syntax(
+                get_file_extension_from_diff_header_line_file_path(&self.minus_file)
+                    .or_else(|| get_file_extension_from_marker_line(line)),
+            );
         } else {
             self.painter
                 .set_syntax(get_file_extension_from_diff_header_line_file_path(
@@ -121,17 +160,26 @@ impl<'a> StateMachine<'a> {
             return Ok(false);
         }
         let mut handled_line = false;
-        let (path_or_mode, file_event) =
-            parse_diff_header_line(&self.line, self.source == Source::GitDiff);
+        let (path_or_mode, file_event) = parse_diff_header_line(
+            &self.line,
+            &self.source,
+            &get_git_diff_prefixes(self.config),
+        );
 
         self.plus_file = utils::path::relativize_path_maybe(&path_or_mode, self.config)
             .map(|p| p.to_string_lossy().into_owned())
             .unwrap_or(path_or_mode);
         self.plus_file_event = file_event;
-        self.painter
-            .set_syntax(get_file_extension_from_diff_header_line_file_path(
-                &self.plus_file,
-            ));
+        if matches!(self.source, Source::Mercurial | Source::Subversion) {
+            // These have no "rename from" and "rename to" lines.
+            self.minus_file_event =
+                get_file_event_from_file_paths(&self.minus_file, &self.plus_file);
+            self.plus_file_event = self.minus_file_event.clone();
+        }
+        self.painter.set_syntax(get_file_extension_from_file_pair(
+            &self.minus_file,
+            &self.plus_file,
+        ));
         self.current_file_pair = Some((self.minus_file.clone(), self.plus_file.clone()));
 
         self.painter.paint_buffered_minus_and_plus_lines();
@@ -160,9 +208,16 @@ impl<'a> StateMachine<'a> {
             return Ok(false);
         }
         let mut handled_line = false;
-        let (_mode_info, file_event) =
-            parse_diff_header_line(&self.line, self.source == Source::GitDiff);
-        let name = get_repeated_file_path_from_diff_line(&self.diff_line).unwrap_or_default();
+        let (_mode_info, file_event) = parse_diff_header_line(
+            &self.line,
+            &self.source,
+            &get_git_diff_prefixes(self.config),
+        );
+        let name = get_repeated_file_path_from_diff_line(
+            &self.diff_line,
+            &get_git_diff_prefixes(self.config),
+        )
+        .unwrap_or_default();
         match file_event {
             FileEvent::Removed => {
                 self.minus_file = name;
@@ -190,8 +245,45 @@ impl<'a> StateMachine<'a> {
         Ok(handled_line)
     }
 
+    /// Set the file pair from the line introducing a file in Mercurial, Subversion or Perforce
+    /// output. Perforce output has no "---" and "+++" lines, so its file header is written here.
+    pub fn handle_diff_header_file_pair_from_diff_line(&mut self) -> std::io::Result<()> {
+        let (minus_file, plus_file) = match parse_file_pair_from_diff_line(&self.line, &self.source)
+        {
+            Some(file_pair) => file_pair,
+            None => return Ok(()),
+        };
+        let config = self.config;
+        let relativize = |path: String| {
+            utils::path::relativize_path_maybe(&path, config)
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or(path)
+        };
+        self.minus_file = relativize(minus_file);
+        self.plus_file = relativize(plus_file);
+        self.minus_file_event = get_file_event_from_file_paths(&self.minus_file, &self.plus_file);
+        self.plus_file_event = self.minus_file_event.clone();
+        self.painter.set_syntax(get_file_extension_from_file_pair(
+            &self.minus_file,
+            &self.plus_file,
+        ));
+        self.current_file_pair = Some((self.minus_file.clone(), self.plus_file.clone()));
+
+        // These sources have no "---" and "+++" lines from which to write the file header.
+        if matches!(self.source, Source::Perforce | Source::GitReject)
+            && !self.config.color_only
+            && self.should_handle()
+        {
+            self.painter.emit()?;
+            self._handle_diff_header_header_line(false)?;
+            self.handled_diff_header_header_line_file_pair = self.current_file_pair.clone();
+        }
+        Ok(())
+    }
+
     /// Construct file change line from minus and plus file and write with DiffHeader styling.
     fn _handle_diff_header_header_line(&mut self, comparing: bool) -> std::io::Result<()> {
+        self.record_changed_file_pair(&self.minus_file.clone(), &self.plus_file.clone(), false);
         let line = get_file_change_description_from_file_paths(
             &self.minus_file,
             &self.plus_file,
@@ -200,6 +292,14 @@ impl<'a> StateMachine<'a> {
             &self.plus_file_event,
             self.config,
         );
+        let line = match &self.lfs_pointer_diff {
+            Some(_) => format!("LFS {line}"),
+            None => line,
+        };
+        let line = match self.source {
+            Source::GitReject => format!("{line} (rejected hunks)"),
+            _ => line,
+        };
         // FIXME: no support for 'raw'
         write_generic_diff_header_header_line(
             &line,
@@ -221,6 +321,12 @@ impl<'a> StateMachine<'a> {
         }
 
         if !self.mode_info.is_empty() {
+            let name = get_repeated_file_path_from_diff_line(
+                &self.diff_line,
+                &get_git_diff_prefixes(self.config),
+            )
+            .unwrap_or_default();
+            self.record_changed_file_pair(&name, &name, false);
             let format_label = |label: &str| {
                 if !label.is_empty() {
                     format!("{label} ")
@@ -241,7 +347,6 @@ impl<'a> StateMachine<'a> {
                 _ => Cow::from(file),
             };
             let label = format_label(&self.config.file_modified_label);
-            let name = get_repeated_file_path_from_diff_line(&self.diff_line).unwrap_or_default();
             let line = format!("{}{}", label, format_file(&name));
             write_generic_diff_header_header_line(
                 &line,
@@ -317,6 +422,15 @@ fn get_file_extension_from_diff_header_line_file_path(path: &str) -> Option<&str
     }
 }
 
+/// The extension of the plus file, or of the minus file if the file has been removed.
+fn get_file_extension_from_file_pair<'a>(
+    minus_file: &'a str,
+    plus_file: &'a str,
+) -> Option<&'a str> {
+    get_file_extension_from_diff_header_line_file_path(plus_file)
+        .or_else(|| get_file_extension_from_diff_header_line_file_path(minus_file))
+}
+
 /// Attempt to parse input as a file path and return extension as a &str.
 pub fn get_extension(s: &str) -> Option<&str> {
     let path = Path::new(s);
@@ -326,24 +440,63 @@ pub fn get_extension(s: &str) -> Option<&str> {
         .or_else(|| path.file_name().and_then(|s| s.to_str()))
 }
 
-fn parse_diff_header_line(line: &str, git_diff_name: bool) -> (String, FileEvent) {
+/// Does this line introduce a file in `hg diff` output?
+pub fn is_mercurial_diff_line(line: &str) -> bool {
+    MERCURIAL_DIFF_LINE_REGEX.is_match(line)
+}
+
+/// Does this line introduce the rejected hunks of a file written by `git apply --reject`?
+pub fn is_git_reject_diff_line(line: &str) -> bool {
+    GIT_REJECT_DIFF_LINE_REGEX.is_match(line)
+}
+
+/// Does this line introduce a file in `p4 diff -du` output?
+pub fn is_perforce_diff_line(line: &str) -> bool {
+    PERFORCE_DIFF_LINE_REGEX.is_match(line)
+}
+
+fn parse_diff_header_line(
+    line: &str,
+    source: &Source,
+    git_diff_prefixes: &GitDiffPrefixes,
+) -> (String, FileEvent) {
+    // Paths in the "rename" and "copy" lines have no prefix, but may be quoted.
+    let unquote = |path: &str| utils::path::unquote_path(path).into_owned();
     match line {
         line if line.starts_with("--- ") || line.starts_with("+++ ") => {
             let offset = 4;
-            let file = _parse_file_path(&line[offset..], git_diff_name);
+            let side = if line.starts_with("--- ") {
+                Minus
+            } else {
+                Plus
+            };
+            let file = match source {
+                // E.g. "--- a/src/main.rs	Thu Jan 01 00:00:00 1970 +0000"
+                Source::Mercurial => _parse_file_path(
+                    line[offset..].split('\t').next().unwrap(),
+                    Some((&GitDiffPrefixes::Default, side)),
+                ),
+                Source::Subversion => {
+                    _parse_subversion_file_path(&line[offset..], line.starts_with("--- "))
+                }
+                Source::GitDiff => {
+                    _parse_file_path(&line[offset..], Some((git_diff_prefixes, side)))
+                }
+                _ => _parse_file_path(&line[offset..], None),
+            };
             (file, FileEvent::Change)
         }
         line if line.starts_with("rename from ") => {
-            (line[12..].to_string(), FileEvent::Rename) // "rename from ".len()
+            (unquote(&line[12..]), FileEvent::Rename) // "rename from ".len()
         }
         line if line.starts_with("rename to ") => {
-            (line[10..].to_string(), FileEvent::Rename) // "rename to ".len()
+            (unquote(&line[10..]), FileEvent::Rename) // "rename to ".len()
         }
         line if line.starts_with("copy from ") => {
-            (line[10..].to_string(), FileEvent::Copy) // "copy from ".len()
+            (unquote(&line[10..]), FileEvent::Copy) // "copy from ".len()
         }
         line if line.starts_with("copy to ") => {
-            (line[8..].to_string(), FileEvent::Copy) // "copy to ".len()
+            (unquote(&line[8..]), FileEvent::Copy) // "copy to ".len()
         }
         line if line.starts_with("new file mode ") => {
             (line[14..].to_string(), FileEvent::Added) // "new file mode ".len()
@@ -357,31 +510,116 @@ fn parse_diff_header_line(line: &str, git_diff_name: bool) -> (String, FileEvent
 
 /// Given input like "diff --git a/src/my file.rs b/src/my file.rs"
 /// return Some("src/my file.rs")
-fn get_repeated_file_path_from_diff_line(line: &str) -> Option<String> {
-    if let Some(line) = line.strip_prefix("diff --git ") {
-        let line: Vec<&str> = line.graphemes(true).collect();
-        let midpoint = line.len() / 2;
-        if line[midpoint] == " " {
-            let first_path = _parse_file_path(&line[..midpoint].join(""), true);
-            let second_path = _parse_file_path(&line[midpoint + 1..].join(""), true);
-            if first_path == second_path {
-                return Some(first_path);
-            }
+pub fn get_repeated_file_path_from_diff_line(
+    line: &str,
+    git_diff_prefixes: &GitDiffPrefixes,
+) -> Option<String> {
+    let line = line.strip_prefix("diff --git ")?;
+    let line: Vec<&str> = line.graphemes(true).collect();
+    // The two paths are separated by a space, and are the same after removing their prefixes,
+    // which may differ in length.
+    let midpoint = line.len() / 2;
+    let mut separators: Vec<usize> = (0..line.len()).filter(|&i| line[i] == " ").collect();
+    separators.sort_by_key(|&i| (i as isize - midpoint as isize).abs());
+    separators.into_iter().find_map(|i| {
+        let first_path = _parse_file_path(&line[..i].join(""), Some((git_diff_prefixes, Minus)));
+        let second_path =
+            _parse_file_path(&line[i + 1..].join(""), Some((git_diff_prefixes, Plus)));
+        (first_path == second_path).then_some(first_path)
+    })
+}
+
+/// Given the line introducing a file in Mercurial, Subversion or Perforce output, return the
+/// minus and plus file paths.
+fn parse_file_pair_from_diff_line(line: &str, source: &Source) -> Option<(String, String)> {
+    match source {
+        Source::Mercurial => MERCURIAL_DIFF_LINE_REGEX
+            .captures(line)
+            .map(|caps| caps[1].to_string())
+            .map(|path| (path.clone(), path)),
+        // E.g. "Index: src/main.rs"
+        Source::Subversion => line
+            .strip_prefix("Index: ")
+            .map(|path| (path.to_string(), path.to_string())),
+        Source::Perforce => PERFORCE_DIFF_LINE_REGEX.captures(line).map(|caps| {
+            let minus_file = _parse_perforce_file_path(&caps[1]);
+            let plus_file = match caps.get(2).map(|m| m.as_str()) {
+                // `p4 diff` compares a depot file with its copy in the client workspace.
+                Some(path) if !path.starts_with("//") && minus_file != "/dev/null" => {
+                    minus_file.clone()
+                }
+                Some(path) => _parse_perforce_file_path(path),
+                None => minus_file.clone(),
+            };
+            (minus_file, plus_file)
+        }),
+        Source::GitReject => GIT_REJECT_DIFF_LINE_REGEX
+            .captures(line)
+            .map(|caps| (caps[1].to_string(), caps[2].to_string())),
+        _ => None,
+    }
+}
+
+/// Given input like "Binary files a/logo.png and b/logo.png differ" return the minus and plus file
+/// paths, without their "a/" and "b/" prefixes if the input is git output.
+pub fn parse_file_pair_from_binary_files_line(
+    line: &str,
+    source: &Source,
+    git_diff_prefixes: &GitDiffPrefixes,
+) -> Option<(String, String)> {
+    let caps = BINARY_FILES_LINE_REGEX.captures(line)?;
+    let git_diff_name = |side| (*source == Source::GitDiff).then_some((git_diff_prefixes, side));
+    Some((
+        _parse_file_path(&caps[1], git_diff_name(Minus)),
+        _parse_file_path(&caps[2], git_diff_name(Plus)),
+    ))
+}
+
+/// Given input like "//depot/src/main.rs#4 (text)" return "//depot/src/main.rs".
+fn _parse_perforce_file_path(s: &str) -> String {
+    if s == "<none>" {
+        return "/dev/null".to_string();
+    }
+    let path = match s.rfind(" (") {
+        Some(i) if s.ends_with(')') => &s[..i],
+        _ => s,
+    };
+    let path = match path.rsplit_once('#') {
+        Some((path, revision))
+            if revision == "none" || revision.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            path
         }
+        _ => path,
+    };
+    path.to_string()
+}
+
+#[allow(clippy::tabs_in_doc_comments)]
+/// Given input like "src/main.rs	(revision 12)" return "src/main.rs". A file which does not exist
+/// on one side of the diff is "(nonexistent)", or "(revision 0)" in older versions of Subversion.
+fn _parse_subversion_file_path(s: &str, is_minus_file: bool) -> String {
+    let mut fields = s.split('\t');
+    let path = fields.next().unwrap();
+    match fields.next_back() {
+        Some("(nonexistent)") => "/dev/null".to_string(),
+        Some("(revision 0)") if is_minus_file => "/dev/null".to_string(),
+        _ => path.to_string(),
     }
-    None
 }
 
-fn remove_surrounding_quotes(path: &str) -> &str {
-    if path.starts_with('"') && path.ends_with('"') {
-        // Indexing into the UTF-8 string is safe because of the previous test
-        &path[1..path.len() - 1]
+/// A file pair with two different existing files is a rename.
+fn get_file_event_from_file_paths(minus_file: &str, plus_file: &str) -> FileEvent {
+    if minus_file != plus_file && minus_file != "/dev/null" && plus_file != "/dev/null" {
+        FileEvent::Rename
     } else {
-        path
+        FileEvent::Change
     }
 }
 
-fn _parse_file_path(s: &str, git_diff_name: bool) -> String {
+/// Given a path in git output (`git_diff_name` is the prefixes of git paths, and the side of the
+/// path) or in diff output, return the path without its quotes and prefix.
+fn _parse_file_path(s: &str, git_diff_name: Option<(&GitDiffPrefixes, MinusPlusIndex)>) -> String {
     // It appears that, if the file name contains a space, git appends a tab
     // character in the diff metadata lines, e.g.
     // $ git diff --no-index "a b" "c d" | cat -A
@@ -390,14 +628,104 @@ fn _parse_file_path(s: &str, git_diff_name: bool) -> String {
     // ---·a/a·b├──┤␊
     // +++·b/c·d├──┤␊
     let path = match s.strip_suffix('\t').unwrap_or(s) {
-        path if path == "/dev/null" => "/dev/null",
-        path if git_diff_name && DIFF_PREFIXES.iter().any(|s| path.starts_with(s)) => &path[2..],
-        path if git_diff_name => path,
+        path if path == "/dev/null" => return path.to_string(),
+        path if git_diff_name.is_some() => path,
         path => path.split('\t').next().unwrap_or(""),
     };
-    // When a path contains non-ASCII characters, a backslash, or a quote then it is quoted,
-    // so remove these quotes. Characters may also be escaped, but these are left as-is.
-    remove_surrounding_quotes(path).to_string()
+    // When a path contains non-ASCII characters, a backslash, or a quote then it is quoted and
+    // these characters are escaped. The prefix is inside the quotes.
+    let path = utils::path::unquote_path(path);
+    match git_diff_name {
+        Some((git_diff_prefixes, side)) => git_diff_prefixes.strip_prefix(&path, side).to_string(),
+        None => path.into_owned(),
+    }
+}
+
+impl GitDiffPrefixes {
+    pub fn from_git_config(git_config: &GitConfig) -> Self {
+        if git_config.get::<bool>("diff.noprefix").unwrap_or(false) {
+            return GitDiffPrefixes::NoPrefix;
+        }
+        match (
+            git_config.get::<String>("diff.srcprefix"),
+            git_config.get::<String>("diff.dstprefix"),
+        ) {
+            (None, None) => GitDiffPrefixes::Default,
+            (src_prefix, dst_prefix) => GitDiffPrefixes::Custom(
+                src_prefix.unwrap_or_else(|| "a/".to_string()),
+                dst_prefix.unwrap_or_else(|| "b/".to_string()),
+            ),
+        }
+    }
+
+    /// The prefixes requested by the options of the git command, if any, overriding those of
+    /// the git config.
+    fn from_calling_process(calling_process: &CallingProcess) -> Option<Self> {
+        let cmd_line = match calling_process {
+            CallingProcess::GitDiff(cmd_line)
+            | CallingProcess::GitShow(cmd_line, _)
+            | CallingProcess::GitLog(cmd_line)
+            | CallingProcess::GitReflog(cmd_line) => cmd_line,
+            _ => return None,
+        };
+        if cmd_line.long_options.contains("--no-prefix") {
+            return Some(GitDiffPrefixes::NoPrefix);
+        }
+        if cmd_line.long_options.contains("--default-prefix") {
+            return Some(GitDiffPrefixes::Default);
+        }
+        match (
+            cmd_line.long_option_values.get("--src-prefix"),
+            cmd_line.long_option_values.get("--dst-prefix"),
+        ) {
+            (None, None) => None,
+            (src_prefix, dst_prefix) => Some(GitDiffPrefixes::Custom(
+                src_prefix.cloned().unwrap_or_else(|| "a/".to_string()),
+                dst_prefix.cloned().unwrap_or_else(|| "b/".to_string()),
+            )),
+        }
+    }
+
+    /// Remove the prefix of the old (minus) or the new (plus) path.
+    fn strip_prefix<'p>(&self, path: &'p str, side: MinusPlusIndex) -> &'p str {
+        match self {
+            GitDiffPrefixes::Default => DIFF_PREFIXES
+                .iter()
+                .find_map(|prefix| path.strip_prefix(prefix))
+                .unwrap_or(path),
+            GitDiffPrefixes::Custom(src_prefix, dst_prefix) => {
+                let prefix = if side == Minus {
+                    src_prefix
+                } else {
+                    dst_prefix
+                };
+                path.strip_prefix(prefix.as_str()).unwrap_or(path)
+            }
+            GitDiffPrefixes::NoPrefix => path,
+        }
+    }
+}
+
+/// The prefixes of the paths in git output: those requested by the git command, or else those of
+/// the git config.
+pub fn get_git_diff_prefixes(config: &Config) -> GitDiffPrefixes {
+    get_calling_process_git_diff_prefixes().unwrap_or_else(|| config.git_diff_prefixes.clone())
+}
+
+#[cfg(not(test))]
+fn get_calling_process_git_diff_prefixes() -> Option<GitDiffPrefixes> {
+    lazy_static! {
+        static ref CACHED_CALLING_PROCESS_GIT_DIFF_PREFIXES: Option<GitDiffPrefixes> =
+            GitDiffPrefixes::from_calling_process(&process::calling_process());
+    }
+    CACHED_CALLING_PROCESS_GIT_DIFF_PREFIXES.clone()
+}
+
+// Not cached when testing, since the calling process then depends on the FakeParentArgs set by
+// each test.
+#[cfg(test)]
+fn get_calling_process_git_diff_prefixes() -> Option<GitDiffPrefixes> {
+    GitDiffPrefixes::from_calling_process(&process::calling_process())
 }
 
 pub fn get_file_change_description_from_file_paths(
@@ -475,6 +803,8 @@ pub fn get_file_change_description_from_file_paths(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tests::integration_test_utils::DeltaTest;
+    use crate::utils::process::tests::FakeParentArgs;
 
     #[test]
     fn test_get_file_extension_from_marker_line() {
@@ -543,26 +873,42 @@ mod tests {
     #[test]
     fn test_get_file_path_from_git_diff_header_line() {
         assert_eq!(
-            parse_diff_header_line("--- /dev/null", true),
+            parse_diff_header_line("--- /dev/null", &Source::GitDiff, &GitDiffPrefixes::Default),
             ("/dev/null".to_string(), FileEvent::Change)
         );
         for prefix in &DIFF_PREFIXES {
             assert_eq!(
-                parse_diff_header_line(&format!("--- {prefix}src/delta.rs"), true),
+                parse_diff_header_line(
+                    &format!("--- {prefix}src/delta.rs"),
+                    &Source::GitDiff,
+                    &GitDiffPrefixes::Default
+                ),
                 ("src/delta.rs".to_string(), FileEvent::Change)
             );
         }
         assert_eq!(
-            parse_diff_header_line("--- src/delta.rs", true),
+            parse_diff_header_line(
+                "--- src/delta.rs",
+                &Source::GitDiff,
+                &GitDiffPrefixes::Default
+            ),
             ("src/delta.rs".to_string(), FileEvent::Change)
         );
         assert_eq!(
-            parse_diff_header_line("+++ src/delta.rs", true),
+            parse_diff_header_line(
+                "+++ src/delta.rs",
+                &Source::GitDiff,
+                &GitDiffPrefixes::Default
+            ),
             ("src/delta.rs".to_string(), FileEvent::Change)
         );
 
         assert_eq!(
-            parse_diff_header_line("+++ \".\\delta.rs\"", true),
+            parse_diff_header_line(
+                "+++ \".\\delta.rs\"",
+                &Source::GitDiff,
+                &GitDiffPrefixes::Default
+            ),
             (".\\delta.rs".to_string(), FileEvent::Change)
         );
     }
@@ -570,23 +916,43 @@ mod tests {
     #[test]
     fn test_get_file_path_from_git_diff_header_line_containing_spaces() {
         assert_eq!(
-            parse_diff_header_line("+++ a/my src/delta.rs", true),
+            parse_diff_header_line(
+                "+++ a/my src/delta.rs",
+                &Source::GitDiff,
+                &GitDiffPrefixes::Default
+            ),
             ("my src/delta.rs".to_string(), FileEvent::Change)
         );
         assert_eq!(
-            parse_diff_header_line("+++ my src/delta.rs", true),
+            parse_diff_header_line(
+                "+++ my src/delta.rs",
+                &Source::GitDiff,
+                &GitDiffPrefixes::Default
+            ),
             ("my src/delta.rs".to_string(), FileEvent::Change)
         );
         assert_eq!(
-            parse_diff_header_line("+++ a/src/my delta.rs", true),
+            parse_diff_header_line(
+                "+++ a/src/my delta.rs",
+                &Source::GitDiff,
+                &GitDiffPrefixes::Default
+            ),
             ("src/my delta.rs".to_string(), FileEvent::Change)
         );
         assert_eq!(
-            parse_diff_header_line("+++ a/my src/my delta.rs", true),
+            parse_diff_header_line(
+                "+++ a/my src/my delta.rs",
+                &Source::GitDiff,
+                &GitDiffPrefixes::Default
+            ),
             ("my src/my delta.rs".to_string(), FileEvent::Change)
         );
         assert_eq!(
-            parse_diff_header_line("+++ b/my src/my enough/my delta.rs", true),
+            parse_diff_header_line(
+                "+++ b/my src/my enough/my delta.rs",
+                &Source::GitDiff,
+                &GitDiffPrefixes::Default
+            ),
             (
                 "my src/my enough/my delta.rs".to_string(),
                 FileEvent::Change
@@ -597,7 +963,11 @@ mod tests {
     #[test]
     fn test_get_file_path_from_git_diff_header_line_rename() {
         assert_eq!(
-            parse_diff_header_line("rename from nospace/file2.el", true),
+            parse_diff_header_line(
+                "rename from nospace/file2.el",
+                &Source::GitDiff,
+                &GitDiffPrefixes::Default
+            ),
             ("nospace/file2.el".to_string(), FileEvent::Rename)
         );
     }
@@ -605,7 +975,11 @@ mod tests {
     #[test]
     fn test_get_file_path_from_git_diff_header_line_rename_containing_spaces() {
         assert_eq!(
-            parse_diff_header_line("rename from with space/file1.el", true),
+            parse_diff_header_line(
+                "rename from with space/file1.el",
+                &Source::GitDiff,
+                &GitDiffPrefixes::Default
+            ),
             ("with space/file1.el".to_string(), FileEvent::Rename)
         );
     }
@@ -613,11 +987,19 @@ mod tests {
     #[test]
     fn test_parse_diff_header_line() {
         assert_eq!(
-            parse_diff_header_line("--- src/delta.rs", false),
+            parse_diff_header_line(
+                "--- src/delta.rs",
+                &Source::DiffUnified,
+                &GitDiffPrefixes::Default
+            ),
             ("src/delta.rs".to_string(), FileEvent::Change)
         );
         assert_eq!(
-            parse_diff_header_line("+++ src/delta.rs", false),
+            parse_diff_header_line(
+                "+++ src/delta.rs",
+                &Source::DiffUnified,
+                &GitDiffPrefixes::Default
+            ),
             ("src/delta.rs".to_string(), FileEvent::Change)
         );
     }
@@ -625,25 +1007,316 @@ mod tests {
     #[test]
     fn test_get_repeated_file_path_from_diff_line() {
         assert_eq!(
-            get_repeated_file_path_from_diff_line("diff --git a/src/main.rs b/src/main.rs"),
+            get_repeated_file_path_from_diff_line(
+                "diff --git a/src/main.rs b/src/main.rs",
+                &GitDiffPrefixes::Default
+            ),
             Some("src/main.rs".to_string())
         );
         assert_eq!(
-            get_repeated_file_path_from_diff_line("diff --git a/a b/a"),
+            get_repeated_file_path_from_diff_line("diff --git a/a b/a", &GitDiffPrefixes::Default),
             Some("a".to_string())
         );
         assert_eq!(
-            get_repeated_file_path_from_diff_line("diff --git a/a b b/a b"),
+            get_repeated_file_path_from_diff_line(
+                "diff --git a/a b b/a b",
+                &GitDiffPrefixes::Default
+            ),
             Some("a b".to_string())
         );
         assert_eq!(
-            get_repeated_file_path_from_diff_line("diff --git a/a b/aa"),
+            get_repeated_file_path_from_diff_line("diff --git a/a b/aa", &GitDiffPrefixes::Default),
             None
         );
         assert_eq!(
             get_repeated_file_path_from_diff_line(
-                "diff --git a/.config/Code - Insiders/User/settings.json b/.config/Code - Insiders/User/settings.json"),
+                "diff --git a/.config/Code - Insiders/User/settings.json b/.config/Code - Insiders/User/settings.json", &GitDiffPrefixes::Default),
             Some(".config/Code - Insiders/User/settings.json".to_string())
         );
     }
+
+    #[test]
+    fn test_get_file_path_from_git_diff_header_line_quoted() {
+        let parse = |line, git_diff_prefixes| {
+            parse_diff_header_line(line, &Source::GitDiff, git_diff_prefixes).0
+        };
+        let default = &GitDiffPrefixes::Default;
+        assert_eq!(
+            parse("--- \"a/\\303\\251t\\303\\251.txt\"", default),
+            "été.txt"
+        );
+        assert_eq!(
+            parse("+++ \"b/say \\\"hi\\\".rs\"", default),
+            "say \"hi\".rs"
+        );
+        assert_eq!(parse("+++ \"b/tab\\there.rs\"", default), "tab\there.rs");
+        assert_eq!(
+            parse("rename to \"\\303\\274.py\"", default),
+            "ü.py".to_string()
+        );
+        assert_eq!(
+            get_repeated_file_path_from_diff_line(
+                "diff --git \"a/\\303\\274.py\" \"b/\\303\\274.py\"",
+                default
+            ),
+            Some("ü.py".to_string())
+        );
+    }
+
+    #[test]
+    fn test_get_file_path_from_git_diff_header_line_with_custom_prefixes() {
+        let parse = |line, git_diff_prefixes| {
+            parse_diff_header_line(line, &Source::GitDiff, git_diff_prefixes).0
+        };
+        let no_prefix = &GitDiffPrefixes::NoPrefix;
+        assert_eq!(parse("--- a/src/main.rs", no_prefix), "a/src/main.rs");
+        assert_eq!(
+            get_repeated_file_path_from_diff_line("diff --git a/f.rs a/f.rs", no_prefix),
+            Some("a/f.rs".to_string())
+        );
+        let custom = &GitDiffPrefixes::Custom("old/".to_string(), "new/".to_string());
+        assert_eq!(parse("--- old/a/f.rs", custom), "a/f.rs");
+        assert_eq!(parse("+++ new/a/f.rs", custom), "a/f.rs");
+        assert_eq!(parse("+++ old/a/f.rs", custom), "old/a/f.rs");
+        let custom = &GitDiffPrefixes::Custom("original/".to_string(), "".to_string());
+        assert_eq!(
+            get_repeated_file_path_from_diff_line(
+                "diff --git original/my file.rs my file.rs",
+                custom
+            ),
+            Some("my file.rs".to_string())
+        );
+    }
+
+    #[test]
+    fn test_git_diff_prefixes_from_calling_process() {
+        for (command, expected) in [
+            ("git diff", None),
+            ("git diff --no-prefix", Some(GitDiffPrefixes::NoPrefix)),
+            (
+                "git log -p --default-prefix",
+                Some(GitDiffPrefixes::Default),
+            ),
+            (
+                "git show --src-prefix=old/",
+                Some(GitDiffPrefixes::Custom(
+                    "old/".to_string(),
+                    "b/".to_string(),
+                )),
+            ),
+            ("rg foo", None),
+        ] {
+            let _args = FakeParentArgs::for_scope(command);
+            assert_eq!(
+                GitDiffPrefixes::from_calling_process(&process::calling_process()),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_file_pair_from_diff_line() {
+        let pair = |minus: &str, plus: &str| Some((minus.to_string(), plus.to_string()));
+        assert_eq!(
+            parse_file_pair_from_diff_line(
+                "diff -r 82e55d328c8c -r 2a5f3c6f9b2e src/my file.rs",
+                &Source::Mercurial
+            ),
+            pair("src/my file.rs", "src/my file.rs")
+        );
+        assert_eq!(
+            parse_file_pair_from_diff_line("diff -r 82e55d328c8c src/main.rs", &Source::Mercurial),
+            pair("src/main.rs", "src/main.rs")
+        );
+        assert_eq!(
+            parse_file_pair_from_diff_line("Index: src/main.rs", &Source::Subversion),
+            pair("src/main.rs", "src/main.rs")
+        );
+        assert_eq!(
+            parse_file_pair_from_diff_line(
+                "==== //depot/src/main.rs#4 - /home/user/src/main.rs ====",
+                &Source::Perforce
+            ),
+            pair("//depot/src/main.rs", "//depot/src/main.rs")
+        );
+        assert_eq!(
+            parse_file_pair_from_diff_line(
+                "==== //depot/src/main.rs#4 (text) ====",
+                &Source::Perforce
+            ),
+            pair("//depot/src/main.rs", "//depot/src/main.rs")
+        );
+        assert_eq!(
+            parse_file_pair_from_diff_line(
+                "==== //depot/a.rs#1 (text) - //depot/b.rs#2 (text) ==== content",
+                &Source::Perforce
+            ),
+            pair("//depot/a.rs", "//depot/b.rs")
+        );
+        assert_eq!(
+            parse_file_pair_from_diff_line(
+                "==== <none> - //depot/b.rs#1 (text) ==== content",
+                &Source::Perforce
+            ),
+            pair("/dev/null", "//depot/b.rs")
+        );
+        assert_eq!(
+            parse_file_pair_from_diff_line("diff -u a.rs b.rs", &Source::DiffUnified),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_diff_header_line_mercurial_and_subversion() {
+        assert_eq!(
+            parse_diff_header_line(
+                "--- a/src/main.rs\tThu Jan 01 00:00:00 1970 +0000",
+                &Source::Mercurial,
+                &GitDiffPrefixes::Default
+            ),
+            ("src/main.rs".to_string(), FileEvent::Change)
+        );
+        assert_eq!(
+            parse_diff_header_line(
+                "+++ /dev/null\tThu Jan 01 00:00:00 1970 +0000",
+                &Source::Mercurial,
+                &GitDiffPrefixes::Default
+            ),
+            ("/dev/null".to_string(), FileEvent::Change)
+        );
+        for (line, expected) in [
+            ("--- src/main.rs\t(revision 12)", "src/main.rs"),
+            ("+++ src/main.rs\t(working copy)", "src/main.rs"),
+            ("--- src/main.rs\t(nonexistent)", "/dev/null"),
+            ("--- src/main.rs\t(revision 0)", "/dev/null"),
+            ("+++ src/main.rs\t(revision 0)", "src/main.rs"),
+            ("+++ src/main.rs\t(nonexistent)", "/dev/null"),
+        ] {
+            assert_eq!(
+                parse_diff_header_line(line, &Source::Subversion, &GitDiffPrefixes::Default),
+                (expected.to_string(), FileEvent::Change),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mercurial_diff() {
+        DeltaTest::with_args(&[])
+            .with_input(HG_DIFF)
+            .expect_contains("\nsrc/main.rs\n")
+            .expect_contains("removed: old.py");
+    }
+
+    #[test]
+    fn test_subversion_diff() {
+        DeltaTest::with_args(&[])
+            .with_input(SVN_DIFF)
+            .expect_contains("\nsrc/main.rs\n")
+            .expect_contains("added: new.py")
+            .expect_contains("renamed: old.rs ⟶   moved.rs");
+    }
+
+    #[test]
+    fn test_perforce_diff() {
+        DeltaTest::with_args(&[])
+            .with_input(P4_DIFF)
+            .expect_contains("\n//depot/proj/src/main.rs\n")
+            .expect_contains("renamed: //depot/proj/a.py ⟶   //depot/proj/b.py");
+    }
+
+    #[test]
+    fn test_git_reject_diff() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_REJECT_DIFF)
+            .expect_contains("\nsrc/main.rs (rejected hunks)\n");
+        let config = crate::tests::integration_test_utils::make_config_from_args(&[]);
+        let mut writer = Vec::new();
+        let mut state_machine = StateMachine::new(&mut writer, &config);
+        for line in GIT_REJECT_DIFF.lines() {
+            state_machine.consume_line(line.as_bytes()).unwrap();
+        }
+        assert_eq!(state_machine.source, Source::GitReject);
+        assert_eq!(state_machine.painter.syntax.name, "Rust");
+    }
+
+    #[test]
+    fn test_subversion_diff_syntax_of_added_and_removed_files() {
+        let config = crate::tests::integration_test_utils::make_config_from_args(&[]);
+        let mut writer = Vec::new();
+        let mut state_machine = StateMachine::new(&mut writer, &config);
+        for line in SVN_DIFF.lines().take(4) {
+            state_machine.consume_line(line.as_bytes()).unwrap();
+        }
+        assert_eq!(state_machine.source, Source::Subversion);
+        assert_eq!(state_machine.painter.syntax.name, "Rust");
+        for line in "Index: old.py\n--- old.py\t(revision 12)\n+++ old.py\t(nonexistent)".lines() {
+            state_machine.consume_line(line.as_bytes()).unwrap();
+        }
+        assert_eq!(state_machine.plus_file, "/dev/null");
+        assert_eq!(state_machine.painter.syntax.name, "Python");
+    }
+
+    const HG_DIFF: &str = "\
+diff -r 82e55d328c8c -r 2a5f3c6f9b2e src/main.rs
+--- a/src/main.rs	Thu Jan 01 00:00:00 1970 +0000
++++ b/src/main.rs	Thu Jan 01 00:00:01 1970 +0000
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+diff -r 82e55d328c8c -r 2a5f3c6f9b2e old.py
+--- a/old.py	Thu Jan 01 00:00:00 1970 +0000
++++ /dev/null	Thu Jan 01 00:00:00 1970 +0000
+@@ -1,1 +0,0 @@
+-x = 1
+";
+
+    const SVN_DIFF: &str = "\
+Index: src/main.rs
+===================================================================
+--- src/main.rs	(revision 12)
++++ src/main.rs	(working copy)
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+Index: new.py
+===================================================================
+--- new.py	(nonexistent)
++++ new.py	(working copy)
+@@ -0,0 +1 @@
++x = 1
+Index: moved.rs
+===================================================================
+--- old.rs	(revision 12)
++++ moved.rs	(working copy)
+@@ -1 +1 @@
+-let x = 1;
++let x = 2;
+";
+
+    const GIT_REJECT_DIFF: &str = "\
+diff a/src/main.rs b/src/main.rs\t(rejected hunks)
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+";
+
+    const P4_DIFF: &str = "\
+==== //depot/proj/src/main.rs#4 - /home/user/proj/src/main.rs ====
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+==== //depot/proj/a.py#1 (text) - //depot/proj/b.py#2 (text) ==== content
+@@ -1 +1 @@
+-x = 1
++x = 2
+";
 }
@@ -1,9 +1,13 @@
-use crate::delta::{DiffType, InMergeConflict, MergeParents, State, StateMachine};
+use crate::delta::{DiffType, InMergeConflict, MergeParents, Source, State, StateMachine};
+use crate::handlers::diff_header;
 
 impl<'a> StateMachine<'a> {
     #[inline]
     fn test_diff_header_diff_line(&self) -> bool {
         self.line.starts_with("diff ")
+            // Subversion and Perforce introduce each file with a line of their own.
+            || self.source == Source::Subversion && self.line.starts_with("Index: ")
+            || self.source == Source::Perforce && diff_header::is_perforce_diff_line(&self.line)
     }
 
     #[allow(clippy::unnecessary_wraps)]
@@ -24,7 +28,14 @@ impl<'a> StateMachine<'a> {
             };
         self.handle_pending_line_with_diff_name()?;
         self.handled_diff_header_header_line_file_pair = None;
+        self.blob_ids = None;
         self.diff_line = self.line.clone();
+        if matches!(
+            self.source,
+            Source::Mercurial | Source::Subversion | Source::Perforce | Source::GitReject
+        ) {
+            self.handle_diff_header_file_pair_from_diff_line()?;
+        }
         if !self.should_skip_line() {
             self.emit_line_unchanged()?;
         }
@@ -3,7 +3,9 @@ use crate::delta::{DiffType, Source, State, StateMachine};
 impl<'a> StateMachine<'a> {
     #[inline]
     fn test_diff_header_misc_cases(&self) -> bool {
-        self.source == Source::DiffUnified && self.line.starts_with("Only in ")
+        self.source == Source::DiffUnified
+            && (self.line.starts_with("Only in ")
+                || self.line.starts_with("Common subdirectories: "))
             || self.line.starts_with("Binary files ")
     }
 
@@ -11,9 +13,18 @@ impl<'a> StateMachine<'a> {
         if !self.test_diff_header_misc_cases() {
             return Ok(false);
         }
-        self.handle_additional_cases(match self.state {
+        let to_state = match self.state {
             State::DiffHeader(_) => self.state.clone(),
             _ => State::DiffHeader(DiffType::Unified),
-        })
+        };
+        let description = if self.line.starts_with("Only in ") {
+            self.handle_only_in_line()
+        } else if self.line.starts_with("Common subdirectories: ") {
+            return self.handle_common_subdirectories_line(to_state);
+        } else {
+            self.handle_binary_files_line();
+            None
+        };
+        self.handle_additional_cases_with_description(to_state, description)
     }
 }
@@ -1,6 +1,7 @@
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::borrow::Cow;
+use unicode_width::UnicodeWidthStr;
 
 use crate::config::Config;
 use crate::delta::{State, StateMachine};
@@ -49,7 +50,8 @@ pub fn relativize_path_in_diff_stat_line(
     config: &Config,
 ) -> Option<String> {
     let caps = DIFF_STAT_LINE_REGEX.captures(line)?;
-    let path_relative_to_repo_root = caps.get(1).unwrap().as_str();
+    let path_relative_to_repo_root = utils::path::unquote_path(caps.get(1).unwrap().as_str());
+    let path_relative_to_repo_root = path_relative_to_repo_root.as_ref();
 
     let relative_path =
         pathdiff::diff_paths(path_relative_to_repo_root, cwd_relative_to_repo_root)?;
@@ -69,7 +71,7 @@ pub fn relativize_path_in_diff_stat_line(
     let suffix = caps.get(2).unwrap().as_str();
     let pad_width = config
         .diff_stat_align_width
-        .saturating_sub(relative_path.len());
+        .saturating_sub(relative_path.width());
     let padding = " ".repeat(pad_width);
     Some(format!(" {formatted_path}{padding}{suffix}"))
 }
@@ -96,6 +98,19 @@ mod tests {
         assert_eq!(caps.get(2).unwrap().as_str(), "|  2 ++");
     }
 
+    #[test]
+    fn test_relativize_path_in_diff_stat_line_quoted() {
+        let config = crate::tests::integration_test_utils::make_config_from_args(&[]);
+        assert_eq!(
+            relativize_path_in_diff_stat_line(
+                " \"src/\\303\\251t\\303\\251.rs\" | 1 +",
+                "src/",
+                &config
+            ),
+            Some(format!(" été.rs{}| 1 +", " ".repeat(48 - 6)))
+        );
+    }
+
     #[test]
     fn test_relative_path() {
         for (path, cwd_relative_to_repo_root, expected) in &[
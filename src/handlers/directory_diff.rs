@@ -0,0 +1,407 @@
+// A module for handling the comparison of directories by `diff -r`, e.g.
+// ```
+// Binary files a/logo.png and b/logo.png differ
+// diff -ru a/src/main.rs b/src/main.rs
+// --- a/src/main.rs	2019-11-20 06:47:56.000000000 +0100
+// +++ b/src/main.rs	2019-11-20 06:47:56.000000000 +0100
+// @@ -1,3 +1,3 @@
+// ...
+// Only in a/src: old.rs
+// Only in b: README.md
+// ```
+// (without -r, subdirectories are not compared, but listed as "Common subdirectories: a/src and
+// b/src"). The two directories compared are inferred from the paths of the files compared, so that
+// a file found in only one of them can be shown as removed or added. With --tree-summary, the
+// added, removed and modified files are listed as a tree at the end of the output.
+
+use std::collections::BTreeMap;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::config::Config;
+use crate::delta::{Source, State, StateMachine};
+use crate::handlers::diff_header::{
+    get_file_change_description_from_file_paths, get_git_diff_prefixes,
+    parse_file_pair_from_binary_files_line, FileEvent,
+};
+
+#[derive(Debug, Default)]
+pub struct DirectoryDiff {
+    // The two directories compared, e.g. ("a", "b") for `diff -ru a b`.
+    roots: Option<(String, String)>,
+    // The changed files, relative to the directories compared, if --tree-summary is in effect.
+    changes: Vec<(String, FileChange)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FileChange {
+    Added,
+    Removed,
+    Modified,
+    Binary,
+}
+
+#[derive(Debug, Default)]
+struct FileTreeNode {
+    change: Option<FileChange>,
+    children: BTreeMap<String, FileTreeNode>,
+}
+
+lazy_static! {
+    // E.g. "Only in a/src: old.rs"
+    static ref ONLY_IN_LINE_REGEX: Regex = Regex::new(r"^Only in (.+?): (.+)$").unwrap();
+    // E.g. "Common subdirectories: a/src and b/src"
+    static ref COMMON_SUBDIRECTORIES_LINE_REGEX: Regex =
+        Regex::new(r"^Common subdirectories: (.+) and (.+)$").unwrap();
+}
+
+impl<'a> StateMachine<'a> {
+    /// Handle a line naming a file found in only one of the directories compared. If it is known
+    /// which directory that is, return the description of the file as added or removed.
+    pub fn handle_only_in_line(&mut self) -> Option<String> {
+        let caps = ONLY_IN_LINE_REGEX.captures(&self.line)?;
+        let directory = caps[1].trim_end_matches('/');
+        let path = format!("{}/{}", directory, &caps[2]);
+        let (minus_root, plus_root) = self.directory_diff.roots.as_ref()?;
+        let (minus_file, plus_file, file_event) = match (
+            is_in_directory(directory, minus_root),
+            is_in_directory(directory, plus_root),
+        ) {
+            (true, false) => (path, "/dev/null".to_string(), FileEvent::Removed),
+            (false, true) => ("/dev/null".to_string(), path, FileEvent::Added),
+            // The directories are the same, or one is inside the other.
+            _ => return None,
+        };
+        self.record_changed_file_pair(&minus_file, &plus_file, false);
+        Some(get_file_change_description_from_file_paths(
+            &minus_file,
+            &plus_file,
+            false,
+            &file_event,
+            &file_event,
+            self.config,
+        ))
+    }
+
+    /// Record the binary file of a "Binary files ... differ" line.
+    pub fn handle_binary_files_line(&mut self) {
+        if let Some((minus_file, plus_file)) = parse_file_pair_from_binary_files_line(
+            &self.line,
+            &self.source,
+            &get_git_diff_prefixes(self.config),
+        ) {
+            self.record_changed_file_pair(&minus_file, &plus_file, true);
+        }
+    }
+
+    /// Handle a line naming a subdirectory of both directories compared, which `diff` without -r
+    /// does not compare. The line is emitted unchanged.
+    pub fn handle_common_subdirectories_line(&mut self, to_state: State) -> std::io::Result<bool> {
+        if let Some(caps) = COMMON_SUBDIRECTORIES_LINE_REGEX.captures(&self.line) {
+            self.directory_diff.set_roots(&caps[1], &caps[2]);
+        }
+        self.painter.paint_buffered_minus_and_plus_lines();
+        self.painter.emit()?;
+        self.state = to_state;
+        writeln!(self.painter.writer, "{}", self.raw_line)?;
+        Ok(true)
+    }
+
+    /// Record the change of a file for the tree summary, given the paths of its two versions.
+    pub fn record_changed_file_pair(&mut self, minus_file: &str, plus_file: &str, is_binary: bool) {
+        if self.source == Source::DiffUnified {
+            self.directory_diff.set_roots(minus_file, plus_file);
+        }
+        if !self.config.tree_summary {
+            return;
+        }
+        let (path, change) = match (minus_file, plus_file) {
+            ("", _) | (_, "") => return,
+            (minus_file, "/dev/null") => (minus_file, FileChange::Removed),
+            ("/dev/null", plus_file) => (plus_file, FileChange::Added),
+            (_, plus_file) if is_binary => (plus_file, FileChange::Binary),
+            (_, plus_file) => (plus_file, FileChange::Modified),
+        };
+        let path = self.directory_diff.get_relative_path(path).to_string();
+        self.directory_diff.changes.push((path, change));
+    }
+
+    /// With --tree-summary, write the tree of the changed files.
+    pub fn write_tree_summary(&mut self) -> std::io::Result<()> {
+        if !self.config.tree_summary
+            || self.config.color_only
+            || self.directory_diff.changes.is_empty()
+        {
+            return Ok(());
+        }
+        writeln!(self.painter.writer)?;
+        for line in format_tree_summary_lines(&self.directory_diff, self.config) {
+            writeln!(self.painter.writer, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+impl DirectoryDiff {
+    /// Infer the directories compared from the paths of a file in each of them, by removing the
+    /// components at the end of the paths which they have in common.
+    fn set_roots(&mut self, minus_path: &str, plus_path: &str) {
+        if self.roots.is_some() || minus_path == "/dev/null" || plus_path == "/dev/null" {
+            return;
+        }
+        let mut minus_components: Vec<&str> = minus_path.trim_end_matches('/').split('/').collect();
+        let mut plus_components: Vec<&str> = plus_path.trim_end_matches('/').split('/').collect();
+        let mut n_common_components = 0;
+        while minus_components.len() > 1
+            && plus_components.len() > 1
+            && minus_components.last() == plus_components.last()
+        {
+            minus_components.pop();
+            plus_components.pop();
+            n_common_components += 1;
+        }
+        if n_common_components > 0 && minus_components != plus_components {
+            self.roots = Some((minus_components.join("/"), plus_components.join("/")));
+        }
+    }
+
+    /// Return `path` relative to the directory compared which contains it.
+    fn get_relative_path<'p>(&self, path: &'p str) -> &'p str {
+        self.roots
+            .iter()
+            .flat_map(|(minus_root, plus_root)| [minus_root, plus_root])
+            .filter_map(|root| path.strip_prefix(root.as_str())?.strip_prefix('/'))
+            .next()
+            .unwrap_or(path)
+    }
+}
+
+fn is_in_directory(path: &str, directory: &str) -> bool {
+    path == directory
+        || path
+            .strip_prefix(directory)
+            .map(|rest| rest.starts_with('/'))
+            .unwrap_or(false)
+}
+
+/// Return the lines of a tree of the changed files, e.g.
+/// ```text
+/// a ⟶   b
+/// ├── README.md
+/// └── src
+///     ├── added: lib.rs
+///     └── removed: old.rs
+/// ```
+fn format_tree_summary_lines(directory_diff: &DirectoryDiff, config: &Config) -> Vec<String> {
+    let mut tree = FileTreeNode::default();
+    for (path, change) in &directory_diff.changes {
+        let node = path
+            .split('/')
+            .filter(|component| !component.is_empty())
+            .fold(&mut tree, |node, component| {
+                node.children.entry(component.to_string()).or_default()
+            });
+        node.change = Some(*change);
+    }
+    let root = match &directory_diff.roots {
+        Some((minus_root, plus_root)) => {
+            format!("{} {} {}", minus_root, config.right_arrow, plus_root)
+        }
+        None => ".".to_string(),
+    };
+    let mut lines = vec![config.file_style.paint(root).to_string()];
+    format_tree_lines(&tree, "", &mut lines, config);
+    lines
+}
+
+fn format_tree_lines(node: &FileTreeNode, prefix: &str, lines: &mut Vec<String>, config: &Config) {
+    let n_children = node.children.len();
+    for (i, (name, child)) in node.children.iter().enumerate() {
+        let (branch, indent) = if i + 1 < n_children {
+            ("├── ", "│   ")
+        } else {
+            ("└── ", "    ")
+        };
+        lines.push(format!(
+            "{}{}{}",
+            prefix,
+            branch,
+            config
+                .file_style
+                .paint(format_tree_entry(name, child.change, config))
+        ));
+        format_tree_lines(child, &format!("{prefix}{indent}"), lines, config);
+    }
+}
+
+fn format_tree_entry(name: &str, change: Option<FileChange>, config: &Config) -> String {
+    let label = match change {
+        Some(FileChange::Added) => &config.file_added_label,
+        Some(FileChange::Removed) => &config.file_removed_label,
+        Some(FileChange::Modified) | Some(FileChange::Binary) => &config.file_modified_label,
+        // A directory containing changed files.
+        None => return name.to_string(),
+    };
+    let mut entry = if label.is_empty() {
+        name.to_string()
+    } else {
+        format!("{label} {name}")
+    };
+    if change == Some(FileChange::Binary) {
+        entry.push_str(" (binary)");
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::{make_config_from_args, DeltaTest};
+
+    #[test]
+    fn test_set_roots() {
+        let get_roots = |minus_path, plus_path| {
+            let mut directory_diff = DirectoryDiff::default();
+            directory_diff.set_roots(minus_path, plus_path);
+            directory_diff.roots
+        };
+        assert_eq!(
+            get_roots("a/src/main.rs", "b/src/main.rs"),
+            Some(("a".to_string(), "b".to_string()))
+        );
+        assert_eq!(
+            get_roots("/tmp/x/main.rs", "/tmp/y/main.rs"),
+            Some(("/tmp/x".to_string(), "/tmp/y".to_string()))
+        );
+        assert_eq!(get_roots("one.rs", "two.rs"), None);
+        assert_eq!(get_roots("a/main.rs", "a/main.rs"), None);
+        assert_eq!(get_roots("a/main.rs", "/dev/null"), None);
+    }
+
+    #[test]
+    fn test_format_tree_summary_lines() {
+        let config = make_config_from_args(&["--file-style", "raw"]);
+        let directory_diff = DirectoryDiff {
+            roots: Some(("a".to_string(), "b".to_string())),
+            changes: vec![
+                ("src/main.rs".to_string(), FileChange::Modified),
+                ("src/old.rs".to_string(), FileChange::Removed),
+                ("README.md".to_string(), FileChange::Added),
+                ("logo.png".to_string(), FileChange::Binary),
+            ],
+        };
+        assert_eq!(
+            format_tree_summary_lines(&directory_diff, &config),
+            vec![
+                "a ⟶   b",
+                "├── added: README.md",
+                "├── logo.png (binary)",
+                "└── src",
+                "    ├── main.rs",
+                "    └── removed: old.rs",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_only_in_lines() {
+        DeltaTest::with_args(&["--file-decoration-style", "none"])
+            .with_input(DIFF_RECURSIVE)
+            .expect_contains("\nremoved: a/src/old.rs\n")
+            .expect_contains("\nadded: b/README.md\n");
+    }
+
+    #[test]
+    fn test_only_in_line_of_unknown_directory() {
+        DeltaTest::with_args(&["--file-decoration-style", "none"])
+            .with_input("Only in a: README.md\n")
+            .expect_contains("\nOnly in a: README.md\n");
+    }
+
+    #[test]
+    fn test_common_subdirectories_line() {
+        DeltaTest::with_args(&[])
+            .with_input(DIFF_NON_RECURSIVE)
+            .expect_contains("\nCommon subdirectories: a/src and b/src\n");
+    }
+
+    #[test]
+    fn test_tree_summary() {
+        DeltaTest::with_args(&["--tree-summary"])
+            .with_input(DIFF_RECURSIVE)
+            .expect_contains(
+                "
+a ⟶   b
+├── added: README.md
+├── logo.png (binary)
+└── src
+    ├── main.rs
+    └── removed: old.rs
+",
+            );
+        DeltaTest::with_args(&["--tree-summary"])
+            .with_input(DIFF_NON_RECURSIVE)
+            .expect_contains(
+                "
+a ⟶   b
+├── removed: README.md
+└── main.rs
+",
+            );
+    }
+
+    #[test]
+    fn test_tree_summary_of_git_diff() {
+        DeltaTest::with_args(&["--tree-summary"])
+            .with_input(GIT_DIFF)
+            .expect_contains(
+                "
+.
+├── logo.png (binary)
+└── src
+    └── added: lib.rs
+",
+            );
+    }
+
+    const DIFF_RECURSIVE: &str = "\
+Binary files a/logo.png and b/logo.png differ
+diff -ru a/src/main.rs b/src/main.rs
+--- a/src/main.rs	2019-11-20 06:47:56.000000000 +0100
++++ b/src/main.rs	2019-11-20 06:47:56.000000000 +0100
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+Only in a/src: old.rs
+Only in b: README.md
+";
+
+    const DIFF_NON_RECURSIVE: &str = "\
+diff -u a/main.rs b/main.rs
+--- a/main.rs	2019-11-20 06:47:56.000000000 +0100
++++ b/main.rs	2019-11-20 06:47:56.000000000 +0100
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+Only in a: README.md
+Common subdirectories: a/src and b/src
+";
+
+    const GIT_DIFF: &str = "\
+diff --git a/logo.png b/logo.png
+index 8b13789..e69de29 100644
+Binary files a/logo.png and b/logo.png differ
+diff --git a/src/lib.rs b/src/lib.rs
+new file mode 100644
+index 0000000..e69de29
+--- /dev/null
++++ b/src/lib.rs
+@@ -0,0 +1 @@
++pub mod x;
+";
+}
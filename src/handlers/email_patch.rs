@@ -0,0 +1,300 @@
+// A module for handling email patches, as output by `git format-patch` and stored in mbox files.
+//
+// Each patch is an email:
+// ```
+// From 4b825dc642cb6eb9a060e54bf8d69288fbee4904 Mon Sep 17 00:00:00 2001
+// From: Dan Davison <dandavison7@gmail.com>
+// Date: Sat, 11 Dec 2021 17:08:56 -0500
+// Subject: [PATCH 2/5] Change x
+//
+// Commit message body.
+// ---
+//  src/main.rs | 2 +-
+//  1 file changed, 1 insertion(+), 1 deletion(-)
+//
+// diff --git a/src/main.rs b/src/main.rs
+// ...
+// --
+// 2.40.0
+// ```
+// Unless commit-style is raw, the email headers are introduced by a commit block, which shows the
+// position of the patch in its series, the `---` separator is replaced by an empty line, and the
+// signature is removed. The diffstat is handled like that of `git log --stat`.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::delta::{State, StateMachine};
+use crate::handlers::hunk_header::RemainingHunkLines;
+use crate::style::DecorationStyle;
+
+#[derive(Debug)]
+pub struct EmailPatch {
+    commit: Option<String>,
+    from_line: String,
+    header_lines: Vec<String>,
+    series: Option<(usize, usize)>,
+    section: EmailPatchSection,
+    // The lines still to come of the hunk being read, if any.
+    hunk: Option<RemainingHunkLines>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum EmailPatchSection {
+    Headers,
+    Message,
+    Diff,
+    Signature,
+}
+
+lazy_static! {
+    // E.g. "From 4b825dc642cb6eb9a060e54bf8d69288fbee4904 Mon Sep 17 00:00:00 2001"
+    static ref EMAIL_PATCH_FROM_LINE_REGEX: Regex =
+        Regex::new(r"^From (\S+) \w{3} \w{3} [ \d]\d \d{2}:\d{2}:\d{2} \d{4}$").unwrap();
+    // E.g. "Subject: [PATCH 2/5] Change x" and "Subject: [RFC PATCH v2 10/12] Change x"
+    static ref EMAIL_PATCH_SUBJECT_SERIES_REGEX: Regex =
+        Regex::new(r"^Subject: \[[^\]]*\bPATCH\b[^\]]*?\b(\d+)/(\d+)\]").unwrap();
+    static ref COMMIT_HASH_REGEX: Regex = Regex::new(r"^[0-9a-f]{7,40}$").unwrap();
+}
+
+/// Does this line start an email patch?
+pub fn is_email_patch_from_line(line: &str) -> bool {
+    EMAIL_PATCH_FROM_LINE_REGEX.is_match(line)
+}
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_email_patch_line(&self) -> bool {
+        self.email_patch.is_some() || is_email_patch_from_line(&self.line)
+    }
+
+    /// Handle the lines of an email patch that are not part of its commit message or its diff.
+    pub fn handle_email_patch_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_email_patch_line() {
+            return Ok(false);
+        }
+        if let Some(caps) = EMAIL_PATCH_FROM_LINE_REGEX.captures(&self.line) {
+            let commit = Some(&caps[1])
+                .filter(|token| COMMIT_HASH_REGEX.is_match(token))
+                .map(str::to_string);
+            self.painter.paint_buffered_minus_and_plus_lines();
+            self.handle_pending_line_with_diff_name()?;
+            self.state = State::CommitMeta;
+            self.email_patch = Some(EmailPatch {
+                commit,
+                from_line: self.raw_line.clone(),
+                header_lines: Vec::new(),
+                series: None,
+                section: EmailPatchSection::Headers,
+                hunk: None,
+            });
+            return Ok(self.should_decorate_email_patch());
+        }
+        let should_decorate = self.should_decorate_email_patch();
+        let email_patch = self.email_patch.as_mut().unwrap();
+        if email_patch.section == EmailPatchSection::Diff {
+            // A removed line "- " in a hunk reads as the signature separator "-- ".
+            if let Some(hunk) = email_patch.hunk.as_mut() {
+                if hunk.count_line(&self.line) {
+                    return Ok(false);
+                }
+                email_patch.hunk = None;
+            }
+            if self.line.starts_with("@@") {
+                email_patch.hunk = RemainingHunkLines::from_hunk_header_line(&self.line);
+                return Ok(false);
+            }
+        }
+        match email_patch.section {
+            EmailPatchSection::Headers if self.line.is_empty() => {
+                email_patch.section = EmailPatchSection::Message;
+                if should_decorate {
+                    self.write_email_patch_headers()?;
+                }
+                Ok(false)
+            }
+            EmailPatchSection::Headers => {
+                if let Some(caps) = EMAIL_PATCH_SUBJECT_SERIES_REGEX.captures(&self.line) {
+                    email_patch.series = caps[1].parse().ok().zip(caps[2].parse().ok());
+                }
+                if should_decorate {
+                    email_patch.header_lines.push(self.raw_line.clone());
+                }
+                Ok(should_decorate)
+            }
+            EmailPatchSection::Message if self.line == "---" => {
+                email_patch.section = EmailPatchSection::Diff;
+                if should_decorate {
+                    self.painter.emit()?;
+                    writeln!(self.painter.writer)?;
+                }
+                Ok(should_decorate)
+            }
+            EmailPatchSection::Diff if self.line == "-- " => {
+                email_patch.section = EmailPatchSection::Signature;
+                self.painter.paint_buffered_minus_and_plus_lines();
+                self.handle_pending_line_with_diff_name()?;
+                self.state = State::CommitMeta;
+                if !should_decorate {
+                    self.emit_line_unchanged()?;
+                }
+                Ok(true)
+            }
+            EmailPatchSection::Signature => Ok(should_decorate && !self.line.is_empty()),
+            _ => Ok(false),
+        }
+    }
+
+    fn should_decorate_email_patch(&self) -> bool {
+        let style = self.config.commit_style;
+        let is_raw = style.is_raw && style.decoration_style == DecorationStyle::NoDecoration;
+        !(self.config.color_only || is_raw)
+    }
+
+    /// Write the commit block of an email patch, followed by its headers.
+    fn write_email_patch_headers(&mut self) -> std::io::Result<()> {
+        let email_patch = self.email_patch.as_mut().unwrap();
+        let mut line = match &email_patch.commit {
+            Some(commit) => format!("commit {commit}"),
+            None => email_patch.from_line.clone(),
+        };
+        if let Some((n, m)) = email_patch.series {
+            line.push_str(&format!(" (patch {n}/{m})"));
+        }
+        let header_lines = std::mem::take(&mut email_patch.header_lines);
+        let (line, raw_line) = (
+            std::mem::replace(&mut self.line, line.clone()),
+            std::mem::replace(&mut self.raw_line, line),
+        );
+        self.painter.emit()?;
+        self._handle_commit_meta_header_line()?;
+        self.line = line;
+        self.raw_line = raw_line;
+        for header_line in header_lines {
+            writeln!(self.painter.writer, "{header_line}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_email_patch() {
+        DeltaTest::with_args(&["--commit-style", "yellow"])
+            .with_input(GIT_FORMAT_PATCH)
+            .expect_after_skip(
+                0,
+                "
+#indent_mark
+commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e (patch 1/2)
+From: Dan Davison <dandavison7@gmail.com>
+Date: Sat, 11 Dec 2021 17:08:56 -0500
+Subject: [PATCH 1/2] Change x
+
+Change x to 2.
+
+ src/main.rs | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+
+src/main.rs
+────────────────────────────────────────────────────────────────────────────────
+
+───┐
+1: │
+───┘
+fn main() {
+    let x = 1;
+    let x = 2;
+}
+
+commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904 (patch 2/2)
+From: Dan Davison <dandavison7@gmail.com>
+Date: Sat, 11 Dec 2021 17:09:56 -0500
+Subject: [PATCH 2/2] Remove y
+
+
+ src/y.rs | 1 -
+ 1 file changed, 1 deletion(-)
+",
+            );
+    }
+
+    #[test]
+    fn test_email_patch_raw_commit_style() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_FORMAT_PATCH)
+            .expect_contains(
+                "From 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e Mon Sep 17 00:00:00 2001\n",
+            )
+            .expect_contains("\n-- \n2.40.0\n")
+            .expect_contains("    let x = 2;\n}\n-- \n");
+    }
+
+    #[test]
+    fn test_email_patch_removed_line_like_signature_separator() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_FORMAT_PATCH_REMOVING_LIST_ITEM)
+            // The hunk lines are painted without their markers, and the signature is written raw.
+            .expect_contains("\nstart\n- \n- item2\nend\n-- \n2.40.0\n");
+    }
+
+    const GIT_FORMAT_PATCH_REMOVING_LIST_ITEM: &str = "\
+From 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e Mon Sep 17 00:00:00 2001
+From: Dan Davison <dandavison7@gmail.com>
+Date: Sat, 11 Dec 2021 17:08:56 -0500
+Subject: [PATCH] Change list
+
+---
+ README.md | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+diff --git a/README.md b/README.md
+index 8b13789..e69de29 100644
+--- a/README.md
++++ b/README.md
+@@ -1,3 +1,3 @@
+ start
+--\x20
++- item2
+ end
+--\x20
+2.40.0
+";
+
+    const GIT_FORMAT_PATCH: &str = "\
+From 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e Mon Sep 17 00:00:00 2001
+From: Dan Davison <dandavison7@gmail.com>
+Date: Sat, 11 Dec 2021 17:08:56 -0500
+Subject: [PATCH 1/2] Change x
+
+Change x to 2.
+---
+ src/main.rs | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+diff --git a/src/main.rs b/src/main.rs
+index 8b13789..e69de29 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+--\x20
+2.40.0
+
+From 4b825dc642cb6eb9a060e54bf8d69288fbee4904 Mon Sep 17 00:00:00 2001
+From: Dan Davison <dandavison7@gmail.com>
+Date: Sat, 11 Dec 2021 17:09:56 -0500
+Subject: [PATCH 2/2] Remove y
+
+---
+ src/y.rs | 1 -
+ 1 file changed, 1 deletion(-)
+";
+}
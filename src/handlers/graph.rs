@@ -0,0 +1,357 @@
+// A module for handling `git log --graph` output.
+//
+// With `--graph`, git prefixes every line of its output with the commit graph, e.g.
+// ```
+// * commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+// | Author: ...
+// |
+// | diff --git a/src/main.rs b/src/main.rs
+// | --- a/src/main.rs
+// | +++ b/src/main.rs
+// | @@ -1,3 +1,3 @@
+// |  fn main() {
+// | -    let x = 1;
+// | +    let x = 2;
+// ```
+// The graph prefix is removed before the line is handled, and is emitted, with its colors, as a
+// gutter at the start of every output line. The width of the graph is fixed between two commit
+// lines; delta's output for the lines of a commit is laid out within the remaining width.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+
+use bytelines::ByteLines;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::ansi;
+use crate::cli;
+use crate::config::Config;
+use crate::delta::StateMachine;
+use crate::features::side_by_side::{ansifill, SideBySideData};
+use crate::handlers::hunk_header::RemainingHunkLines;
+
+const GRAPH_CHARS: &[char] = &['*', '|', '/', '\\', '_', '-', '.', ' '];
+
+lazy_static! {
+    // The commit node "*" (or "*-." etc. for an octopus merge) is preceded by the edges of the
+    // graph, and followed by a space and `commit <hash>`, or by `<hash>` with `--oneline`, after
+    // any edges to its right.
+    static ref GRAPH_COMMIT_LINE_REGEX: Regex =
+        Regex::new(r"^((?:[|/\\_] ?)*\*[-.]* [|/\\_ ]*?)(?:commit )?[0-9a-f]{7,40}\b").unwrap();
+}
+
+/// Does this line start a commit in `git log --graph` output? The first line of the output must
+/// be such a line, with the commit in the leftmost column of the graph.
+pub fn is_graph_commit_line(raw_line_bytes: &[u8]) -> bool {
+    let line = ansi::strip_ansi_codes(&String::from_utf8_lossy(raw_line_bytes));
+    line.starts_with('*') && get_graph_commit_line_width(&line).is_some()
+}
+
+/// Return the width of the graph preceding the commit, if this is a commit line.
+fn get_graph_commit_line_width(line: &str) -> Option<usize> {
+    GRAPH_COMMIT_LINE_REGEX
+        .captures(line)
+        .map(|caps| caps.get(1).unwrap().end())
+}
+
+/// Split a raw line into the graph (with its colors) and the rest of the line, given the width of
+/// the graph. A line whose first `width` characters are not all graph characters has no graph.
+fn split_graph_line(raw_line: &str, width: usize) -> (String, String) {
+    let line = ansi::strip_ansi_codes(raw_line);
+    let graph_width = line
+        .chars()
+        .take(width)
+        .take_while(|c| GRAPH_CHARS.contains(c))
+        .count();
+    if graph_width < width && graph_width < line.chars().count() || graph_width == 0 {
+        return ("".to_string(), raw_line.to_string());
+    }
+    let graph_end = ansi::ansi_preserving_index(raw_line, graph_width - 1).unwrap() + 1;
+    (
+        raw_line[..graph_end].to_string(),
+        ansi::ansi_preserving_slice(raw_line, graph_width),
+    )
+}
+
+/// Return a copy of `config` with the output width reduced by the width of the graph.
+fn make_graph_config(config: &Config, graph_width: usize) -> Config {
+    let mut config = config.clone();
+    config.available_terminal_width = config.available_terminal_width.saturating_sub(graph_width);
+    if let cli::Width::Fixed(width) = config.decorations_width {
+        config.decorations_width = cli::Width::Fixed(width.saturating_sub(graph_width));
+    }
+    config.side_by_side_data = ansifill::UseFullPanelWidth::sbs_odd_fix(
+        &config.decorations_width,
+        &config.line_fill_method,
+        SideBySideData::new_sbs(
+            &config.decorations_width,
+            &config.available_terminal_width,
+            &config.side_by_side_split,
+        ),
+    );
+    config
+}
+
+/// A writer which starts every line with the current graph.
+struct GraphWriter<'a> {
+    writer: &'a mut dyn Write,
+    graph: Rc<RefCell<String>>,
+    at_line_start: bool,
+}
+
+impl<'a> Write for GraphWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        for chunk in buf.split_inclusive(|b| *b == b'\n') {
+            if self.at_line_start {
+                let graph = self.graph.borrow();
+                // Do not add trailing whitespace to empty lines.
+                let graph = if chunk == b"\n" {
+                    graph.trim_end_matches(' ')
+                } else {
+                    graph.as_str()
+                };
+                self.writer.write_all(graph.as_bytes())?;
+                if graph.contains('\x1b') {
+                    self.writer.write_all(b"\x1b[0m")?;
+                }
+            }
+            self.writer.write_all(chunk)?;
+            self.at_line_start = chunk.ends_with(b"\n");
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Process `git log --graph` output, starting with `first_line`.
+// A new StateMachine, with a Config adapted to the width of the graph, is used whenever the width
+// of the graph changes.
+pub fn delta_with_graph<I>(
+    first_line: Vec<u8>,
+    mut lines: ByteLines<I>,
+    writer: &mut dyn Write,
+    config: &Config,
+) -> std::io::Result<()>
+where
+    I: BufRead,
+{
+    let graph = Rc::new(RefCell::new(String::new()));
+    let mut writer = GraphWriter {
+        writer,
+        graph: Rc::clone(&graph),
+        at_line_start: true,
+    };
+    let mut graph_configs = HashMap::new();
+    let mut raw_line = String::from_utf8_lossy(&first_line).to_string();
+    let mut graph_width = get_graph_commit_line_width(&ansi::strip_ansi_codes(&raw_line)).unwrap();
+    loop {
+        let graph_config = graph_configs
+            .entry(graph_width)
+            .or_insert_with(|| make_graph_config(config, graph_width));
+        let mut state_machine = StateMachine::new(&mut writer, graph_config);
+        // Lines held back until the word diff format is decided would be written with the graph
+        // of a later line.
+        state_machine.is_word_diff_format_decided = true;
+        let mut new_graph_width = None;
+        // The lines still to come of the hunk being read, if any: their text may look like a
+        // commit line.
+        let mut hunk: Option<RemainingHunkLines> = None;
+        loop {
+            let (line_graph, line) = split_graph_line(&raw_line, graph_width);
+            if line_graph != *graph.borrow() {
+                // Lines buffered so far belong to the previous graph.
+                state_machine.painter.paint_buffered_minus_and_plus_lines();
+                state_machine.painter.emit()?;
+                *graph.borrow_mut() = line_graph;
+            }
+            state_machine.consume_line(line.as_bytes())?;
+            let line = ansi::strip_ansi_codes(&line);
+            if !hunk.as_mut().is_some_and(|hunk| hunk.count_line(&line)) {
+                hunk = Some(&line)
+                    .filter(|line| line.starts_with("@@"))
+                    .and_then(|line| RemainingHunkLines::from_hunk_header_line(line));
+            }
+            match lines.next() {
+                Some(Ok(raw_line_bytes)) => {
+                    raw_line = String::from_utf8_lossy(raw_line_bytes).to_string();
+                    if hunk.is_some_and(|hunk| !hunk.is_complete()) {
+                        continue;
+                    }
+                    new_graph_width =
+                        get_graph_commit_line_width(&ansi::strip_ansi_codes(&raw_line))
+                            .filter(|width| *width != graph_width);
+                    if new_graph_width.is_some() {
+                        break;
+                    }
+                }
+                _ => break,
+            }
+        }
+        state_machine.finish()?;
+        match new_graph_width {
+            Some(width) => graph_width = width,
+            None => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_get_graph_commit_line_width() {
+        for (line, expected) in [
+            ("* commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904", Some(2)),
+            (
+                "| * commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904",
+                Some(4),
+            ),
+            (
+                "*   commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904",
+                Some(4),
+            ),
+            ("* | 4b825dc Subject", Some(4)),
+            ("| diff --git a/src/main.rs b/src/main.rs", None),
+            ("|     * deadbeat", None),
+            ("|     * deadbeef", None),
+            ("| -* 1a2b3c4 old entry", None),
+            (
+                "*-.   commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904",
+                Some(6),
+            ),
+            ("commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904", None),
+        ] {
+            assert_eq!(get_graph_commit_line_width(line), expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_split_graph_line() {
+        for (raw_line, width, expected) in [
+            ("| +added", 2, ("| ", "+added")),
+            ("| | -removed", 4, ("| | ", "-removed")),
+            ("|  unchanged", 2, ("| ", " unchanged")),
+            ("|", 2, ("|", "")),
+            ("|/", 4, ("|/", "")),
+            ("diff --git a/x b/x", 2, ("", "diff --git a/x b/x")),
+            (
+                "\x1b[31m|\x1b[m +added",
+                2,
+                ("\x1b[31m|\x1b[m ", "\x1b[31m\x1b[m+added"),
+            ),
+        ] {
+            let (graph, line) = split_graph_line(raw_line, width);
+            assert_eq!((graph.as_str(), line.as_str()), expected, "{raw_line}");
+        }
+    }
+
+    #[test]
+    fn test_git_log_graph() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_LOG_GRAPH)
+            .expect_after_skip(
+                0,
+                "
+#indent_mark
+* commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+| Author: Dan Davison <dandavison7@gmail.com>
+| Date:   Sat Dec 11 17:08:56 2021 -0500
+|
+|     Change x
+|
+|
+| src/main.rs
+| ──────────────────────────────────────────────────────────────────────────────
+|
+| ───┐
+| 1: │
+| ───┘
+| fn main() {
+|     let x = 1;
+|     let x = 2;
+| }
+* commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+  Author: Dan Davison <dandavison7@gmail.com>",
+            );
+    }
+
+    #[test]
+    fn test_git_log_graph_side_by_side_width() {
+        let output = DeltaTest::with_args(&["--side-by-side", "--width", "40"])
+            .with_input(GIT_LOG_GRAPH)
+            .expect_contains("| │  1 │fn main() {  │  1 │fn main() {");
+        for line in output.output.lines().filter(|line| line.contains('│')) {
+            assert!(ansi::measure_text_width(line) <= 40, "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_git_log_graph_hunk_line_like_commit_line() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_LOG_GRAPH_HUNK_LINE_LIKE_COMMIT_LINE)
+            .expect_after_skip(
+                0,
+                "
+#indent_mark
+* commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+|
+|     Update changelog
+|
+|
+| CHANGELOG.md
+| ──────────────────────────────────────────────────────────────────────────────
+|
+| ───┐
+| 1: │
+| ───┘
+| Changes:
+| * 1a2b3c4 old entry
+| * 1a2b3c4 new entry
+* commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904",
+            );
+    }
+
+    const GIT_LOG_GRAPH: &str = "\
+* commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+| Author: Dan Davison <dandavison7@gmail.com>
+| Date:   Sat Dec 11 17:08:56 2021 -0500
+|
+|     Change x
+|
+| diff --git a/src/main.rs b/src/main.rs
+| index 8b13789..e69de29 100644
+| --- a/src/main.rs
+| +++ b/src/main.rs
+| @@ -1,3 +1,3 @@
+|  fn main() {
+| -    let x = 1;
+| +    let x = 2;
+|  }
+* commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+  Author: Dan Davison <dandavison7@gmail.com>
+";
+
+    const GIT_LOG_GRAPH_HUNK_LINE_LIKE_COMMIT_LINE: &str = "\
+* commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+|
+|     Update changelog
+|
+| diff --git a/CHANGELOG.md b/CHANGELOG.md
+| index 8b13789..e69de29 100644
+| --- a/CHANGELOG.md
+| +++ b/CHANGELOG.md
+| @@ -1,2 +1,2 @@
+|  Changes:
+| -* 1a2b3c4 old entry
+| +* 1a2b3c4 new entry
+* commit 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+";
+}
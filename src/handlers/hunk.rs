@@ -1,45 +1,13 @@
 use std::cmp::min;
 
-use lazy_static::lazy_static;
-
 use crate::cli;
 use crate::config::{delta_unreachable, Config};
 use crate::delta::{DiffType, InMergeConflict, MergeParents, State, StateMachine};
+use crate::handlers::word_diff::is_color_words;
 use crate::paint::{expand_tabs, prepare, prepare_raw_line};
 use crate::style;
-use crate::utils::process::{self, CallingProcess};
 use unicode_segmentation::UnicodeSegmentation;
 
-// HACK: WordDiff should probably be a distinct top-level line state
-pub fn is_word_diff() -> bool {
-    #[cfg(not(test))]
-    {
-        *CACHED_IS_WORD_DIFF
-    }
-    #[cfg(test)]
-    {
-        compute_is_word_diff()
-    }
-}
-
-lazy_static! {
-    static ref CACHED_IS_WORD_DIFF: bool = compute_is_word_diff();
-}
-
-fn compute_is_word_diff() -> bool {
-    match &*process::calling_process() {
-        CallingProcess::GitDiff(cmd_line)
-        | CallingProcess::GitShow(cmd_line, _)
-        | CallingProcess::GitLog(cmd_line)
-        | CallingProcess::GitReflog(cmd_line) => {
-            cmd_line.long_options.contains("--word-diff")
-                || cmd_line.long_options.contains("--word-diff-regex")
-                || cmd_line.long_options.contains("--color-words")
-        }
-        _ => false,
-    }
-}
-
 impl<'a> StateMachine<'a> {
     #[inline]
     fn test_hunk_line(&self) -> bool {
@@ -103,7 +71,7 @@ impl<'a> StateMachine<'a> {
                 // sequence of consecutive minus (removed) and/or plus (added) lines). Process that
                 // subhunk and flush the line buffers.
                 self.painter.paint_buffered_minus_and_plus_lines();
-                let n_parents = if is_word_diff() {
+                let n_parents = if is_color_words() {
                     0
                 } else {
                     diff_type.n_parents()
@@ -139,7 +107,7 @@ fn maybe_raw_line(
     non_raw_styles: &[style::Style],
     config: &Config,
 ) -> Option<String> {
-    let emit_raw_line = is_word_diff()
+    let emit_raw_line = is_color_words()
         || config.inspect_raw_lines == cli::InspectRawLines::True
             && style::line_has_style_other_than(raw_line, non_raw_styles)
         || state_style_is_raw;
@@ -162,7 +130,7 @@ fn new_line_state(
     use MergeParents::*;
     use State::*;
 
-    if is_word_diff() {
+    if is_color_words() {
         return Some(HunkZero(
             Unified,
             maybe_raw_line(new_raw_line, config.zero_style.is_raw, 0, &[], config),
@@ -291,7 +259,7 @@ mod tests {
 (blue)───(blue)┐(normal)
 (blue)1(normal): (blue)│(normal)
 (blue)───(blue)┘(normal)
-    (red)[-aaa-](green){+bbb+}(normal)
+(231)    (normal 124)aaa(231 28)bbb(normal)
 ",
                 );
         }
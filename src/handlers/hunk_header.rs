@@ -27,8 +27,10 @@ use regex::Regex;
 use super::draw;
 use crate::config::Config;
 use crate::delta::{self, DiffType, InMergeConflict, MergeParents, State, StateMachine};
+use crate::handlers::adaptive_panels::{self, AdaptivePanelsHunk};
 use crate::paint::{self, BgShouldFill, Painter, StyleSectionSpecifier};
 use crate::style::DecorationStyle;
+use crate::utils::process;
 
 #[derive(Clone, Default, Debug, PartialEq, Eq)]
 pub struct ParsedHunkHeader {
@@ -36,6 +38,59 @@ pub struct ParsedHunkHeader {
     line_numbers_and_hunk_lengths: Vec<(usize, usize)>,
 }
 
+/// The numbers of lines of a hunk still to come, on its minus and plus sides, as given by the
+/// lengths in its header.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RemainingHunkLines {
+    n_minus_lines: usize,
+    n_plus_lines: usize,
+}
+
+impl RemainingHunkLines {
+    /// Return the lines of a hunk given the line numbers and lengths in its header, unless it is
+    /// a hunk of a combined diff.
+    pub fn new(line_numbers_and_hunk_lengths: &[(usize, usize)]) -> Option<Self> {
+        match line_numbers_and_hunk_lengths {
+            [(_, n_minus_lines), (_, n_plus_lines)] => Some(Self {
+                n_minus_lines: *n_minus_lines,
+                n_plus_lines: *n_plus_lines,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Return the lines of the hunk introduced by a hunk header line.
+    pub fn from_hunk_header_line(line: &str) -> Option<Self> {
+        Self::new(&parse_hunk_header(line)?.line_numbers_and_hunk_lengths)
+    }
+
+    /// Count `line` as a line of the hunk, returning false if it is not one.
+    pub fn count_line(&mut self, line: &str) -> bool {
+        let (is_minus, is_plus) = match line.chars().next() {
+            Some('-') => (true, false),
+            Some('+') => (false, true),
+            Some(' ') => (true, true),
+            // "\ No newline at end of file"
+            Some('\\') => (false, false),
+            _ => return false,
+        };
+        if is_minus && self.n_minus_lines == 0 || is_plus && self.n_plus_lines == 0 {
+            return false;
+        }
+        if is_minus {
+            self.n_minus_lines -= 1;
+        }
+        if is_plus {
+            self.n_plus_lines -= 1;
+        }
+        true
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.n_minus_lines == 0 && self.n_plus_lines == 0
+    }
+}
+
 impl<'a> StateMachine<'a> {
     #[inline]
     fn test_hunk_header_line(&self) -> bool {
@@ -65,6 +120,25 @@ impl<'a> StateMachine<'a> {
                 | HunkPlus(diff_type, _) => diff_type.clone(),
                 _ => Unified,
             };
+            if self.state == Unknown {
+                // A hunk without a file header, as in a `.rej` file written by patch(1).
+                let extension = process::reject_filename_extension()
+                    .or_else(|| self.config.default_language.clone());
+                self.painter.set_syntax(extension.as_deref());
+            }
+            if adaptive_panels::holds_back_hunks(self.config) {
+                // Paint the lines of the previous hunk before the panels and layout of this one are
+                // chosen.
+                self.painter.paint_buffered_minus_and_plus_lines();
+                if let Some(line_numbers_data) = self.painter.line_numbers_data.as_mut() {
+                    line_numbers_data.hunk_panel_widths = None;
+                    line_numbers_data.hunk_is_unified = false;
+                }
+                self.adaptive_panels_hunk = AdaptivePanelsHunk::new(
+                    &parsed_hunk_header.line_numbers_and_hunk_lengths,
+                    self.config,
+                );
+            }
             self.state = HunkHeader(
                 diff_type,
                 parsed_hunk_header,
@@ -316,6 +390,7 @@ pub mod tests {
     use super::*;
     use crate::ansi::strip_ansi_codes;
     use crate::tests::integration_test_utils;
+    use crate::utils::process::tests::FakeParentArgs;
 
     #[test]
     fn test_parse_hunk_header() {
@@ -487,6 +562,68 @@ pub mod tests {
         assert!(output.contains("@@@2021-12-05"));
     }
 
+    #[test]
+    fn test_reject_file_hunks() {
+        let get_syntax_name = |input: &str, args: &[&str]| {
+            let config = integration_test_utils::make_config_from_args(args);
+            let mut writer = Vec::new();
+            let mut state_machine = StateMachine::new(&mut writer, &config);
+            for line in input.lines() {
+                state_machine.consume_line(line.as_bytes()).unwrap();
+            }
+            state_machine.finish().unwrap();
+            assert!(matches!(
+                state_machine.state,
+                State::HunkZero(DiffType::Unified, _)
+            ));
+            state_machine.painter.syntax.name.clone()
+        };
+        assert_eq!(get_syntax_name(REJECT_FILE, &[]), "Plain Text");
+        assert_eq!(
+            get_syntax_name(REJECT_FILE, &["--default-language", "rs"]),
+            "Rust"
+        );
+        // A `.rej` file written by patch(1) from a context diff.
+        assert_eq!(
+            get_syntax_name(CONTEXT_REJECT_FILE, &["--default-language", "rs"]),
+            "Rust"
+        );
+        let _args = FakeParentArgs::for_scope("cat src/main.py.rej");
+        assert_eq!(
+            get_syntax_name(REJECT_FILE, &["--default-language", "rs"]),
+            "Python"
+        );
+    }
+
+    #[test]
+    fn test_reject_file_hunks_are_styled() {
+        let config = integration_test_utils::make_config_from_args(&[]);
+        let output = integration_test_utils::run_delta(CONTEXT_REJECT_FILE, &config);
+        let output = strip_ansi_codes(&output);
+        assert!(output.contains("\n1: │\n"));
+        assert!(output.contains("\n    let x = 1;\n    let x = 2;\n"));
+    }
+
+    const REJECT_FILE: &str = "\
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+";
+
+    const CONTEXT_REJECT_FILE: &str = "\
+***************
+*** 1,3 ****
+  fn main() {
+!     let x = 1;
+  }
+--- 1,3 ----
+  fn main() {
+!     let x = 2;
+  }
+";
+
     const GIT_LOG_OUTPUT_WITH_NOT_A_HUNK_HEADER: &str = "\
 @@@2021-12-05
 
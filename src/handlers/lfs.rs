@@ -0,0 +1,364 @@
+// A module for handling changes of files tracked by Git LFS. Git shows the change of such a file as
+// a change of its pointer file, e.g.
+// ```
+// diff --git a/assets/logo.psd b/assets/logo.psd
+// index 8b13789..e69de29 100644
+// --- a/assets/logo.psd
+// +++ b/assets/logo.psd
+// @@ -1,3 +1,3 @@
+//  version https://git-lfs.github.com/spec/v1
+// -oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393
+// -size 12345
+// +oid sha256:8f2b9e0a4e2c6d8e59f5c4b7c0f1a8e3d2b6c9a7e5f4d3c2b1a0f9e8d7c6b5a4
+// +size 23456
+// ```
+// Whether a file is a pointer file is only known from its first hunk line, so the "+++" and hunk
+// header lines are held back until then. The file header is labeled "LFS", and the hunk, whose
+// end is known from the lengths in its header, is replaced by a line summarizing the change of the
+// size and oid of the file.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::draw;
+use crate::config::Config;
+use crate::delta::{Source, State, StateMachine};
+use crate::handlers::hunk_header::RemainingHunkLines;
+
+/// The lines of the pointer files of the two versions of a file tracked by Git LFS.
+#[derive(Debug, Default)]
+pub struct LfsPointerDiff {
+    minus_pointer: Option<LfsPointer>,
+    plus_pointer: Option<LfsPointer>,
+    remaining_lines: RemainingHunkLines,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct LfsPointer {
+    oid: Option<String>,
+    size: Option<u64>,
+}
+
+const LFS_POINTER_VERSION_LINE: &str = "version https://git-lfs.github.com/spec/v1";
+
+lazy_static! {
+    // E.g. "-oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393"
+    static ref LFS_POINTER_LINE_REGEX: Regex =
+        Regex::new(r"^([ +-])(version|oid|size|ext-\d+-\w+) (.+)$").unwrap();
+}
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_lfs_line(&self) -> bool {
+        self.source == Source::GitDiff
+            && !self.config.color_only
+            && !self.config.hunk_header_style.is_raw
+            && (self.lfs_pointer_diff.is_some()
+                || !self.lfs_lookahead_lines.is_empty()
+                || matches!(self.state, State::DiffHeader(_)) && self.line.starts_with("+++ "))
+    }
+
+    /// Hold back the "+++" and hunk header lines of a file until its first hunk line shows whether
+    /// it is a Git LFS pointer file, and summarize the change of a pointer file.
+    pub fn handle_lfs_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_lfs_line() {
+            return Ok(false);
+        }
+        if self.lfs_pointer_diff.is_some() {
+            return self.handle_lfs_pointer_line();
+        }
+        match self.lfs_lookahead_lines.len() {
+            0 => {
+                self.lfs_lookahead_lines
+                    .push((self.line.clone(), self.raw_line.clone()));
+                Ok(true)
+            }
+            1 if self.line.starts_with("@@ ") => {
+                self.lfs_lookahead_lines
+                    .push((self.line.clone(), self.raw_line.clone()));
+                Ok(true)
+            }
+            2 if self.line.get(1..) == Some(LFS_POINTER_VERSION_LINE) => {
+                let remaining_lines =
+                    RemainingHunkLines::from_hunk_header_line(&self.lfs_lookahead_lines[1].0)
+                        .unwrap_or_default();
+                self.lfs_pointer_diff = Some(LfsPointerDiff {
+                    remaining_lines,
+                    ..LfsPointerDiff::default()
+                });
+                // Only the "+++" line is handled: the hunk is replaced by the summary.
+                self.lfs_lookahead_lines.truncate(1);
+                self.handle_lfs_lookahead_lines()?;
+                self.handle_lfs_pointer_line()
+            }
+            _ => {
+                self.handle_lfs_lookahead_lines()?;
+                // The lines of the hunk may be held back from its header on.
+                Ok(
+                    self.handle_adaptive_panels_line()?
+                        || self.handle_word_diff_lookahead_line()?,
+                )
+            }
+        }
+    }
+
+    /// Handle the lines held back, as if the current line had not been seen yet.
+    fn handle_lfs_lookahead_lines(&mut self) -> std::io::Result<()> {
+        let current_line = (self.line.clone(), self.raw_line.clone());
+        for (line, raw_line) in std::mem::take(&mut self.lfs_lookahead_lines) {
+            self.line = line;
+            self.raw_line = raw_line;
+            let _ = self.handle_diff_header_plus_line()?
+                || self.handle_hunk_header_line()?
+                || self.should_skip_line()
+                || self.emit_line_unchanged()?;
+        }
+        self.line = current_line.0;
+        self.raw_line = current_line.1;
+        Ok(())
+    }
+
+    /// Add a line of the hunk to the pointer files, and write the summary once the hunk has ended.
+    fn handle_lfs_pointer_line(&mut self) -> std::io::Result<bool> {
+        let handled_line = self.update_lfs_pointer_diff();
+        let is_hunk_end = match &self.lfs_pointer_diff {
+            Some(lfs_pointer_diff) => lfs_pointer_diff.remaining_lines.is_complete(),
+            None => true,
+        };
+        if !handled_line || is_hunk_end {
+            self.flush_lfs_pointer_diff()?;
+        }
+        Ok(handled_line)
+    }
+
+    /// Add the current line to the pointer files, returning false if it is not a pointer file line.
+    fn update_lfs_pointer_diff(&mut self) -> bool {
+        let (lfs_pointer_diff, caps) = match (
+            self.lfs_pointer_diff.as_mut(),
+            LFS_POINTER_LINE_REGEX.captures(&self.line),
+        ) {
+            (Some(lfs_pointer_diff), Some(caps)) => (lfs_pointer_diff, caps),
+            _ => return false,
+        };
+        if !lfs_pointer_diff.remaining_lines.count_line(&self.line) {
+            return false;
+        }
+        let mut pointers = Vec::new();
+        if &caps[1] != "+" {
+            pointers.push(
+                lfs_pointer_diff
+                    .minus_pointer
+                    .get_or_insert_with(Default::default),
+            );
+        }
+        if &caps[1] != "-" {
+            pointers.push(
+                lfs_pointer_diff
+                    .plus_pointer
+                    .get_or_insert_with(Default::default),
+            );
+        }
+        for pointer in pointers {
+            match &caps[2] {
+                "oid" => pointer.oid = Some(caps[3].to_string()),
+                "size" => pointer.size = caps[3].parse().ok(),
+                _ => {}
+            }
+        }
+        true
+    }
+
+    /// Handle the lines held back, and write the summary of the pointer file being handled, if any.
+    pub fn flush_lfs(&mut self) -> std::io::Result<()> {
+        self.handle_lfs_lookahead_lines()?;
+        self.flush_lfs_pointer_diff()
+    }
+
+    fn flush_lfs_pointer_diff(&mut self) -> std::io::Result<()> {
+        let lfs_pointer_diff = match self.lfs_pointer_diff.take() {
+            Some(lfs_pointer_diff) => lfs_pointer_diff,
+            None => return Ok(()),
+        };
+        let style = self.config.hunk_header_style;
+        if style.is_omitted {
+            return Ok(());
+        }
+        self.painter.emit()?;
+        writeln!(self.painter.writer)?;
+        let (mut draw_fn, pad, decoration_ansi_term_style) =
+            draw::get_draw_function(self.config.hunk_header_style.decoration_style);
+        let text = format!(
+            "{}{}",
+            format_lfs_pointer_diff_summary(&lfs_pointer_diff, self.config),
+            if pad { " " } else { "" }
+        );
+        draw_fn(
+            self.painter.writer,
+            &text,
+            &text,
+            "",
+            &self.config.decorations_width,
+            style,
+            decoration_ansi_term_style,
+        )?;
+        Ok(())
+    }
+}
+
+/// Return e.g. "12.1 KiB ⟶   22.9 KiB (+10.8 KiB), 4d7a214 ⟶   8f2b9e0".
+fn format_lfs_pointer_diff_summary(lfs_pointer_diff: &LfsPointerDiff, config: &Config) -> String {
+    let minus_pointer = lfs_pointer_diff.minus_pointer.as_ref();
+    let plus_pointer = lfs_pointer_diff.plus_pointer.as_ref();
+    let get_size = |pointer: Option<&LfsPointer>| pointer.and_then(|pointer| pointer.size);
+    let format_size = |size: Option<u64>| match size {
+        Some(size) => format_human_readable_size(size),
+        None => "none".to_string(),
+    };
+    let format_oid = |pointer: Option<&LfsPointer>| match pointer.and_then(|p| p.oid.as_deref()) {
+        Some(oid) => {
+            let hash = oid.split_once(':').map(|(_, hash)| hash).unwrap_or(oid);
+            hash.chars().take(7).collect()
+        }
+        None => "none".to_string(),
+    };
+    let (minus_size, plus_size) = (get_size(minus_pointer), get_size(plus_pointer));
+    let size_delta = plus_size.unwrap_or(0) as i128 - minus_size.unwrap_or(0) as i128;
+    let sign = match size_delta {
+        size_delta if size_delta > 0 => "+",
+        size_delta if size_delta < 0 => "-",
+        _ => "±",
+    };
+    format!(
+        "{} {} {} ({}{}), {} {} {}",
+        format_size(minus_size),
+        config.right_arrow,
+        format_size(plus_size),
+        sign,
+        format_human_readable_size(size_delta.unsigned_abs() as u64),
+        format_oid(minus_pointer),
+        config.right_arrow,
+        format_oid(plus_pointer)
+    )
+}
+
+/// Return e.g. "512 B" and "22.9 KiB".
+fn format_human_readable_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if size < 1024 {
+        return format!("{size} B");
+    }
+    let mut size = size as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{size:.1} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_format_human_readable_size() {
+        assert_eq!(format_human_readable_size(0), "0 B");
+        assert_eq!(format_human_readable_size(1023), "1023 B");
+        assert_eq!(format_human_readable_size(12345), "12.1 KiB");
+        assert_eq!(format_human_readable_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn test_lfs_pointer_change() {
+        DeltaTest::with_args(&[])
+            .with_input(LFS_POINTER_CHANGE)
+            .expect_after_skip(
+                0,
+                "
+#indent_mark
+
+LFS assets/logo.psd
+────────────────────────────────────────────────────────────────────────────────
+
+───────────────────────────────────────────────────────┐
+12.1 KiB ⟶   22.9 KiB (+10.9 KiB), 4d7a214 ⟶   8f2b9e0 │
+───────────────────────────────────────────────────────┘
+",
+            );
+    }
+
+    #[test]
+    fn test_lfs_pointer_added() {
+        DeltaTest::with_args(&["--hunk-header-decoration-style", "none"])
+            .with_input(LFS_POINTER_ADDED)
+            .expect_contains("\nLFS added: assets/logo.psd\n")
+            .expect_contains("\nnone ⟶   512 B (+512 B), none ⟶   8f2b9e0\n");
+    }
+
+    #[test]
+    fn test_non_lfs_file_is_unchanged() {
+        DeltaTest::with_args(&["--hunk-header-decoration-style", "none"])
+            .with_input(NON_LFS_CHANGE)
+            .expect_contains("\nsrc/main.rs\n")
+            .expect_contains("\nfn main() {\n    let x = 1;");
+    }
+
+    #[test]
+    fn test_lfs_color_only_and_raw() {
+        for args in [&["--color-only"], &["--raw"]] {
+            DeltaTest::with_args(args)
+                .with_input(LFS_POINTER_CHANGE)
+                .expect_contains("\n version https://git-lfs.github.com/spec/v1\n-oid sha256:");
+        }
+    }
+
+    const LFS_POINTER_CHANGE: &str = "\
+diff --git a/assets/logo.psd b/assets/logo.psd
+index 8b13789..e69de29 100644
+--- a/assets/logo.psd
++++ b/assets/logo.psd
+@@ -1,3 +1,3 @@
+ version https://git-lfs.github.com/spec/v1
+-oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393
+-size 12345
++oid sha256:8f2b9e0a4e2c6d8e59f5c4b7c0f1a8e3d2b6c9a7e5f4d3c2b1a0f9e8d7c6b5a4
++size 23456
+";
+
+    const LFS_POINTER_ADDED: &str = "\
+diff --git a/assets/logo.psd b/assets/logo.psd
+new file mode 100644
+index 0000000..e69de29
+--- /dev/null
++++ b/assets/logo.psd
+@@ -0,0 +1,3 @@
++version https://git-lfs.github.com/spec/v1
++oid sha256:8f2b9e0a4e2c6d8e59f5c4b7c0f1a8e3d2b6c9a7e5f4d3c2b1a0f9e8d7c6b5a4
++size 512
+diff --git a/src/main.rs b/src/main.rs
+index 8b13789..e69de29 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+";
+
+    const NON_LFS_CHANGE: &str = "\
+diff --git a/src/main.rs b/src/main.rs
+index 8b13789..e69de29 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+";
+}
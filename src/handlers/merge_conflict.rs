@@ -7,6 +7,7 @@ use super::draw;
 use crate::cli;
 use crate::config::{self, delta_unreachable};
 use crate::delta::{DiffType, InMergeConflict, MergeParents, State, StateMachine};
+use crate::features::side_by_side;
 use crate::minusplus::MinusPlus;
 use crate::paint::{self, prepare};
 use crate::style::Style;
@@ -19,9 +20,9 @@ pub enum MergeConflictCommit {
 }
 
 pub struct MergeConflictCommits<T> {
-    ours: T,
-    ancestral: T,
-    theirs: T,
+    pub ours: T,
+    pub ancestral: T,
+    pub theirs: T,
 }
 
 pub type MergeConflictLines = MergeConflictCommits<Vec<(String, State)>>;
@@ -142,6 +143,35 @@ impl<'a> StateMachine<'a> {
             &mut self.painter,
             self.config,
         )?;
+        // Without the ancestral lines (see merge.conflictStyle), there is no base for a third panel.
+        if self.config.side_by_side && self.painter.merge_conflict_commit_names[Ancestral].is_some()
+        {
+            side_by_side::paint_merge_conflict_lines_in_three_panels(
+                &self.painter.merge_conflict_lines,
+                &self.painter.merge_conflict_commit_names,
+                &mut self.painter.highlighter,
+                &mut self.painter.line_numbers_data,
+                &mut self.painter.output_buffer,
+                self.config,
+            );
+            self.painter.emit()?;
+        } else {
+            self.paint_merge_conflict_lines_as_two_diffs()?;
+        }
+        // write_merge_conflict_decoration("bold ol", &mut self.painter, self.config)?;
+        write_merge_conflict_bar(
+            &self.config.merge_conflict_end_symbol,
+            &mut self.painter,
+            self.config,
+        )?;
+        self.painter.merge_conflict_lines.clear();
+        self.state = HunkZero(Combined(merge_parents.clone(), InMergeConflict::No), None);
+        Ok(())
+    }
+
+    /// Paint the changes of ours and of theirs, relative to the ancestral lines, one after the
+    /// other.
+    fn paint_merge_conflict_lines_as_two_diffs(&mut self) -> std::io::Result<()> {
         for (derived_commit_type, header_style) in &[
             (Ours, self.config.merge_conflict_ours_diff_header_style),
             (Theirs, self.config.merge_conflict_theirs_diff_header_style),
@@ -158,6 +188,7 @@ impl<'a> StateMachine<'a> {
                     &self.painter.merge_conflict_lines[Ancestral],
                     &self.painter.merge_conflict_lines[derived_commit_type],
                 ),
+                &MinusPlus::default(),
                 &mut self.painter.line_numbers_data,
                 &mut self.painter.highlighter,
                 &mut self.painter.output_buffer,
@@ -165,14 +196,6 @@ impl<'a> StateMachine<'a> {
             );
             self.painter.emit()?;
         }
-        // write_merge_conflict_decoration("bold ol", &mut self.painter, self.config)?;
-        write_merge_conflict_bar(
-            &self.config.merge_conflict_end_symbol,
-            &mut self.painter,
-            self.config,
-        )?;
-        self.painter.merge_conflict_lines.clear();
-        self.state = HunkZero(Combined(merge_parents.clone(), InMergeConflict::No), None);
         Ok(())
     }
 }
@@ -320,6 +343,39 @@ ancestor ⟶   HEAD │
         assert!(output.contains("\n▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲▲"));
     }
 
+    #[test]
+    fn test_merge_conflict_in_three_panels() {
+        let config = integration_test_utils::make_config_from_args(&[
+            "--side-by-side",
+            "--line-numbers",
+            "--width",
+            "90",
+        ]);
+        let output = integration_test_utils::run_delta(GIT_DIFF3_MERGE_CONFLICT, &config);
+        let output = strip_ansi_codes(&output);
+        // The line numbers are those of the working tree file.
+        assert!(output.contains(
+            "
+HEAD                         │5f69f15                      │other
+│  3 │    let x = 100;       ││  5 │    let x = 1;         ││  7 │    let x = 10;
+▲▲▲"
+        ));
+        assert!(
+            output.contains("│  3 │      let y = 2;                       │  9 │      let y = 2;")
+        );
+        // Lines too long for their panel are wrapped.
+        assert!(output.contains(
+            "
+HEAD                         │5f69f15                      │other
+│ 11 │    let z = 3;         ││    │                       ││    │
+│ 12 │    println!(\"{}\", x +↴││ 14 │    println!(\"{}\", x +↴││ 16 │    println!(\"sum {}\", ↴
+│    │              … y + z);││    │                  … y);││    │                …x + y);
+▲▲▲"
+        ));
+        assert!(output.contains("│  6 │  }                                    │ 18 │  }"));
+        assert!(!output.contains("ancestor ⟶"));
+    }
+
     #[test]
     fn test_real_merge_conflict() {
         let config = integration_test_utils::make_config_from_args(&[]);
@@ -367,6 +423,32 @@ index 6178079,7898192..0000000
 ++>>>>>>> 0c20c9d... wip
 ";
 
+    const GIT_DIFF3_MERGE_CONFLICT: &str = r#"\
+diff --cc main.rs
+index 8de16c1,be87480..0000000
+--- a/main.rs
++++ b/main.rs
+@@@ -1,6 -1,5 +1,18 @@@
+  fn main() {
+++<<<<<<< HEAD
+ +    let x = 100;
+++||||||| 5f69f15
+++    let x = 1;
+++=======
++     let x = 10;
+++>>>>>>> other
+      let y = 2;
+++<<<<<<< HEAD
+ +    let z = 3;
+ +    println!("{}", x + y + z);
+++||||||| 5f69f15
+++    println!("{}", x + y);
+++=======
++     println!("sum {}", x + y);
+++>>>>>>> other
+  }
+"#;
+
     const GIT_MERGE_CONFLICT: &str = r#"\
 diff --cc src/handlers/merge_conflict.rs
 index 27d47c0,3a7e7b9..0000000
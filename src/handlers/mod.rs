@@ -1,24 +1,46 @@
 /// This module contains functions handling input lines encountered during the
 /// main `StateMachine::consume()` loop.
+pub mod adaptive_panels;
+pub mod binary;
 pub mod blame;
 pub mod commit_meta;
+pub mod context_diff;
 pub mod diff_header;
 pub mod diff_header_diff;
 pub mod diff_header_misc;
 pub mod diff_stat;
+pub mod directory_diff;
 pub mod draw;
+pub mod email_patch;
 pub mod git_show_file;
+pub mod graph;
 pub mod grep;
 pub mod hunk;
 pub mod hunk_header;
+pub mod lfs;
 pub mod merge_conflict;
+pub mod normal_diff;
+pub mod notes;
+pub mod range_diff;
 mod ripgrep_json;
+pub mod signature;
 pub mod submodule;
+pub mod word_diff;
 
 use crate::delta::{State, StateMachine};
 
 impl<'a> StateMachine<'a> {
     pub fn handle_additional_cases(&mut self, to_state: State) -> std::io::Result<bool> {
+        self.handle_additional_cases_with_description(to_state, None)
+    }
+
+    /// Handle the line as `handle_additional_cases` does, but write `description` in its place,
+    /// unless delta is running in color-only mode.
+    pub fn handle_additional_cases_with_description(
+        &mut self,
+        to_state: State,
+        description: Option<String>,
+    ) -> std::io::Result<bool> {
         let mut handled_line = false;
 
         // Additional cases:
@@ -26,7 +48,8 @@ impl<'a> StateMachine<'a> {
         // 1. When comparing directories with diff -u, if filenames match between the
         //    directories, the files themselves will be compared. However, if an equivalent
         //    filename is not present, diff outputs a single line (Only in...) starting
-        //    indicating that the file is present in only one of the directories.
+        //    indicating that the file is present in only one of the directories. If it is
+        //    known which directory that is, the file is described as added or removed.
         //
         // 2. Git diff emits lines describing submodule state such as "Submodule x/y/z contains
         //    untracked content"
@@ -38,9 +61,13 @@ impl<'a> StateMachine<'a> {
         self.state = to_state;
         if self.should_handle() {
             self.painter.emit()?;
+            let (line, raw_line) = match description {
+                Some(description) if !self.config.color_only => (description.clone(), description),
+                _ => (self.line.clone(), self.raw_line.clone()),
+            };
             diff_header::write_generic_diff_header_header_line(
-                &self.line,
-                &self.raw_line,
+                &line,
+                &raw_line,
                 &mut self.painter,
                 &mut self.mode_info,
                 self.config,
@@ -0,0 +1,183 @@
+// A module for handling normal format diffs, as output by `diff` without a format option, e.g.
+// ```
+// 2,3c2
+// <     let x = 1;
+// <     let y = 2;
+// ---
+// >     let x = 3;
+// 4a4
+// >     let z = 4;
+// ```
+// Each hunk is handled as the equivalent unified diff hunk, which has no unchanged lines.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::delta::{Source, State, StateMachine};
+
+lazy_static! {
+    // E.g. "2,3c2", "4a4" and "7d6"
+    static ref NORMAL_DIFF_HUNK_HEADER_REGEX: Regex =
+        Regex::new(r"^(\d+)(?:,(\d+))?([acd])(\d+)(?:,(\d+))?$").unwrap();
+}
+
+/// Is this line the header of a hunk of `diff` output?
+pub fn is_normal_diff_hunk_header(line: &str) -> bool {
+    NORMAL_DIFF_HUNK_HEADER_REGEX.is_match(line)
+}
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_normal_diff_line(&self) -> bool {
+        !self.config.color_only && self.source == Source::DiffUnified
+    }
+
+    /// Handle a hunk header or a hunk line of `diff` output.
+    pub fn handle_normal_diff_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_normal_diff_line() {
+            return Ok(false);
+        }
+        if let Some(hunk_header) = get_unified_hunk_header(&self.line) {
+            // `diff -r` does not name the files in lines of their own.
+            if matches!(self.state, State::DiffHeader(_))
+                && self.handled_diff_header_header_line_file_pair.is_none()
+            {
+                if let Some((minus_file, plus_file)) = get_file_pair_from_diff_line(&self.diff_line)
+                {
+                    let minus_line = format!("--- {minus_file}");
+                    let plus_line = format!("+++ {plus_file}");
+                    self.handle_unified_line(minus_line, Self::handle_diff_header_minus_line)?;
+                    self.handle_unified_line(plus_line, Self::handle_diff_header_plus_line)?;
+                }
+            }
+            self.in_normal_diff_hunk = true;
+            return self.handle_unified_line(hunk_header, Self::handle_hunk_header_line);
+        }
+        if self.in_normal_diff_hunk {
+            if self.line == "---" {
+                return Ok(true);
+            }
+            if let Some(line) = get_unified_line(&self.line) {
+                return self.handle_unified_line(line, Self::handle_hunk_line);
+            }
+            self.in_normal_diff_hunk = false;
+        }
+        Ok(false)
+    }
+}
+
+/// Given input like "2,3c2" return "@@ -2,2 +2,1 @@".
+fn get_unified_hunk_header(line: &str) -> Option<String> {
+    let caps = NORMAL_DIFF_HUNK_HEADER_REGEX.captures(line)?;
+    let get_range = |start: usize, end: usize| -> Option<(usize, usize)> {
+        let start: usize = caps[start].parse().ok()?;
+        let end = match caps.get(end) {
+            Some(end) => end.as_str().parse().ok()?,
+            None => start,
+        };
+        Some((start, (end + 1).saturating_sub(start)))
+    };
+    let (minus_start, mut minus_count) = get_range(1, 2)?;
+    let (plus_start, mut plus_count) = get_range(4, 5)?;
+    // Lines are added after, or removed after, the line of the other file.
+    match &caps[3] {
+        "a" => minus_count = 0,
+        "d" => plus_count = 0,
+        _ => {}
+    }
+    Some(format!(
+        "@@ -{minus_start},{minus_count} +{plus_start},{plus_count} @@"
+    ))
+}
+
+/// Convert a line of a hunk of `diff` output to a unified diff line.
+fn get_unified_line(line: &str) -> Option<String> {
+    let marker = match line.chars().next()? {
+        '<' => '-',
+        '>' => '+',
+        '\\' => return Some(line.to_string()),
+        _ => return None,
+    };
+    let line = &line[1..];
+    Some(format!(
+        "{marker}{}",
+        line.strip_prefix(' ').unwrap_or(line)
+    ))
+}
+
+/// Given input like "diff -r a/src/main.rs b/src/main.rs" return ("a/src/main.rs",
+/// "b/src/main.rs").
+fn get_file_pair_from_diff_line(line: &str) -> Option<(&str, &str)> {
+    match line.split_whitespace().collect::<Vec<_>>()[..] {
+        [_, .., minus_file, plus_file] if !minus_file.starts_with('-') => {
+            Some((minus_file, plus_file))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_get_unified_hunk_header() {
+        for (line, expected) in [
+            ("2,3c2", Some("@@ -2,2 +2,1 @@")),
+            ("4a5,6", Some("@@ -4,0 +5,2 @@")),
+            ("7d6", Some("@@ -7,1 +6,0 @@")),
+            ("3c3", Some("@@ -3,1 +3,1 @@")),
+            ("3x3", None),
+            ("--- 3c3", None),
+        ] {
+            assert_eq!(get_unified_hunk_header(line).as_deref(), expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_get_file_pair_from_diff_line() {
+        assert_eq!(
+            get_file_pair_from_diff_line("diff -r a/src/main.rs b/src/main.rs"),
+            Some(("a/src/main.rs", "b/src/main.rs"))
+        );
+        assert_eq!(get_file_pair_from_diff_line("diff -r a"), None);
+    }
+
+    #[test]
+    fn test_normal_diff() {
+        DeltaTest::with_args(&["--line-numbers"])
+            .with_input(DIFF_NORMAL)
+            .expect_after_skip(
+                1,
+                "
+#indent_mark
+a/src/main.rs ⟶   b/src/main.rs
+────────────────────────────────────────────────────────────────────────────────
+
+───┐
+2: │
+───┘
+  2 ⋮    │    let x = 1;
+  3 ⋮    │    let y = 2;
+    ⋮  2 │    let x = 3;
+
+───┐
+4: │
+───┘
+    ⋮  4 │    let z = 4;
+",
+            );
+    }
+
+    const DIFF_NORMAL: &str = "\
+diff -r a/src/main.rs b/src/main.rs
+2,3c2
+<     let x = 1;
+<     let y = 2;
+---
+>     let x = 3;
+4a4
+>     let z = 4;
+";
+}
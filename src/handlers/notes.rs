@@ -0,0 +1,155 @@
+// A module for handling the notes of commits, as output by `git log --notes`, e.g.
+// ```
+// commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+// Author: Dan Davison <dandavison7@gmail.com>
+// Date:   Sat Dec 11 17:08:56 2021 -0500
+//
+//     Change x to 2.
+//
+// Notes (review):
+//     Reviewed-by: Dan Davison <dandavison7@gmail.com>
+// ```
+// The header of a block of notes is replaced by the name of the notes ref, in notes-style and
+// notes-decoration-style, and the notes are painted in notes-style. The block ends at the first
+// line which is not indented.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::draw;
+use crate::delta::{State, StateMachine};
+use crate::style::DecorationStyle;
+
+lazy_static! {
+    // E.g. "Notes:" and "Notes (review):"
+    static ref NOTES_HEADER_REGEX: Regex = Regex::new(r"^Notes(?: \((.+)\))?:$").unwrap();
+}
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_notes_line(&self) -> bool {
+        self.state == State::CommitMeta
+    }
+
+    /// Handle the header or a line of a block of commit notes.
+    pub fn handle_notes_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_notes_line() {
+            return Ok(false);
+        }
+        if let Some(caps) = NOTES_HEADER_REGEX.captures(&self.line) {
+            self.in_notes = true;
+            // Notes shown by default are those of refs/notes/commits.
+            let notes_ref = caps.get(1).map_or("commits", |m| m.as_str()).to_string();
+            return self.handle_notes_header_line(&notes_ref);
+        }
+        if self.in_notes {
+            if self.line.starts_with("    ") {
+                let style = self.config.notes_style;
+                if style.is_raw {
+                    return Ok(false);
+                }
+                self.painter.emit()?;
+                writeln!(self.painter.writer, "    {}", style.paint(&self.line[4..]))?;
+                return Ok(true);
+            }
+            self.in_notes = false;
+        }
+        Ok(false)
+    }
+
+    fn handle_notes_header_line(&mut self, notes_ref: &str) -> std::io::Result<bool> {
+        let style = self.config.notes_style;
+        if style.is_raw && style.decoration_style == DecorationStyle::NoDecoration {
+            return Ok(false);
+        }
+        self.painter.emit()?;
+        if self.config.color_only {
+            // The header cannot be replaced without altering the input structurally.
+            writeln!(self.painter.writer, "{}", style.paint(&self.line))?;
+            return Ok(true);
+        }
+        let (mut draw_fn, pad, decoration_ansi_term_style) =
+            draw::get_draw_function(style.decoration_style);
+        let text = format!("{}{}", notes_ref, if pad { " " } else { "" });
+        draw_fn(
+            self.painter.writer,
+            &text,
+            &text,
+            "",
+            &self.config.decorations_width,
+            style,
+            decoration_ansi_term_style,
+        )?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_notes() {
+        DeltaTest::with_args(&["--notes-style", "yellow"])
+            .with_input(GIT_LOG_NOTES)
+            .expect_contains(
+                "
+    Change x to 2.
+
+────────┐
+commits │
+────────┘
+    CI: passed
+
+───────┐
+review │
+───────┘
+    Reviewed-by: Dan Davison <dandavison7@gmail.com>
+
+",
+            );
+    }
+
+    #[test]
+    fn test_notes_style() {
+        DeltaTest::with_args(&["--notes-style", "yellow", "--notes-decoration-style", "none"])
+            .explain_ansi()
+            .with_input(GIT_LOG_NOTES)
+            .expect_contains("\n(yellow)review(normal)\n    (yellow)Reviewed-by: Dan Davison <dandavison7@gmail.com>(normal)\n\n");
+    }
+
+    #[test]
+    fn test_notes_color_only() {
+        DeltaTest::with_args(&["--color-only"])
+            .with_input(GIT_LOG_NOTES)
+            .expect_contains("\nNotes:\n    CI: passed\n\nNotes (review):\n");
+        DeltaTest::with_args(&["--color-only", "--notes-style", "yellow"])
+            .explain_ansi()
+            .with_input(GIT_LOG_NOTES)
+            .expect_contains("\n(yellow)Notes (review):(normal)\n");
+    }
+
+    const GIT_LOG_NOTES: &str = "\
+commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Sat Dec 11 17:08:56 2021 -0500
+
+    Change x to 2.
+
+Notes:
+    CI: passed
+
+Notes (review):
+    Reviewed-by: Dan Davison <dandavison7@gmail.com>
+
+diff --git a/src/main.rs b/src/main.rs
+index 8b13789..e69de29 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+";
+}
@@ -0,0 +1,319 @@
+// A module for handling `git range-diff` output.
+//
+// Each commit of the two ranges is listed on a pair line, e.g.
+// ```
+// 1:  a1b2c3d = 1:  e4f5a6b Subject of unchanged commit
+// 2:  c7d8e9f ! 2:  0a1b2c3 Subject of modified commit
+// 3:  d4e5f6a < -:  ------- Subject of dropped commit
+// -:  ------- > 3:  b7c8d9e Subject of added commit
+// ```
+// A modified commit ('!') is followed by a "diff of diffs": lines indented by four spaces, with an
+// outer column holding the diff marker between the two versions of the patch, and an inner column
+// holding the diff marker of the patch itself:
+// ```
+//     @@ src/main.rs: fn main() {
+//           let x = 1;
+//     -+    let y = 2;
+//     ++    let y = 3;
+// ```
+
+use std::fmt::Write as FmtWrite;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use super::draw;
+use crate::ansi;
+use crate::config::Config;
+use crate::delta::{State, StateMachine};
+use crate::paint::Painter;
+use crate::style::Style;
+
+const INDENT: &str = "    ";
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_range_diff_line(&self) -> bool {
+        // A pair line can only start or continue range-diff output: within a diff, a line such as
+        // " 1: a = 2: b" is an unchanged line of a hunk.
+        !self.config.color_only
+            && (self.state == State::RangeDiff && self.line.starts_with(INDENT)
+                || matches!(
+                    self.state,
+                    State::Unknown | State::CommitMeta | State::RangeDiff
+                ) && RANGE_DIFF_PAIR_LINE_REGEX.is_match(&self.line))
+    }
+
+    /// Handle a pair line of `git range-diff` output, or a line of the diff of diffs following it.
+    pub fn handle_range_diff_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_range_diff_line() {
+            return Ok(false);
+        }
+        self.painter.paint_buffered_minus_and_plus_lines();
+        self.painter.emit()?;
+        if let Some(caps) = RANGE_DIFF_PAIR_LINE_REGEX.captures(&self.line) {
+            self.state = State::RangeDiff;
+            let pair_line = RangeDiffPairLine {
+                left: caps.get(1).unwrap().as_str(),
+                marker: caps.get(2).unwrap().as_str().chars().next().unwrap(),
+                right: caps.get(3).unwrap().as_str(),
+                subject: caps.get(4).map_or("", |m| m.as_str()),
+            };
+            write_range_diff_pair_line(&pair_line, &self.raw_line, &mut self.painter, self.config)?;
+        } else {
+            paint_range_diff_inner_line(
+                &self.line[INDENT.len()..],
+                &mut self.painter.output_buffer,
+                self.config,
+            );
+            self.painter.emit()?;
+        }
+        Ok(true)
+    }
+}
+
+lazy_static! {
+    static ref RANGE_DIFF_PAIR_LINE_REGEX: Regex = Regex::new(
+        r"^(\s*(?:\d+|-+):\s+(?:[0-9a-f]+|-+)) ([=!<>]) (\s*(?:\d+|-+):\s+(?:[0-9a-f]+|-+))(?: (.*))?$"
+    )
+    .unwrap();
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct RangeDiffPairLine<'a> {
+    left: &'a str,    // e.g. "1:  a1b2c3d"
+    marker: char,     // One of '=', '!', '<', '>'
+    right: &'a str,   // e.g. "1:  e4f5a6b"
+    subject: &'a str, // Subject of the commit
+}
+
+fn write_range_diff_pair_line(
+    pair_line: &RangeDiffPairLine,
+    raw_line: &str,
+    painter: &mut Painter,
+    config: &Config,
+) -> std::io::Result<()> {
+    if config.commit_style.is_omitted {
+        return Ok(());
+    }
+    let (mut draw_fn, pad, decoration_ansi_term_style) =
+        draw::get_draw_function(config.commit_style.decoration_style);
+    let pad = if pad { " " } else { "" };
+    if config.commit_style.is_raw {
+        draw_fn(
+            painter.writer,
+            &format!("{}{}", ansi::strip_ansi_codes(raw_line), pad),
+            &format!("{raw_line}{pad}"),
+            "",
+            &config.decorations_width,
+            config.commit_style,
+            decoration_ansi_term_style,
+        )
+    } else {
+        let marker_style = match pair_line.marker {
+            '<' => config.minus_style,
+            '>' => config.plus_style,
+            _ => config.commit_style,
+        };
+        let mut line = format!(
+            "{} {} {}",
+            config.commit_style.paint(pair_line.left),
+            marker_style.paint(pair_line.marker.to_string()),
+            config.commit_style.paint(pair_line.right),
+        );
+        if !pair_line.subject.is_empty() {
+            let _ = write!(line, " {}", config.commit_style.paint(pair_line.subject));
+        }
+        line.push_str(pad);
+        draw_fn(
+            painter.writer,
+            &line,
+            &line,
+            "",
+            &config.decorations_width,
+            config.null_style,
+            decoration_ansi_term_style,
+        )
+    }
+}
+
+/// Paint a line of the diff of diffs, with the indentation removed. The line is painted according
+/// to its outer marker, and the inner marker (if any) is highlighted as a minus or plus marker.
+fn paint_range_diff_inner_line(line: &str, output_buffer: &mut String, config: &Config) {
+    let mut chars = line.chars();
+    let (outer_style, outer_marker) = match chars.next() {
+        Some('-') => (config.minus_style, "-"),
+        Some('+') => (config.plus_style, "+"),
+        Some(' ') => (config.zero_style, " "),
+        _ => {
+            // E.g. '@@ src/main.rs: fn main() {'
+            let _ = writeln!(
+                output_buffer,
+                "{INDENT}{}",
+                config.hunk_header_style.paint(line)
+            );
+            return;
+        }
+    };
+    let inner_line = chars.as_str();
+    output_buffer.push_str(INDENT);
+    if config.keep_plus_minus_markers {
+        output_buffer.push_str(&outer_style.paint(outer_marker).to_string());
+    } else {
+        output_buffer.push(' ');
+    }
+    if inner_line.starts_with("## ") {
+        // E.g. '## src/main.rs ##' and '## Commit message ##'
+        let _ = writeln!(
+            output_buffer,
+            "{}",
+            paint_inner(config.file_style, outer_style, inner_line)
+        );
+        return;
+    }
+    let mut inner_chars = inner_line.chars();
+    let inner_marker_style = match inner_chars.next() {
+        Some('-') => Some(config.minus_emph_style),
+        Some('+') => Some(config.plus_emph_style),
+        _ => None,
+    };
+    match inner_marker_style {
+        Some(inner_marker_style) => {
+            let _ = writeln!(
+                output_buffer,
+                "{}{}",
+                inner_marker_style.paint(&inner_line[..1]),
+                outer_style.paint(inner_chars.as_str())
+            );
+        }
+        None => {
+            let _ = writeln!(output_buffer, "{}", outer_style.paint(inner_line));
+        }
+    }
+}
+
+// Paint `text` in `style`, using the background color of `outer_style` if `style` has none.
+fn paint_inner(style: Style, outer_style: Style, text: &str) -> String {
+    let mut style = style;
+    if style.ansi_term_style.background.is_none() {
+        style.ansi_term_style.background = outer_style.ansi_term_style.background;
+    }
+    style.paint(text).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_range_diff_pair_line_regex() {
+        for (line, expected) in [
+            (
+                "1:  a1b2c3d = 1:  e4f5a6b Add feature",
+                Some(("1:  a1b2c3d", "=", "1:  e4f5a6b", Some("Add feature"))),
+            ),
+            (
+                " 9:  a1b2c3d ! 10:  e4f5a6b Fix bug",
+                Some((" 9:  a1b2c3d", "!", "10:  e4f5a6b", Some("Fix bug"))),
+            ),
+            (
+                "3:  d4e5f6a < -:  ------- Dropped",
+                Some(("3:  d4e5f6a", "<", "-:  -------", Some("Dropped"))),
+            ),
+            (
+                "-:  ------- > 3:  b7c8d9e Added",
+                Some(("-:  -------", ">", "3:  b7c8d9e", Some("Added"))),
+            ),
+            (
+                "1:  a1b2c3d = 1:  e4f5a6b",
+                Some(("1:  a1b2c3d", "=", "1:  e4f5a6b", None)),
+            ),
+            ("1: a1b2c3d ~ 1: e4f5a6b Subject", None),
+            ("commit a1b2c3d", None),
+        ] {
+            let caps = RANGE_DIFF_PAIR_LINE_REGEX.captures(line).map(|caps| {
+                (
+                    caps.get(1).unwrap().as_str(),
+                    caps.get(2).unwrap().as_str(),
+                    caps.get(3).unwrap().as_str(),
+                    caps.get(4).map(|m| m.as_str()),
+                )
+            });
+            assert_eq!(caps, expected, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_range_diff() {
+        DeltaTest::with_args(&["--commit-style", "yellow"])
+            .explain_ansi()
+            .with_input(GIT_RANGE_DIFF)
+            .expect_after_skip(
+                0,
+                "
+#indent_mark
+(yellow)1:  a1b2c3d(normal) (yellow)=(normal) (yellow)1:  e4f5a6b(normal) (yellow)Add feature(normal)
+(yellow)2:  c7d8e9f(normal) (yellow)!(normal) (yellow)2:  0a1b2c3(normal) (yellow)Fix bug(normal)
+    @@ src/main.rs: fn main() {
+           let x = 1;
+     (normal 124)-(normal 52)    let y = 2;(normal)
+     (normal 28)+(normal 52)    let y = 3;(normal)
+     (normal 28)+(normal 22)    let y = 4;(normal)
+(yellow)3:  d4e5f6a(normal) (normal 52)<(normal) (yellow)-:  -------(normal) (yellow)Drop hack(normal)
+(yellow)-:  -------(normal) (normal 22)>(normal) (yellow)3:  b7c8d9e(normal) (yellow)Add docs(normal)
+",
+            );
+    }
+
+    #[test]
+    fn test_range_diff_raw_commit_style() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_RANGE_DIFF)
+            .expect_contains("3:  d4e5f6a < -:  ------- Drop hack");
+    }
+
+    #[test]
+    fn test_range_diff_pair_line_in_hunk_is_not_range_diff() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_DIFF_WITH_PAIR_LINE_IN_HUNK)
+            .expect_after_skip(
+                1,
+                "
+#indent_mark
+t
+────────────────────────────────────────────────────────────────────────────────
+
+───┐
+1: │
+───┘
+1: a = 2: b
+x
+y
+",
+            );
+    }
+
+    const GIT_DIFF_WITH_PAIR_LINE_IN_HUNK: &str = "\
+diff --git a/t b/t
+index 0000001..0000002 100644
+--- a/t
++++ b/t
+@@ -1,2 +1,2 @@
+ 1: a = 2: b
+-x
++y
+";
+
+    const GIT_RANGE_DIFF: &str = "\
+1:  a1b2c3d = 1:  e4f5a6b Add feature
+2:  c7d8e9f ! 2:  0a1b2c3 Fix bug
+    @@ src/main.rs: fn main() {
+           let x = 1;
+    --    let y = 2;
+    -+    let y = 3;
+    ++    let y = 4;
+3:  d4e5f6a < -:  ------- Drop hack
+-:  ------- > 3:  b7c8d9e Add docs
+";
+}
@@ -0,0 +1,290 @@
+// A module for handling the verification of commit signatures, as output by
+// `git log --show-signature`, e.g.
+// ```
+// commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+// gpg: Signature made Sat Dec 11 17:08:56 2021 EST
+// gpg:                using RSA key 4AEE18F83AFDEB23
+// gpg: Good signature from "Dan Davison <dandavison7@gmail.com>" [ultimate]
+// Author: Dan Davison <dandavison7@gmail.com>
+// ```
+// or, for a commit signed with an SSH key,
+// ```
+// Good "git" signature for dandavison7@gmail.com with ED25519 key SHA256:ZqBAMtoT9Ssx...
+// ```
+// The lines are replaced by a single line, painted in the style of the outcome of the
+// verification: good, unknown (the key or its trust is unknown) or bad.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::delta::{State, StateMachine};
+use crate::features::hyperlinks;
+
+#[derive(Debug, Default)]
+pub struct Signature {
+    is_good: bool,
+    is_bad: bool,
+    is_trusted: bool,
+    signer: Option<String>,
+    // The type and id of the key, e.g. ("RSA", "4AEE18F83AFDEB23").
+    key: Option<(String, String)>,
+    raw_lines: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum SignatureStatus {
+    Good,
+    Unknown,
+    Bad,
+}
+
+lazy_static! {
+    static ref SIGNATURE_LINE_REGEX: Regex = Regex::new(
+        r#"^(?:gpg: |Primary key fingerprint: |Good "git" signature |No principal matched|Could not verify signature)"#
+    )
+    .unwrap();
+    // E.g. "gpg:                using RSA key 4AEE18F83AFDEB23"
+    static ref GPG_KEY_REGEX: Regex = Regex::new(r"^gpg: +using (\S+) key (\S+)$").unwrap();
+    // E.g. "gpg: Good signature from "Dan Davison <dandavison7@gmail.com>" [ultimate]"
+    static ref GPG_STATUS_REGEX: Regex =
+        Regex::new(r#"^gpg: (Good|BAD) signature from "([^"]*)""#).unwrap();
+    // E.g. "Good "git" signature for dandavison7@gmail.com with ED25519 key SHA256:ZqBAMtoT9Ssx"
+    static ref SSH_GOOD_REGEX: Regex =
+        Regex::new(r#"^Good "git" signature (?:for (.+) )?with (\S+) key (\S+)$"#).unwrap();
+}
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_signature_line(&self) -> bool {
+        self.state == State::CommitMeta && !self.config.color_only
+    }
+
+    /// Buffer a line verifying the signature of a commit, or emit the summary of the signature
+    /// once its lines have ended.
+    pub fn handle_signature_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_signature_line() {
+            return Ok(false);
+        }
+        if !SIGNATURE_LINE_REGEX.is_match(&self.line) {
+            self.flush_signature()?;
+            return Ok(false);
+        }
+        let signature = self.signature.get_or_insert_with(Signature::default);
+        signature.update(&self.line);
+        signature.raw_lines.push(self.raw_line.clone());
+        Ok(true)
+    }
+
+    /// Emit the line summarizing the signature being handled, if any.
+    pub fn flush_signature(&mut self) -> std::io::Result<()> {
+        let signature = match self.signature.take() {
+            Some(signature) => signature,
+            None => return Ok(()),
+        };
+        let (style, symbol, description) = match signature.get_status() {
+            SignatureStatus::Good => (self.config.commit_signature_good_style, "✔", "Good"),
+            SignatureStatus::Unknown => (
+                self.config.commit_signature_unknown_style,
+                "?",
+                "Unverified",
+            ),
+            SignatureStatus::Bad => (self.config.commit_signature_bad_style, "✘", "BAD"),
+        };
+        self.painter.emit()?;
+        if style.is_raw {
+            for raw_line in &signature.raw_lines {
+                writeln!(self.painter.writer, "{raw_line}")?;
+            }
+            return Ok(());
+        }
+        let mut summary = format!("{symbol} {description} signature");
+        if let Some(signer) = &signature.signer {
+            summary.push_str(&format!(" from {signer}"));
+        }
+        if let Some((key_type, key)) = &signature.key {
+            let key = hyperlinks::format_osc8_signature_key_hyperlink(key, self.config);
+            summary.push_str(&format!(" ({key_type} key {key})"));
+        }
+        writeln!(self.painter.writer, "{}", style.paint(summary))?;
+        Ok(())
+    }
+}
+
+impl Signature {
+    fn update(&mut self, line: &str) {
+        if let Some(caps) = GPG_KEY_REGEX.captures(line) {
+            self.key = Some((caps[1].to_string(), caps[2].to_string()));
+        } else if let Some(caps) = GPG_STATUS_REGEX.captures(line) {
+            if &caps[1] == "Good" {
+                self.is_good = true;
+                self.is_trusted = true;
+            } else {
+                self.is_bad = true;
+            }
+            self.signer = Some(caps[2].to_string());
+        } else if let Some(caps) = SSH_GOOD_REGEX.captures(line) {
+            self.is_good = true;
+            self.is_trusted = caps.get(1).is_some();
+            self.signer = caps.get(1).map(|m| m.as_str().to_string());
+            self.key = Some((caps[2].to_string(), caps[3].to_string()));
+        } else if line.starts_with("gpg: WARNING:") || line.starts_with("No principal matched") {
+            self.is_trusted = false;
+        } else if line.starts_with("Could not verify signature") {
+            self.is_bad = true;
+        }
+    }
+
+    fn get_status(&self) -> SignatureStatus {
+        if self.is_bad {
+            SignatureStatus::Bad
+        } else if self.is_good && self.is_trusted {
+            SignatureStatus::Good
+        } else {
+            SignatureStatus::Unknown
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    fn make_signature(lines: &str) -> Signature {
+        let mut signature = Signature::default();
+        for line in lines.lines() {
+            signature.update(line);
+        }
+        signature
+    }
+
+    #[test]
+    fn test_signature_status() {
+        let signature = make_signature(GPG_GOOD);
+        assert_eq!(signature.get_status(), SignatureStatus::Good);
+        assert_eq!(
+            signature.signer.as_deref(),
+            Some("Dan Davison <dandavison7@gmail.com>")
+        );
+        assert_eq!(
+            signature.key,
+            Some(("RSA".to_string(), "4AEE18F83AFDEB23".to_string()))
+        );
+        let signature = make_signature(&format!(
+            "{GPG_GOOD}\
+gpg: WARNING: This key is not certified with a trusted signature!
+gpg:          There is no indication that the signature belongs to the owner.
+"
+        ));
+        assert_eq!(signature.get_status(), SignatureStatus::Unknown);
+        let signature = make_signature(
+            "\
+gpg: Signature made Sat Dec 11 17:08:56 2021 EST
+gpg:                using RSA key 4AEE18F83AFDEB23
+gpg: Can't check signature: No public key
+",
+        );
+        assert_eq!(signature.get_status(), SignatureStatus::Unknown);
+        assert_eq!(make_signature(GPG_BAD).get_status(), SignatureStatus::Bad);
+        let signature = make_signature(SSH_GOOD);
+        assert_eq!(signature.get_status(), SignatureStatus::Good);
+        assert_eq!(
+            signature.key,
+            Some(("ED25519".to_string(), "SHA256:ZqBAMtoT9Ssx".to_string()))
+        );
+        let signature = make_signature(
+            "\
+Good \"git\" signature with ED25519 key SHA256:ZqBAMtoT9Ssx
+No principal matched.
+",
+        );
+        assert_eq!(signature.get_status(), SignatureStatus::Unknown);
+    }
+
+    #[test]
+    fn test_signature_good() {
+        DeltaTest::with_args(&[])
+            .explain_ansi()
+            .with_input(&make_git_log(GPG_GOOD))
+            .expect_after_skip(
+                0,
+                "
+#indent_mark
+(normal)commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+(green)✔ Good signature from Dan Davison <dandavison7@gmail.com> (RSA key 4AEE18F83AFDEB23)(normal)
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Sat Dec 11 17:08:56 2021 -0500
+",
+            );
+    }
+
+    #[test]
+    fn test_signature_bad() {
+        DeltaTest::with_args(&[])
+            .explain_ansi()
+            .with_input(&make_git_log(GPG_BAD))
+            .expect_contains(
+                "\n(bold reverse red)✘ BAD signature from Dan Davison <dandavison7@gmail.com> (RSA key 4AEE18F83AFDEB23)(normal)\n",
+            );
+    }
+
+    #[test]
+    fn test_signature_ssh() {
+        DeltaTest::with_args(&["--commit-signature-good-style", "blue"])
+            .explain_ansi()
+            .with_input(&make_git_log(SSH_GOOD))
+            .expect_contains(
+                "\n(blue)✔ Good signature from dandavison7@gmail.com (ED25519 key SHA256:ZqBAMtoT9Ssx)(normal)\n",
+            );
+    }
+
+    #[test]
+    fn test_signature_raw() {
+        DeltaTest::with_args(&["--commit-signature-good-style", "raw"])
+            .with_input(&make_git_log(GPG_GOOD))
+            .expect_contains(GPG_GOOD);
+        DeltaTest::with_args(&["--color-only"])
+            .with_input(&make_git_log(GPG_GOOD))
+            .expect_contains(GPG_GOOD);
+    }
+
+    #[test]
+    fn test_signature_key_hyperlink() {
+        DeltaTest::with_args(&[
+            "--hyperlinks",
+            "--hyperlinks-signature-link-format",
+            "https://keys.openpgp.org/search?q={key}",
+        ])
+        .with_input(&make_git_log(GPG_GOOD))
+        .expect_raw_contains(
+            "RSA key \x1b]8;;https://keys.openpgp.org/search?q=4AEE18F83AFDEB23\x1b\\4AEE18F83AFDEB23\x1b]8;;\x1b\\)",
+        );
+    }
+
+    fn make_git_log(signature_lines: &str) -> String {
+        format!(
+            "\
+commit 94907c0f136f46dc46ffae2dc92dca9af7eb7c2e
+{signature_lines}\
+Author: Dan Davison <dandavison7@gmail.com>
+Date:   Sat Dec 11 17:08:56 2021 -0500
+"
+        )
+    }
+
+    const GPG_GOOD: &str = "\
+gpg: Signature made Sat Dec 11 17:08:56 2021 EST
+gpg:                using RSA key 4AEE18F83AFDEB23
+gpg: Good signature from \"Dan Davison <dandavison7@gmail.com>\" [ultimate]
+";
+
+    const GPG_BAD: &str = "\
+gpg: Signature made Sat Dec 11 17:08:56 2021 EST
+gpg:                using RSA key 4AEE18F83AFDEB23
+gpg: BAD signature from \"Dan Davison <dandavison7@gmail.com>\" [ultimate]
+";
+
+    const SSH_GOOD: &str = "\
+Good \"git\" signature for dandavison7@gmail.com with ED25519 key SHA256:ZqBAMtoT9Ssx
+";
+}
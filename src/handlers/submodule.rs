@@ -1,19 +1,84 @@
+use std::fmt;
+
 use lazy_static::lazy_static;
 use regex::Regex;
 
+use crate::config::Config;
 use crate::delta::{State, StateMachine};
+use crate::features::hyperlinks;
+use crate::git_config::{get_repository_remote_url, GitRemoteRepo};
+
+/// The commits by which the two commits of a submodule differ, as found in its repository.
+#[derive(Debug, PartialEq, Eq)]
+struct SubmoduleLog {
+    update: SubmoduleUpdate,
+    // The commits only in the old commit's history, then those only in the new commit's history.
+    commits: Vec<SubmoduleLogCommit>,
+    // The number of commits left out of `commits` on the old side and on the new side.
+    omitted_commits: [usize; 2],
+    remote: Option<GitRemoteRepo>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum SubmoduleUpdate {
+    FastForward,
+    Rewind,
+    Divergence,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct SubmoduleLogCommit {
+    is_plus: bool,
+    id: String,
+    subject: String,
+}
 
 impl<'a> StateMachine<'a> {
     #[inline]
     fn test_submodule_log(&self) -> bool {
         self.line.starts_with("Submodule ")
+            || self.in_resolved_submodule_log
+                && self.state == State::SubmoduleLog
+                && SUBMODULE_LOG_COMMIT_LINE_REGEX.is_match(&self.line)
     }
 
     pub fn handle_submodule_log_line(&mut self) -> std::io::Result<bool> {
         if !self.test_submodule_log() {
             return Ok(false);
         }
-        self.handle_additional_cases(State::SubmoduleLog)
+        if !self.line.starts_with("Submodule ") {
+            // The commits have been listed already, from the submodule's repository.
+            return Ok(true);
+        }
+        self.in_resolved_submodule_log = false;
+        let submodule_log = match SUBMODULE_LOG_HEADER_LINE_REGEX.captures(&self.line) {
+            Some(caps) if !self.config.color_only => self
+                .get_submodule_log(&caps[1], &caps[2], &caps[4])
+                .map(|submodule_log| {
+                    let description = format!(
+                        "Submodule {} {}{}{} ({})",
+                        &caps[1], &caps[2], &caps[3], &caps[4], submodule_log.update
+                    );
+                    (submodule_log, description)
+                }),
+            _ => None,
+        };
+        match submodule_log {
+            Some((submodule_log, description)) => {
+                let handled_line = self.handle_additional_cases_with_description(
+                    State::SubmoduleLog,
+                    Some(description),
+                )?;
+                if handled_line {
+                    for line in format_submodule_log_lines(&submodule_log, self.config) {
+                        writeln!(self.painter.writer, "{line}")?;
+                    }
+                    self.in_resolved_submodule_log = true;
+                }
+                Ok(handled_line)
+            }
+            None => self.handle_additional_cases(State::SubmoduleLog),
+        }
     }
 
     #[inline]
@@ -32,31 +97,314 @@ impl<'a> StateMachine<'a> {
             if let State::HunkHeader(_, _, _, _) = self.state {
                 self.state = State::SubmoduleShort(commit.to_owned());
             } else if let State::SubmoduleShort(minus_commit) = &self.state {
+                let submodule_log = self.get_submodule_log(&self.plus_file, minus_commit, commit);
                 self.painter.emit()?;
                 writeln!(
                     self.painter.writer,
-                    "{}..{}",
+                    "{}..{}{}",
                     self.config
                         .minus_style
                         .paint(minus_commit.chars().take(7).collect::<String>()),
                     self.config
                         .plus_style
                         .paint(commit.chars().take(7).collect::<String>()),
+                    match &submodule_log {
+                        Some(submodule_log) => format!(" ({})", submodule_log.update),
+                        None => "".to_string(),
+                    }
                 )?;
+                if let Some(submodule_log) = &submodule_log {
+                    for line in format_submodule_log_lines(submodule_log, self.config) {
+                        writeln!(self.painter.writer, "{line}")?;
+                    }
+                }
             }
         }
         Ok(true)
     }
+
+    /// If the submodule at `path` is checked out, return the commits by which `minus_commit` and
+    /// `plus_commit` differ.
+    fn get_submodule_log(
+        &self,
+        path: &str,
+        minus_commit: &str,
+        plus_commit: &str,
+    ) -> Option<SubmoduleLog> {
+        let repo = self.config.git_config()?.open_submodule(path)?;
+        get_submodule_log(&repo, minus_commit, plus_commit)
+    }
 }
 
 lazy_static! {
     static ref SUBMODULE_SHORT_LINE_REGEX: Regex =
         Regex::new("^[-+]Subproject commit ([0-9a-f]{40})(-dirty)?$").unwrap();
+    // E.g. "Submodule submoduleA f4f55af..310b551 (rewind):" and "Submodule submoduleC
+    // 9f3b744...e04f848:"
+    static ref SUBMODULE_LOG_HEADER_LINE_REGEX: Regex =
+        Regex::new(r"^Submodule (.+) ([0-9a-f]{7,40})(\.\.\.?)([0-9a-f]{7,40})(?: \(rewind\))?:$")
+            .unwrap();
+    // E.g. "  > Submodule B stage change 3"
+    static ref SUBMODULE_LOG_COMMIT_LINE_REGEX: Regex = Regex::new(r"^  [<>] ").unwrap();
 }
 
+/// The maximum number of commits listed on each side of a submodule change.
+const MAX_SUBMODULE_LOG_COMMITS: usize = 32;
+
 pub fn get_submodule_short_commit(line: &str) -> Option<&str> {
     match SUBMODULE_SHORT_LINE_REGEX.captures(line) {
         Some(caps) => Some(caps.get(1).unwrap().as_str()),
         None => None,
     }
 }
+
+impl fmt::Display for SubmoduleUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubmoduleUpdate::FastForward => write!(f, "fast-forward"),
+            SubmoduleUpdate::Rewind => write!(f, "rewind"),
+            SubmoduleUpdate::Divergence => write!(f, "divergence"),
+        }
+    }
+}
+
+/// Return the commits by which two commits of a submodule differ, if both are in its repository.
+fn get_submodule_log(
+    repo: &git2::Repository,
+    minus_commit: &str,
+    plus_commit: &str,
+) -> Option<SubmoduleLog> {
+    let resolve = |commit: &str| {
+        Some(
+            repo.revparse_single(commit)
+                .ok()?
+                .peel_to_commit()
+                .ok()?
+                .id(),
+        )
+    };
+    let (minus_id, plus_id) = (resolve(minus_commit)?, resolve(plus_commit)?);
+    let merge_base = repo.merge_base(minus_id, plus_id).ok();
+    let update = if merge_base == Some(minus_id) {
+        SubmoduleUpdate::FastForward
+    } else if merge_base == Some(plus_id) {
+        SubmoduleUpdate::Rewind
+    } else {
+        SubmoduleUpdate::Divergence
+    };
+    let (mut commits, omitted_minus_commits) = get_commits_not_in(repo, minus_id, plus_id, false)?;
+    let (plus_commits, omitted_plus_commits) = get_commits_not_in(repo, plus_id, minus_id, true)?;
+    commits.extend(plus_commits);
+    Some(SubmoduleLog {
+        update,
+        commits,
+        omitted_commits: [omitted_minus_commits, omitted_plus_commits],
+        remote: get_repository_remote_url(repo),
+    })
+}
+
+/// Return the first `MAX_SUBMODULE_LOG_COMMITS` commits in the history of `id` but not in that of
+/// `other_id`, newest first, and the number of those left out.
+fn get_commits_not_in(
+    repo: &git2::Repository,
+    id: git2::Oid,
+    other_id: git2::Oid,
+    is_plus: bool,
+) -> Option<(Vec<SubmoduleLogCommit>, usize)> {
+    let mut revwalk = repo.revwalk().ok()?;
+    revwalk
+        .set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME)
+        .ok()?;
+    revwalk.push(id).ok()?;
+    revwalk.hide(other_id).ok()?;
+    let commits = revwalk
+        .by_ref()
+        .take(MAX_SUBMODULE_LOG_COMMITS)
+        .map(|id| {
+            let commit = repo.find_commit(id.ok()?).ok()?;
+            Some(SubmoduleLogCommit {
+                is_plus,
+                id: commit.id().to_string(),
+                subject: commit.summary().unwrap_or("").to_string(),
+            })
+        })
+        .collect::<Option<_>>()?;
+    // The topological sort has walked the history already, so counting the rest is cheap.
+    Some((commits, revwalk.count()))
+}
+
+/// Return a line for each commit of the log, e.g. "  > 0c8b00d Submodule B stage change 3", and
+/// e.g. "  > … and 5 more" after the commits of a side if some were left out. The commit is a
+/// hyperlink if the submodule has a known remote.
+fn format_submodule_log_lines(submodule_log: &SubmoduleLog, config: &Config) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (is_plus, omitted_commits) in [
+        (false, submodule_log.omitted_commits[0]),
+        (true, submodule_log.omitted_commits[1]),
+    ] {
+        let (marker, style) = if is_plus {
+            (">", config.plus_style)
+        } else {
+            ("<", config.minus_style)
+        };
+        for commit in submodule_log
+            .commits
+            .iter()
+            .filter(|commit| commit.is_plus == is_plus)
+        {
+            let short_id = &commit.id[..7.min(commit.id.len())];
+            lines.push(format!(
+                "  {} {} {}",
+                marker,
+                style.paint(hyperlinks::format_osc8_commit_hyperlink(
+                    &commit.id,
+                    short_id,
+                    submodule_log.remote.as_ref(),
+                    config
+                )),
+                commit.subject
+            ));
+        }
+        if omitted_commits > 0 {
+            lines.push(format!("  {marker} … and {omitted_commits} more"));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::make_config_from_args;
+
+    #[test]
+    fn test_get_submodule_log() {
+        let path = std::env::temp_dir().join("delta-test-get-submodule-log");
+        let _ = std::fs::remove_dir_all(&path);
+        let repo = git2::Repository::init(&path).unwrap();
+        let signature = git2::Signature::now("Delta", "delta@example.com").unwrap();
+        let tree = repo
+            .find_tree(repo.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit = |message: &str, parent: Option<git2::Oid>| {
+            let parents = parent.map(|id| repo.find_commit(id).unwrap());
+            repo.commit(
+                None,
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents.iter().collect::<Vec<_>>(),
+            )
+            .unwrap()
+            .to_string()
+        };
+        let base = commit("Base", None);
+        let a1 = commit("A 1", Some(git2::Oid::from_str(&base).unwrap()));
+        let a2 = commit("A 2", Some(git2::Oid::from_str(&a1).unwrap()));
+        let b1 = commit("B 1", Some(git2::Oid::from_str(&base).unwrap()));
+        let get_log = |minus: &str, plus: &str| {
+            let submodule_log = get_submodule_log(&repo, minus, plus).unwrap();
+            let commits: Vec<_> = submodule_log
+                .commits
+                .iter()
+                .map(|commit| (commit.is_plus, commit.subject.as_str()))
+                .collect();
+            (submodule_log.update, format!("{commits:?}"))
+        };
+
+        assert_eq!(
+            get_log(&base, &a2),
+            (
+                SubmoduleUpdate::FastForward,
+                r#"[(true, "A 2"), (true, "A 1")]"#.to_string()
+            )
+        );
+        assert_eq!(
+            get_log(&a2[..7], &base[..7]),
+            (
+                SubmoduleUpdate::Rewind,
+                r#"[(false, "A 2"), (false, "A 1")]"#.to_string()
+            )
+        );
+        assert_eq!(
+            get_log(&a1, &b1),
+            (
+                SubmoduleUpdate::Divergence,
+                r#"[(false, "A 1"), (true, "B 1")]"#.to_string()
+            )
+        );
+        assert!(get_submodule_log(&repo, &base, "0000000").is_none());
+
+        let mut tip = base.clone();
+        for i in 1..=MAX_SUBMODULE_LOG_COMMITS + 5 {
+            tip = commit(&format!("C {i}"), Some(git2::Oid::from_str(&tip).unwrap()));
+        }
+        let submodule_log = get_submodule_log(&repo, &a1, &tip).unwrap();
+        assert_eq!(submodule_log.commits.len(), 1 + MAX_SUBMODULE_LOG_COMMITS);
+        assert_eq!(
+            submodule_log.commits[1].subject,
+            format!("C {}", MAX_SUBMODULE_LOG_COMMITS + 5)
+        );
+        assert_eq!(submodule_log.omitted_commits, [0, 5]);
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_format_submodule_log_lines() {
+        let submodule_log = SubmoduleLog {
+            update: SubmoduleUpdate::Divergence,
+            commits: vec![
+                SubmoduleLogCommit {
+                    is_plus: false,
+                    id: "9f3b7443a0e8ec8a2c01d4bf4a0efb6e4ec0fa7c".to_string(),
+                    subject: "Submodule C extra change 1".to_string(),
+                },
+                SubmoduleLogCommit {
+                    is_plus: true,
+                    id: "e04f848a2f2d1b0ae5b5bb6f3b0c5bdbe6a3c3d9".to_string(),
+                    subject: "Submodule C stage change 1".to_string(),
+                },
+            ],
+            omitted_commits: [0, 0],
+            remote: Some(GitRemoteRepo::GitHub {
+                slug: "dandavison/delta".to_string(),
+            }),
+        };
+        let config = make_config_from_args(&["--minus-style", "raw", "--plus-style", "raw"]);
+        assert_eq!(
+            format_submodule_log_lines(&submodule_log, &config),
+            vec![
+                "  < 9f3b744 Submodule C extra change 1",
+                "  > e04f848 Submodule C stage change 1",
+            ]
+        );
+        let config = make_config_from_args(&[
+            "--minus-style",
+            "raw",
+            "--plus-style",
+            "raw",
+            "--hyperlinks",
+        ]);
+        assert_eq!(
+            format_submodule_log_lines(&submodule_log, &config)[1],
+            "  > \x1b]8;;https://github.com/dandavison/delta/commit/e04f848a2f2d1b0ae5b5bb6f3b0c5bdbe6a3c3d9\x1b\\e04f848\x1b]8;;\x1b\\ Submodule C stage change 1"
+        );
+        assert_eq!(
+            format_submodule_log_lines(
+                &SubmoduleLog {
+                    omitted_commits: [3, 40],
+                    ..submodule_log
+                },
+                &make_config_from_args(&["--minus-style", "raw", "--plus-style", "raw"])
+            ),
+            vec![
+                "  < 9f3b744 Submodule C extra change 1",
+                "  < … and 3 more",
+                "  > e04f848 Submodule C stage change 1",
+                "  > … and 40 more",
+            ]
+        );
+        assert_eq!(SubmoduleUpdate::FastForward.to_string(), "fast-forward");
+    }
+}
@@ -0,0 +1,736 @@
+// A module for handling `git diff --word-diff=plain` and `git diff --word-diff=porcelain` output.
+//
+// In these formats git does not emit separate removed and added lines. Instead, the removed and
+// added words are marked within a single line. In the plain format a line looks like
+// ```
+// let [-x-]{+y+} = 1;
+// ```
+// In the porcelain format every run of words is emitted on its own line, prefixed with ' ', '-'
+// or '+', and a line consisting of '~' marks the end of a line of the file:
+// ```
+//  let
+// -x
+// +y
+//  = 1;
+// ~
+// ```
+// Delta detects both formats from the input itself, and paints each line of the file with
+// minus-emph-style and plus-emph-style applied to the removed and added words. In side-by-side
+// mode the old version of the line is displayed in the left panel and the new version in the
+// right panel.
+//
+// `git diff --color-words` (i.e. `--word-diff=color`) output carries no markers other than ANSI
+// colors, so it cannot be parsed; delta emits it raw if the calling process requested it.
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use syntect::highlighting::Style as SyntectStyle;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::config::{delta_unreachable, Config};
+use crate::delta::{DiffType, State, StateMachine};
+use crate::features::side_by_side::{self, Left, LeftRight, Right};
+use crate::paint::{self, expand_tabs, BgShouldFill, LineSections, Painter};
+use crate::style::Style;
+use crate::utils::process::{self, CallingProcess};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordDiffFormat {
+    Plain,     // `[-removed-]{+added+}` markers within a line
+    Porcelain, // One line per run of words, with a '~' line terminating each line of the file
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordDiffSection {
+    Zero,
+    Minus,
+    Plus,
+}
+
+// The sections of a single line of the file, in input order.
+pub type WordDiffLine = Vec<(WordDiffSection, String)>;
+
+/// Did the calling git process request `--color-words` (equivalently `--word-diff=color`)?
+pub fn is_color_words() -> bool {
+    #[cfg(not(test))]
+    {
+        *CACHED_IS_COLOR_WORDS
+    }
+    #[cfg(test)]
+    {
+        compute_is_color_words()
+    }
+}
+
+/// Return the word diff format requested by the calling git process, if any, other than
+/// `--color-words`.
+fn requested_word_diff_format() -> Option<WordDiffFormat> {
+    #[cfg(not(test))]
+    {
+        *CACHED_REQUESTED_WORD_DIFF_FORMAT
+    }
+    #[cfg(test)]
+    {
+        compute_requested_word_diff_format()
+    }
+}
+
+lazy_static! {
+    static ref CACHED_IS_COLOR_WORDS: bool = compute_is_color_words();
+    static ref CACHED_REQUESTED_WORD_DIFF_FORMAT: Option<WordDiffFormat> =
+        compute_requested_word_diff_format();
+}
+
+fn compute_is_color_words() -> bool {
+    match &*process::calling_process() {
+        CallingProcess::GitDiff(cmd_line)
+        | CallingProcess::GitShow(cmd_line, _)
+        | CallingProcess::GitLog(cmd_line)
+        | CallingProcess::GitReflog(cmd_line) => {
+            cmd_line.long_options.contains("--color-words")
+                || cmd_line
+                    .long_option_values
+                    .get("--word-diff")
+                    .map(String::as_str)
+                    == Some("color")
+        }
+        _ => false,
+    }
+}
+
+fn compute_requested_word_diff_format() -> Option<WordDiffFormat> {
+    match &*process::calling_process() {
+        CallingProcess::GitDiff(cmd_line)
+        | CallingProcess::GitShow(cmd_line, _)
+        | CallingProcess::GitLog(cmd_line)
+        | CallingProcess::GitReflog(cmd_line) => {
+            if cmd_line.long_options.contains("--color-words") {
+                return None;
+            }
+            match cmd_line
+                .long_option_values
+                .get("--word-diff")
+                .map(String::as_str)
+            {
+                Some("porcelain") => Some(WordDiffFormat::Porcelain),
+                Some("plain") => Some(WordDiffFormat::Plain),
+                Some(_) => None, // color, none
+                None if cmd_line.long_options.contains("--word-diff")
+                    || cmd_line.long_options.contains("--word-diff-regex") =>
+                {
+                    Some(WordDiffFormat::Plain)
+                }
+                None => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+impl<'a> StateMachine<'a> {
+    #[inline]
+    fn test_word_diff_line(&self) -> Option<WordDiffFormat> {
+        use DiffType::*;
+        use State::*;
+        if self.config.color_only {
+            // Word diff lines are joined, so the output would not correspond 1-1 to the input.
+            return None;
+        }
+        match &self.state {
+            WordDiff(format) => Some(*format),
+            HunkHeader(Unified, _, _, _)
+                if self.word_diff_format.is_some() || requested_word_diff_format().is_some() =>
+            {
+                self.word_diff_format.or_else(requested_word_diff_format)
+            }
+            HunkHeader(Unified, _, _, _)
+            | HunkZero(Unified, _)
+            | HunkMinus(Unified, _)
+            | HunkPlus(Unified, _) => detect_word_diff_format(&self.line),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn test_word_diff_lookahead_line(&self) -> bool {
+        self.word_diff_lookahead_lines.is_some()
+            || (!self.is_word_diff_format_decided
+                && matches!(self.state, State::HunkHeader(DiffType::Unified, _, _, _))
+                && !self.config.color_only
+                && self.word_diff_format.is_none()
+                && requested_word_diff_format().is_none())
+    }
+
+    /// Hold back the lines of the first hunk until a '~' line shows that they are
+    /// `--word-diff=porcelain` output, or until the hunk ends.
+    pub fn handle_word_diff_lookahead_line(&mut self) -> std::io::Result<bool> {
+        if !self.test_word_diff_lookahead_line() {
+            return Ok(false);
+        }
+        let lines = self.word_diff_lookahead_lines.get_or_insert_with(Vec::new);
+        // Don't let the buffer become arbitrarily large: the rest of a long hunk is handled as a
+        // unified diff hunk.
+        if matches!(self.line.chars().next(), Some(' ' | '-' | '+' | '\\'))
+            && lines.len() < self.config.line_buffer_size
+        {
+            lines.push((self.line.clone(), self.raw_line.clone()));
+            return Ok(true);
+        }
+        if self.line == "~" {
+            self.word_diff_format = Some(WordDiffFormat::Porcelain);
+        }
+        self.flush_word_diff_lookahead_lines()?;
+        Ok(false)
+    }
+
+    /// Handle the lines held back, if any, in the format that has been decided.
+    pub fn flush_word_diff_lookahead_lines(&mut self) -> std::io::Result<()> {
+        let lines = match self.word_diff_lookahead_lines.take() {
+            Some(lines) => lines,
+            None => return Ok(()),
+        };
+        self.is_word_diff_format_decided = true;
+        let current_line = (
+            std::mem::take(&mut self.line),
+            std::mem::take(&mut self.raw_line),
+        );
+        for (line, raw_line) in lines {
+            self.line = line;
+            self.raw_line = raw_line;
+            self.handle_line()?;
+        }
+        self.line = current_line.0;
+        self.raw_line = current_line.1;
+        Ok(())
+    }
+
+    /// Handle a line of `--word-diff=plain` or `--word-diff=porcelain` output.
+    // Word diff output is recognized from the input: a porcelain line terminator '~', or plain
+    // markers. Once recognized, all subsequent hunks in the input are handled as word diff.
+    pub fn handle_word_diff_line(&mut self) -> std::io::Result<bool> {
+        let format = match self.test_word_diff_line() {
+            Some(format) => format,
+            None => return Ok(false),
+        };
+        // Whether the current line of the file has already been painted as a unified diff line.
+        let mut is_painted = false;
+        match &self.state.clone() {
+            State::HunkHeader(_, parsed_hunk_header, line, raw_line) => {
+                self.emit_hunk_header_line(parsed_hunk_header, line, raw_line)?;
+            }
+            State::WordDiff(_) => {}
+            state if format == WordDiffFormat::Porcelain => {
+                // Porcelain output is recognized at the end of its first line; unless they were
+                // held back, the preceding hunk lines were handled as unified diff lines. Buffered
+                // minus and plus lines are the remaining sections of this line, whereas unchanged
+                // lines have been painted already.
+                is_painted = matches!(state, State::HunkZero(_, _));
+                let minus_lines = std::mem::take(&mut self.painter.minus_lines);
+                let plus_lines = std::mem::take(&mut self.painter.plus_lines);
+                for (section, lines) in [
+                    (WordDiffSection::Minus, minus_lines),
+                    (WordDiffSection::Plus, plus_lines),
+                ] {
+                    self.painter.word_diff_line.extend(
+                        lines
+                            .into_iter()
+                            .map(|(line, _)| (section, line.trim_end_matches('\n').to_string())),
+                    );
+                }
+            }
+            _ => self.painter.paint_buffered_minus_and_plus_lines(),
+        }
+        self.word_diff_format = Some(format);
+        self.state = State::WordDiff(format);
+
+        match format {
+            WordDiffFormat::Plain => {
+                let word_diff_line = parse_word_diff_plain_line(&self.line);
+                paint_word_diff_line(&word_diff_line, &mut self.painter, self.config);
+            }
+            WordDiffFormat::Porcelain => match self.line.chars().next() {
+                Some('~') => {
+                    let word_diff_line = std::mem::take(&mut self.painter.word_diff_line);
+                    if !(is_painted && word_diff_line.is_empty()) {
+                        paint_word_diff_line(&word_diff_line, &mut self.painter, self.config);
+                    }
+                }
+                Some(prefix @ (' ' | '-' | '+')) => {
+                    let section = match prefix {
+                        '-' => WordDiffSection::Minus,
+                        '+' => WordDiffSection::Plus,
+                        _ => WordDiffSection::Zero,
+                    };
+                    self.painter
+                        .word_diff_line
+                        .push((section, self.line[1..].to_string()));
+                }
+                _ => {
+                    // E.g. '\ No newline at end of file'
+                    self.painter.output_buffer.push_str(&self.raw_line);
+                    self.painter.output_buffer.push('\n');
+                }
+            },
+        }
+        self.painter.emit()?;
+        Ok(true)
+    }
+}
+
+lazy_static! {
+    static ref WORD_DIFF_PLAIN_MARKER_REGEX: Regex =
+        Regex::new(r"\[-(.*?)-\]|\{\+(.*?)\+\}").unwrap();
+}
+
+/// Return the word diff format of the line, if it is recognizably a word diff line.
+fn detect_word_diff_format(line: &str) -> Option<WordDiffFormat> {
+    if line == "~" {
+        // A '~' line cannot occur in a unified diff hunk.
+        Some(WordDiffFormat::Porcelain)
+    } else if WORD_DIFF_PLAIN_MARKER_REGEX.is_match(line)
+        // A line starting with ' ', '-', or '+' could be a unified diff line whose content happens
+        // to contain markers, so require confirmation from the calling process.
+        && (!line.starts_with([' ', '-', '+']) || requested_word_diff_format().is_some())
+    {
+        Some(WordDiffFormat::Plain)
+    } else {
+        None
+    }
+}
+
+/// Given input like "let [-x-]{+y+} = 1;", return the zero, minus, and plus sections of the line.
+fn parse_word_diff_plain_line(line: &str) -> WordDiffLine {
+    let mut sections = Vec::new();
+    let mut offset = 0;
+    for caps in WORD_DIFF_PLAIN_MARKER_REGEX.captures_iter(line) {
+        let marker = caps.get(0).unwrap();
+        if marker.start() > offset {
+            sections.push((
+                WordDiffSection::Zero,
+                line[offset..marker.start()].to_string(),
+            ));
+        }
+        match (caps.get(1), caps.get(2)) {
+            (Some(minus), _) => sections.push((WordDiffSection::Minus, minus.as_str().to_string())),
+            (_, Some(plus)) => sections.push((WordDiffSection::Plus, plus.as_str().to_string())),
+            _ => delta_unreachable("Word diff marker must have a minus or plus group."),
+        }
+        offset = marker.end();
+    }
+    if offset < line.len() {
+        sections.push((WordDiffSection::Zero, line[offset..].to_string()));
+    }
+    sections
+}
+
+/// Concatenate the sections of `word_diff_line` that are included in the line, expanding tabs and
+/// terminating with a newline. Return the line and the byte length of each included section.
+fn make_line(
+    word_diff_line: &[(WordDiffSection, String)],
+    include: &[WordDiffSection],
+    config: &Config,
+) -> (String, Vec<(WordDiffSection, usize)>) {
+    let mut line = String::new();
+    let mut section_lengths = Vec::new();
+    for (section, text) in word_diff_line {
+        if include.contains(section) {
+            let text = expand_tabs(text.graphemes(true), config.tab_width);
+            section_lengths.push((*section, text.len()));
+            line.push_str(&text);
+        }
+    }
+    line.push('\n');
+    (line, section_lengths)
+}
+
+/// Return style sections covering `line`, the newline being styled with `newline_style`.
+fn make_style_sections<'a>(
+    line: &'a str,
+    section_lengths: &[(WordDiffSection, usize)],
+    style: impl Fn(WordDiffSection) -> Style,
+    newline_style: Style,
+) -> LineSections<'a, Style> {
+    let mut style_sections = Vec::new();
+    let mut offset = 0;
+    for (section, length) in section_lengths {
+        if *length > 0 {
+            style_sections.push((style(*section), &line[offset..offset + length]));
+        }
+        offset += length;
+    }
+    style_sections.push((newline_style, &line[offset..]));
+    style_sections
+}
+
+fn paint_word_diff_line(
+    word_diff_line: &[(WordDiffSection, String)],
+    painter: &mut Painter,
+    config: &Config,
+) {
+    use WordDiffSection::*;
+    let has_section = |section| {
+        word_diff_line
+            .iter()
+            .any(|(s, text)| *s == section && !text.is_empty())
+    };
+    let (has_minus, has_plus) = (has_section(Minus), has_section(Plus));
+
+    if !has_minus && !has_plus {
+        let (line, _) = make_line(word_diff_line, &[Zero], config);
+        painter.paint_zero_line(&line, State::HunkZero(DiffType::Unified, None));
+    } else if config.side_by_side {
+        paint_word_diff_line_side_by_side(word_diff_line, painter, config);
+    } else {
+        // A line from which words were only removed (or to which words were only added) is
+        // displayed as a minus (plus) line: it contributes to the line numbers of one side only.
+        // The removed and added words of any other line are emphasized, even if no text of the
+        // line is unchanged.
+        let has_zero = has_section(Zero);
+        let (state, base_style) = match (has_zero, has_minus, has_plus) {
+            (false, true, false) => (
+                State::HunkMinus(DiffType::Unified, None),
+                config.minus_style,
+            ),
+            (false, false, true) => (State::HunkPlus(DiffType::Unified, None), config.plus_style),
+            (false, true, true) => (State::HunkZero(DiffType::Unified, None), config.zero_style),
+            _ => (State::HunkZero(DiffType::Unified, None), config.zero_style),
+        };
+        let (line, section_lengths) = make_line(word_diff_line, &[Zero, Minus, Plus], config);
+        let lines = vec![(line, state)];
+        let syntax_style_sections = paint::get_syntax_style_sections_for_lines(
+            &lines,
+            painter.highlighter.as_mut(),
+            config,
+        );
+        let diff_style_sections = vec![make_style_sections(
+            &lines[0].0,
+            &section_lengths,
+            |section| match section {
+                Minus if has_zero || has_plus => config.minus_emph_style,
+                Plus if has_zero || has_minus => config.plus_emph_style,
+                _ => base_style,
+            },
+            base_style,
+        )];
+        Painter::paint_lines(
+            &lines,
+            &syntax_style_sections,
+            &diff_style_sections,
+            &[false],
+            &mut painter.output_buffer,
+            config,
+            &mut painter.line_numbers_data.as_mut(),
+            None,
+            BgShouldFill::default(),
+        );
+    }
+}
+
+/// Display the old version of the line in the left panel and the new version in the right panel.
+fn paint_word_diff_line_side_by_side(
+    word_diff_line: &[(WordDiffSection, String)],
+    painter: &mut Painter,
+    config: &Config,
+) {
+    let has_zero = word_diff_line
+        .iter()
+        .any(|(s, text)| *s == WordDiffSection::Zero && !text.is_empty());
+    let mut lines = LeftRight::<Vec<(String, State)>>::default();
+    let mut section_lengths = LeftRight::<Vec<Vec<(WordDiffSection, usize)>>>::default();
+    for (side, section, state) in [
+        (
+            Left,
+            WordDiffSection::Minus,
+            State::HunkMinus(DiffType::Unified, None),
+        ),
+        (
+            Right,
+            WordDiffSection::Plus,
+            State::HunkPlus(DiffType::Unified, None),
+        ),
+    ] {
+        if has_zero || word_diff_line.iter().any(|(s, _)| *s == section) {
+            let (line, lengths) =
+                make_line(word_diff_line, &[WordDiffSection::Zero, section], config);
+            lines[side].push((line, state));
+            section_lengths[side].push(lengths);
+        }
+    }
+    let is_paired = !lines[Left].is_empty() && !lines[Right].is_empty();
+
+    let style = |section, side| match (section, side, is_paired) {
+        (WordDiffSection::Minus, _, _) => config.minus_emph_style,
+        (WordDiffSection::Plus, _, _) => config.plus_emph_style,
+        (WordDiffSection::Zero, Left, true) => config.minus_non_emph_style,
+        (WordDiffSection::Zero, Left, false) => config.minus_style,
+        (WordDiffSection::Zero, Right, true) => config.plus_non_emph_style,
+        (WordDiffSection::Zero, Right, false) => config.plus_style,
+    };
+    let mut syntax_style_sections = LeftRight::<Vec<LineSections<SyntectStyle>>>::default();
+    let mut diff_style_sections = LeftRight::<Vec<LineSections<Style>>>::default();
+    for side in [Left, Right] {
+        syntax_style_sections[side] = paint::get_syntax_style_sections_for_lines(
+            &lines[side],
+            painter.highlighter.as_mut(),
+            config,
+        );
+        diff_style_sections[side] = lines[side]
+            .iter()
+            .zip(&section_lengths[side])
+            .map(|((line, _), lengths)| {
+                make_style_sections(
+                    line,
+                    lengths,
+                    |section| style(section, side),
+                    style(WordDiffSection::Zero, side),
+                )
+            })
+            .collect();
+    }
+    let line_alignment = vec![(
+        (!lines[Left].is_empty()).then_some(0),
+        (!lines[Right].is_empty()).then_some(0),
+    )];
+    let lines_have_homolog = LeftRight::new(
+        vec![is_paired; lines[Left].len()],
+        vec![is_paired; lines[Right].len()],
+    );
+    side_by_side::paint_minus_and_plus_lines_side_by_side(
+        LeftRight::new(&lines[Left], &lines[Right]),
+        syntax_style_sections,
+        diff_style_sections,
+        lines_have_homolog,
+        line_alignment,
+        &mut painter.line_numbers_data,
+        &mut painter.output_buffer,
+        config,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::DeltaTest;
+
+    #[test]
+    fn test_parse_word_diff_plain_line() {
+        use WordDiffSection::*;
+        assert_eq!(
+            parse_word_diff_plain_line("let [-x-]{+y+} = 1;"),
+            vec![
+                (Zero, "let ".to_string()),
+                (Minus, "x".to_string()),
+                (Plus, "y".to_string()),
+                (Zero, " = 1;".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_word_diff_plain_line("{+fn f() {}+}"),
+            vec![(Plus, "fn f() {}".to_string())]
+        );
+        assert_eq!(
+            parse_word_diff_plain_line("unchanged"),
+            vec![(Zero, "unchanged".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_detect_word_diff_format() {
+        assert_eq!(
+            detect_word_diff_format("~"),
+            Some(WordDiffFormat::Porcelain)
+        );
+        assert_eq!(
+            detect_word_diff_format("[-aaa-]{+bbb+}"),
+            Some(WordDiffFormat::Plain)
+        );
+        assert_eq!(detect_word_diff_format(" aaa"), None);
+        // Could be a unified diff line, and the calling process did not request a word diff.
+        assert_eq!(detect_word_diff_format("+ x[-1-]"), None);
+    }
+
+    #[test]
+    fn test_word_diff_plain_without_calling_process() {
+        DeltaTest::with_args(&[])
+            .explain_ansi()
+            .with_input(GIT_DIFF_WORD_DIFF_PLAIN)
+            .expect_after_skip(
+                4,
+                "
+#indent_mark
+(blue)───(blue)┐(normal)
+(blue)1(normal): (blue)│(normal)
+(blue)───(blue)┘(normal)
+(normal 124)aaa(231 28)bbb(231) ccc(normal)
+(231)ddd(normal)
+",
+            );
+    }
+
+    #[test]
+    fn test_word_diff_porcelain_without_calling_process() {
+        DeltaTest::with_args(&[])
+            .explain_ansi()
+            .with_input(GIT_DIFF_WORD_DIFF_PORCELAIN)
+            .expect_after_skip(
+                4,
+                "
+#indent_mark
+(blue)───(blue)┐(normal)
+(blue)1(normal): (blue)│(normal)
+(blue)───(blue)┘(normal)
+(231)ddd(normal)
+(normal 124)aaa(231 28)bbb(231) ccc(normal)
+(231 22)eee(normal)
+",
+            );
+    }
+
+    #[test]
+    fn test_word_diff_porcelain_first_line_without_calling_process() {
+        DeltaTest::with_args(&[])
+            .with_input(GIT_DIFF_WORD_DIFF_PORCELAIN_FIRST_LINE_CHANGED)
+            .expect_after_skip(
+                4,
+                "
+#indent_mark
+───┐
+1: │
+───┘
+hello there world
+bye
+",
+            );
+    }
+
+    #[test]
+    fn test_word_diff_porcelain_line_numbers() {
+        DeltaTest::with_args(&["--line-numbers"])
+            .with_calling_process("git diff --word-diff=porcelain")
+            .with_input(GIT_DIFF_WORD_DIFF_PORCELAIN)
+            .expect_after_skip(
+                4,
+                "
+#indent_mark
+───┐
+1: │
+───┘
+  1 ⋮  1 │ddd
+  2 ⋮  2 │aaabbb ccc
+    ⋮  3 │eee
+",
+            );
+    }
+
+    #[test]
+    fn test_word_diff_plain_without_unchanged_text() {
+        DeltaTest::with_args(&[])
+            .explain_ansi()
+            .with_input(GIT_DIFF_WORD_DIFF_PLAIN_WITHOUT_UNCHANGED_TEXT)
+            .expect_after_skip(
+                4,
+                "
+#indent_mark
+(blue)───(blue)┐(normal)
+(blue)1(normal): (blue)│(normal)
+(blue)───(blue)┘(normal)
+(normal 124)foo(231 28)bar(normal)
+",
+            );
+    }
+
+    #[test]
+    fn test_word_diff_porcelain_without_unchanged_text() {
+        DeltaTest::with_args(&[])
+            .explain_ansi()
+            .with_calling_process("git diff --word-diff=porcelain")
+            .with_input(GIT_DIFF_WORD_DIFF_PORCELAIN_WITHOUT_UNCHANGED_TEXT)
+            .expect_after_skip(
+                4,
+                "
+#indent_mark
+(blue)───(blue)┐(normal)
+(blue)1(normal): (blue)│(normal)
+(blue)───(blue)┘(normal)
+(normal 124)-x(231 28)-y(normal)
+",
+            );
+    }
+
+    #[test]
+    fn test_word_diff_plain_side_by_side() {
+        DeltaTest::with_args(&["--side-by-side", "--width", "40"])
+            .with_input(GIT_DIFF_WORD_DIFF_PLAIN)
+            .expect_after_skip(
+                4,
+                "
+#indent_mark
+───┐
+1: │
+───┘
+│  1 │aaa ccc       │  1 │bbb ccc
+│  2 │ddd           │  2 │ddd
+",
+            );
+    }
+
+    const GIT_DIFF_WORD_DIFF_PLAIN: &str = "\
+diff --git a/file b/file
+index c005da6..962086f 100644
+--- a/file
++++ b/file
+@@ -1,2 +1,2 @@
+[-aaa-]{+bbb+} ccc
+ddd
+";
+
+    const GIT_DIFF_WORD_DIFF_PLAIN_WITHOUT_UNCHANGED_TEXT: &str = "\
+diff --git a/file b/file
+index c005da6..962086f 100644
+--- a/file
++++ b/file
+@@ -1 +1 @@
+[-foo-]{+bar+}
+";
+
+    const GIT_DIFF_WORD_DIFF_PORCELAIN_WITHOUT_UNCHANGED_TEXT: &str = "\
+diff --git a/file b/file
+index c005da6..962086f 100644
+--- a/file
++++ b/file
+@@ -1 +1 @@
+--x
++-y
+~
+";
+
+    const GIT_DIFF_WORD_DIFF_PORCELAIN_FIRST_LINE_CHANGED: &str = "\
+diff --git a/file b/file
+index c005da6..962086f 100644
+--- a/file
++++ b/file
+@@ -1,2 +1,2 @@
+ hello 
++there
+  world
+~
+ bye
+~
+";
+
+    const GIT_DIFF_WORD_DIFF_PORCELAIN: &str = "\
+diff --git a/file b/file
+index c005da6..962086f 100644
+--- a/file
++++ b/file
+@@ -1,2 +1,3 @@
+ ddd
+~
+-aaa
++bbb
+  ccc
+~
++eee
+~
+";
+}
@@ -0,0 +1,191 @@
+// A module for running delta as interactive.diffFilter, e.g. of `git add -p`.
+//
+// Git requires the filter to emit exactly one line for every line of its input. With
+// --interactive-filter delta handles the input as with --color-only, which does not alter it
+// structurally: it keeps the +/- markers and raw headers, as well as the user's styles and e.g.
+// line numbers. The output of the state machine is checked against its input in chunks: a chunk
+// ends whenever the painter has no buffered minus or plus lines, and its output is written once it
+// has as many lines as the input. Should a chunk have more output lines than input lines, its raw
+// input lines are written instead, so that git never sees mismatched output.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+
+use bytelines::ByteLines;
+
+use crate::config::Config;
+use crate::delta::StateMachine;
+
+/// The output of the state machine for the current chunk of input lines.
+#[derive(Default)]
+struct Output {
+    bytes: Vec<u8>,
+    n_lines: usize,
+}
+
+impl Output {
+    /// Compare the number of output lines, counting an unterminated last line, with `n_lines`.
+    fn cmp_n_lines(&self, n_lines: usize) -> Ordering {
+        let is_unterminated = !self.bytes.is_empty() && !self.bytes.ends_with(b"\n");
+        (self.n_lines + usize::from(is_unterminated)).cmp(&n_lines)
+    }
+}
+
+/// A writer which collects the output of the state machine.
+struct OutputWriter(Rc<RefCell<Output>>);
+
+impl Write for OutputWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut output = self.0.borrow_mut();
+        output.n_lines += buf.iter().filter(|b| **b == b'\n').count();
+        output.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Process the input of interactive.diffFilter, starting with `first_line`, emitting exactly one
+/// line for every input line.
+pub fn delta_as_interactive_filter<I>(
+    first_line: Vec<u8>,
+    mut lines: ByteLines<I>,
+    writer: &mut dyn Write,
+    config: &Config,
+) -> std::io::Result<()>
+where
+    I: BufRead,
+{
+    let output = Rc::new(RefCell::new(Output::default()));
+    let mut output_writer = OutputWriter(Rc::clone(&output));
+    let mut state_machine = StateMachine::new(&mut output_writer, config);
+    let mut input_lines = Vec::new();
+    let mut raw_line = Some(first_line);
+    while let Some(line) = raw_line {
+        state_machine.consume_line(&line)?;
+        input_lines.push(line);
+        let painter = &mut state_machine.painter;
+        if painter.minus_lines.is_empty() && painter.plus_lines.is_empty() {
+            painter.emit()?;
+            write_chunk(&mut input_lines, &mut output.borrow_mut(), writer, false)?;
+        }
+        raw_line = match lines.next() {
+            Some(Ok(raw_line_bytes)) => Some(raw_line_bytes.to_vec()),
+            _ => None,
+        };
+    }
+    state_machine.finish()?;
+    write_chunk(&mut input_lines, &mut output.borrow_mut(), writer, true)?;
+    Ok(())
+}
+
+/// Write the output of a chunk of input lines if it corresponds to them line for line, and their
+/// raw text if it has more lines. Output lagging behind its input is kept for a later chunk,
+/// unless this is the last one.
+fn write_chunk(
+    input_lines: &mut Vec<Vec<u8>>,
+    output: &mut Output,
+    writer: &mut dyn Write,
+    is_last_chunk: bool,
+) -> std::io::Result<()> {
+    match output.cmp_n_lines(input_lines.len()) {
+        Ordering::Less if !is_last_chunk => return Ok(()),
+        Ordering::Equal => writer.write_all(&output.bytes)?,
+        _ => {
+            for line in input_lines.iter() {
+                writer.write_all(line)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+    }
+    input_lines.clear();
+    *output = Output::default();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::{make_config_from_args, run_delta, DeltaTest};
+
+    #[test]
+    fn test_write_chunk() {
+        let mut writer = Vec::new();
+        let mut input_lines = vec![b"a".to_vec(), b"b".to_vec()];
+        let mut output = Output {
+            bytes: b"A\n".to_vec(),
+            n_lines: 1,
+        };
+        write_chunk(&mut input_lines, &mut output, &mut writer, false).unwrap();
+        assert_eq!(writer, b"");
+        output.bytes.extend_from_slice(b"B\n");
+        output.n_lines += 1;
+        write_chunk(&mut input_lines, &mut output, &mut writer, false).unwrap();
+        assert_eq!(writer, b"A\nB\n");
+        assert!(input_lines.is_empty());
+
+        let mut input_lines = vec![b"c".to_vec()];
+        let mut output = Output {
+            bytes: b"C\n\nC\n".to_vec(),
+            n_lines: 3,
+        };
+        write_chunk(&mut input_lines, &mut output, &mut writer, false).unwrap();
+        assert_eq!(writer, b"A\nB\nc\n");
+    }
+
+    #[test]
+    fn test_interactive_filter_output_is_in_one_to_one_correspondence_with_input() {
+        for args in [
+            &["--interactive-filter"][..],
+            &["--interactive-filter", "--line-numbers"],
+            &["--interactive-filter", "--side-by-side"],
+            &[
+                "--interactive-filter",
+                "--hunk-header-decoration-style",
+                "box",
+            ],
+            &["--interactive-filter", "--file-decoration-style", "ol ul"],
+        ] {
+            let config = make_config_from_args(args);
+            let output = run_delta(DIFF, &config);
+            assert_eq!(output.lines().count(), DIFF.lines().count(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn test_interactive_filter_keeps_line_numbers() {
+        DeltaTest::with_args(&["--interactive-filter", "--line-numbers"])
+            .with_input(DIFF)
+            .expect_contains("  1 ⋮  1 │ fn main() {\n")
+            .expect_contains("  2 ⋮    │-    let x = 1;\n")
+            .expect_contains("    ⋮  2 │+    let x = 2;\n");
+    }
+
+    #[test]
+    fn test_interactive_filter_applies_color_only_defaults() {
+        // E.g. the +/- markers are kept and the hunk header is not highlighted.
+        let output = run_delta(DIFF, &make_config_from_args(&["--interactive-filter"]));
+        assert_eq!(
+            output,
+            run_delta(DIFF, &make_config_from_args(&["--color-only"]))
+        );
+        assert!(output.contains("\n@@ -1,3 +1,3 @@\n"));
+        assert!(output.contains("\x1b[48;5;22m+"));
+    }
+
+    const DIFF: &str = "\
+diff --git a/src/main.rs b/src/main.rs
+index 8b13789..e69de29 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@
+ fn main() {
+-    let x = 1;
++    let x = 2;
+ }
+";
+}
@@ -3,6 +3,7 @@ mod ansi;
 mod cli;
 mod color;
 mod colors;
+mod combined;
 mod config;
 mod delta;
 mod edits;
@@ -11,7 +12,9 @@ mod features;
 mod format;
 mod git_config;
 mod handlers;
+mod interactive_filter;
 mod minusplus;
+mod moved;
 mod options;
 mod paint;
 mod parse_style;
@@ -0,0 +1,404 @@
+// A module for detecting moved lines, without relying on git's --color-moved.
+//
+// With --detect-moved, the input is handled one commit at a time; input without commits is
+// handled as a whole. The minus and plus lines of a commit are first collected by a state machine
+// whose output is discarded. A moved block is a run of consecutive minus lines which are also
+// found as consecutive plus lines, ignoring leading and trailing whitespace. Lines of a block may
+// also have been edited, as long as each of them is homologous to its counterpart (see
+// --max-line-distance). A block must contain at least `moved-min-lines` lines which have not been
+// edited and contain alphanumeric characters. The commit is then handled again, and the painter
+// paints the lines of moved blocks in minus-moved-style and plus-moved-style, emphasizing the edits
+// of edited lines against their counterparts.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, Write};
+
+use bytelines::ByteLines;
+
+use crate::ansi;
+use crate::config::Config;
+use crate::delta::{State, StateMachine};
+use crate::edits;
+use crate::handlers;
+use crate::minusplus::*;
+use crate::paint::LineSections;
+use crate::style::Style;
+
+/// The moved lines of the commit being handled.
+#[derive(Debug)]
+pub enum MovedLines {
+    // The minus and plus lines of the commit are being collected.
+    Collecting(MinusPlus<Vec<String>>),
+    // The minus and plus lines of the commit which remain to be painted, in order, each with its
+    // counterpart if it was moved.
+    Detected(MinusPlus<VecDeque<(String, Option<MovedLine>)>>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MovedLine {
+    // The line that this line was moved from or to, if this line was also edited
+    pub edited_counterpart: Option<String>,
+}
+
+/// Handle the input one commit at a time, detecting the moved lines of each commit first.
+pub fn delta_with_moved_line_detection<I>(
+    first_line: Vec<u8>,
+    mut lines: ByteLines<I>,
+    writer: &mut dyn Write,
+    config: &Config,
+) -> std::io::Result<()>
+where
+    I: BufRead,
+{
+    let mut state_machine = StateMachine::new(writer, config);
+    let mut commit_lines = vec![first_line];
+    while let Some(Ok(raw_line_bytes)) = lines.next() {
+        if is_commit_line(raw_line_bytes, config) {
+            handle_commit(&mut state_machine, &commit_lines, config)?;
+            commit_lines.clear();
+        }
+        commit_lines.push(raw_line_bytes.to_vec());
+    }
+    handle_commit(&mut state_machine, &commit_lines, config)?;
+    state_machine.finish()
+}
+
+fn is_commit_line(raw_line_bytes: &[u8], config: &Config) -> bool {
+    let line = ansi::strip_ansi_codes(&String::from_utf8_lossy(raw_line_bytes));
+    config.commit_regex.is_match(&line) || handlers::email_patch::is_email_patch_from_line(&line)
+}
+
+fn handle_commit(
+    state_machine: &mut StateMachine,
+    lines: &[Vec<u8>],
+    config: &Config,
+) -> std::io::Result<()> {
+    let mut sink = std::io::sink();
+    let mut collector = StateMachine::new(&mut sink, config);
+    collector.painter.moved_lines = Some(MovedLines::Collecting(MinusPlus::default()));
+    for line in lines {
+        collector.consume_line(line)?;
+    }
+    collector.finish()?;
+    let collected_lines = match collector.painter.moved_lines.take() {
+        Some(MovedLines::Collecting(collected_lines)) => collected_lines,
+        _ => MinusPlus::default(),
+    };
+    let moved_lines = detect_moved_lines(&collected_lines, config);
+    let detected_lines = MinusPlus::new(
+        collected_lines
+            .minus
+            .into_iter()
+            .zip(moved_lines.minus)
+            .collect(),
+        collected_lines
+            .plus
+            .into_iter()
+            .zip(moved_lines.plus)
+            .collect(),
+    );
+    // Lines still buffered belong to the previous commit.
+    state_machine.painter.paint_buffered_minus_and_plus_lines();
+    state_machine.painter.moved_lines = Some(MovedLines::Detected(detected_lines));
+    for line in lines {
+        state_machine.consume_line(line)?;
+    }
+    Ok(())
+}
+
+/// Return, for each minus and each plus line, its counterpart if it belongs to a moved block.
+fn detect_moved_lines(
+    lines: &MinusPlus<Vec<String>>,
+    config: &Config,
+) -> MinusPlus<Vec<Option<MovedLine>>> {
+    let mut moved_lines = MinusPlus::new(
+        vec![None; lines[Minus].len()],
+        vec![None; lines[Plus].len()],
+    );
+    let mut minus_line_indices = HashMap::<&str, Vec<usize>>::new();
+    for (i, line) in lines[Minus].iter().enumerate() {
+        minus_line_indices.entry(line.trim()).or_default().push(i);
+    }
+    let mut j = 0;
+    while j < lines[Plus].len() {
+        // The longest block starting with this plus line.
+        let block_length = minus_line_indices
+            .get(lines[Plus][j].trim())
+            .into_iter()
+            .flatten()
+            .map(|&i| (i, get_moved_block_length(lines, &moved_lines, i, j, config)))
+            .filter(|(_, length)| *length > 0)
+            .max_by_key(|(i, length)| (*length, std::cmp::Reverse(*i)));
+        match block_length {
+            Some((i, length)) => {
+                for k in 0..length {
+                    let (minus_line, plus_line) = (&lines[Minus][i + k], &lines[Plus][j + k]);
+                    let is_edited = minus_line.trim() != plus_line.trim();
+                    moved_lines[Minus][i + k] = Some(MovedLine {
+                        edited_counterpart: Some(plus_line.clone()).filter(|_| is_edited),
+                    });
+                    moved_lines[Plus][j + k] = Some(MovedLine {
+                        edited_counterpart: Some(minus_line.clone()).filter(|_| is_edited),
+                    });
+                }
+                j += length;
+            }
+            None => j += 1,
+        }
+    }
+    moved_lines
+}
+
+/// Return the length of the moved block starting with minus line i and plus line j, or zero if
+/// there is no such block.
+fn get_moved_block_length(
+    lines: &MinusPlus<Vec<String>>,
+    moved_lines: &MinusPlus<Vec<Option<MovedLine>>>,
+    i: usize,
+    j: usize,
+    config: &Config,
+) -> usize {
+    let (mut length, mut n_counted_lines) = (0, 0);
+    for (k, (minus_line, plus_line)) in lines[Minus][i..].iter().zip(&lines[Plus][j..]).enumerate()
+    {
+        if moved_lines[Minus][i + k].is_some() {
+            break;
+        }
+        if minus_line.trim() == plus_line.trim() {
+            // The block ends with a line which was not edited.
+            length = k + 1;
+            if minus_line.chars().any(char::is_alphanumeric) {
+                n_counted_lines += 1;
+            }
+        } else if !is_homologous(minus_line, plus_line, config) {
+            break;
+        }
+    }
+    if n_counted_lines >= config.moved_min_lines.max(1) {
+        length
+    } else {
+        0
+    }
+}
+
+fn is_homologous(minus_line: &str, plus_line: &str, config: &Config) -> bool {
+    let (_, _, line_alignment) = edits::infer_edits(
+        vec![minus_line],
+        vec![plus_line],
+        vec![()],
+        (),
+        vec![()],
+        (),
+        &config.tokenization_regex,
+        config.max_line_distance,
+        config.max_line_distance,
+        false,
+    );
+    line_alignment == [(Some(0), Some(0))]
+}
+
+/// Return the moved lines detected for `lines`, which are about to be painted.
+pub fn take_moved_lines(
+    detected_lines: &mut VecDeque<(String, Option<MovedLine>)>,
+    lines: &[(String, State)],
+) -> Vec<Option<MovedLine>> {
+    lines
+        .iter()
+        .map(|(line, _)| match detected_lines.pop_front() {
+            Some((detected_line, moved_line)) if detected_line == *line => moved_line,
+            _ => None,
+        })
+        .collect()
+}
+
+/// Paint moved lines in minus-moved-style and plus-moved-style, instead of pairing them with lines
+/// of the same hunk. An edited moved line is emphasized against its counterpart.
+pub fn update_diff_style_sections_of_moved_lines<'a>(
+    lines: &MinusPlus<&'a Vec<(String, State)>>,
+    moved_lines: &'a MinusPlus<Vec<Option<MovedLine>>>,
+    diff_style_sections: &mut MinusPlus<Vec<LineSections<'a, Style>>>,
+    line_alignment: &mut Vec<(Option<usize>, Option<usize>)>,
+    config: &Config,
+) {
+    let is_moved =
+        |side: MinusPlusIndex, i: usize| matches!(moved_lines[side].get(i), Some(Some(_)));
+    let mut updated_line_alignment = Vec::with_capacity(line_alignment.len());
+    for &(minus_index, plus_index) in line_alignment.iter() {
+        match (minus_index, plus_index) {
+            (Some(i), Some(j)) if is_moved(Minus, i) || is_moved(Plus, j) => {
+                // The lines were paired, and each has been emphasized against the other.
+                for (side, k) in [(Minus, i), (Plus, j)] {
+                    let (line, state) = &lines[side][k];
+                    diff_style_sections[side][k] = vec![(*config.get_style(state), line.as_str())];
+                }
+                updated_line_alignment.push((Some(i), None));
+                updated_line_alignment.push((None, Some(j)));
+            }
+            _ => updated_line_alignment.push((minus_index, plus_index)),
+        }
+    }
+    *line_alignment = updated_line_alignment;
+
+    for (side, moved_style) in [
+        (Minus, config.minus_moved_style),
+        (Plus, config.plus_moved_style),
+    ] {
+        for (k, moved_line) in moved_lines[side].iter().enumerate() {
+            let (line, moved_line) = match (lines[side].get(k), moved_line) {
+                (Some((line, _)), Some(moved_line)) => (line.as_str(), moved_line),
+                _ => continue,
+            };
+            diff_style_sections[side][k] = match &moved_line.edited_counterpart {
+                Some(counterpart) => {
+                    get_edited_moved_line_style_sections(side, line, counterpart, config)
+                }
+                None => vec![(moved_style, line)],
+            };
+        }
+    }
+}
+
+fn get_edited_moved_line_style_sections<'a>(
+    side: MinusPlusIndex,
+    line: &'a str,
+    counterpart: &'a str,
+    config: &Config,
+) -> LineSections<'a, Style> {
+    let (minus_line, plus_line) = match side {
+        Minus => (line, counterpart),
+        Plus => (counterpart, line),
+    };
+    let (mut minus_sections, mut plus_sections, _) = edits::infer_edits(
+        vec![minus_line],
+        vec![plus_line],
+        vec![config.minus_moved_style],
+        config.minus_emph_style,
+        vec![config.plus_moved_style],
+        config.plus_emph_style,
+        &config.tokenization_regex,
+        config.max_line_distance,
+        config.max_line_distance,
+        false,
+    );
+    match side {
+        Minus => minus_sections.remove(0),
+        Plus => plus_sections.remove(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::integration_test_utils::{self, DeltaTest};
+
+    #[test]
+    fn test_detect_moved_lines() {
+        let config = integration_test_utils::make_config_from_args(&["--moved-min-lines", "2"]);
+        let to_lines = |lines: &[&str]| lines.iter().map(|line| format!("{line}\n")).collect();
+        let lines = MinusPlus::new(
+            to_lines(&["fn a() {", "    let x = 1;", "}", "removed"]),
+            to_lines(&["added", "  fn a() {", "      let x = 2;", "  }"]),
+        );
+        let moved_lines = detect_moved_lines(&lines, &config);
+        let moved = |edited_counterpart: Option<&str>| {
+            Some(MovedLine {
+                edited_counterpart: edited_counterpart.map(|line| format!("{line}\n")),
+            })
+        };
+        // The edited line does not count, so the block is too short.
+        assert_eq!(moved_lines, MinusPlus::new(vec![None; 4], vec![None; 4]));
+
+        let config = integration_test_utils::make_config_from_args(&["--moved-min-lines", "1"]);
+        let moved_lines = detect_moved_lines(&lines, &config);
+        assert_eq!(
+            moved_lines,
+            MinusPlus::new(
+                vec![
+                    moved(None),
+                    moved(Some("      let x = 2;")),
+                    moved(None),
+                    None
+                ],
+                vec![
+                    None,
+                    moved(None),
+                    moved(Some("    let x = 1;")),
+                    moved(None)
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_detect_moved_lines_unrelated_lines() {
+        let config = integration_test_utils::make_config_from_args(&["--moved-min-lines", "2"]);
+        let to_lines = |lines: &[&str]| lines.iter().map(|line| format!("{line}\n")).collect();
+        let lines = MinusPlus::new(
+            to_lines(&["let x = 1;", "let y = 2;"]),
+            to_lines(&["let x = 1;", "something else entirely", "let y = 2;"]),
+        );
+        let moved_lines = detect_moved_lines(&lines, &config);
+        assert_eq!(moved_lines, MinusPlus::new(vec![None; 2], vec![None; 3]));
+    }
+
+    #[test]
+    fn test_detect_moved() {
+        DeltaTest::with_args(&[
+            "--detect-moved",
+            "--minus-moved-style",
+            "magenta",
+            "--plus-moved-style",
+            "cyan",
+            "--minus-emph-style",
+            "red",
+            "--plus-emph-style",
+            "green",
+        ])
+        .explain_ansi()
+        .with_input(DIFF_WITH_MOVED_BLOCK)
+        .expect_contains(
+            "(purple)fn helper() {(normal)\n\
+             (purple)    let first = compute_first_value();(normal)\n\
+             (purple)    let second = (red)compute_second_value(purple)();(normal)\n\
+             (purple)    let third = compute_third_value();(normal)\n\
+             (purple)}(normal)\n\
+             (normal 52)fn removed() {}(normal)",
+        )
+        .expect_contains(
+            "(cyan)    let first = compute_first_value();(normal)\n\
+             (cyan)    let second = (green)compute_other_value(cyan)();(normal)\n\
+             (cyan)    let third = compute_third_value();(normal)",
+        );
+    }
+
+    #[test]
+    fn test_detect_moved_is_off_by_default() {
+        DeltaTest::with_args(&["--minus-moved-style", "magenta"])
+            .explain_ansi()
+            .with_input(DIFF_WITH_MOVED_BLOCK)
+            .expect_contains("(normal 52)fn helper() {(normal)");
+    }
+
+    const DIFF_WITH_MOVED_BLOCK: &str = "\
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,9 +1,9 @@
+-fn helper() {
+-    let first = compute_first_value();
+-    let second = compute_second_value();
+-    let third = compute_third_value();
+-}
+-fn removed() {}
+ fn main() {
+     helper();
+ }
+@@ -20,3 +20,8 @@ fn other() {
+ fn other() {
+ }
++fn helper() {
++    let first = compute_first_value();
++    let second = compute_other_value();
++    let third = compute_third_value();
++}
+";
+}
@@ -15,7 +15,9 @@ use crate::features;
 use crate::git_config::GitConfig;
 use crate::options::option_value::{OptionValue, ProvenancedOptionValue};
 use crate::options::theme;
+use crate::tests::TESTING;
 use crate::utils::bat::output::PagingMode;
+use crate::utils::process;
 
 macro_rules! set_options {
     ([$( $field_ident:ident ),* ],
@@ -86,12 +88,43 @@ pub fn set_options(
     // Set features
     let mut builtin_features = features::make_builtin_features();
 
+    // As interactive.diffFilter, delta reads from and writes to git rather than a terminal. Unless
+    // git config says whether delta is an interactive filter, its calling process is looked for
+    // then. This is decided before the features are gathered, so that the color-only feature
+    // applies to the interactive filter.
+    if !opt.interactive_filter {
+        opt.interactive_filter = match git_config
+            .as_ref()
+            .and_then(|git_config| git_config.get::<bool>("delta.interactive-filter"))
+        {
+            Some(interactive_filter) => interactive_filter,
+            None => {
+                !atty::is(atty::Stream::Stdin)
+                    && !atty::is(atty::Stream::Stdout)
+                    && !TESTING
+                    && process::git_interactive_patch()
+            }
+        };
+    }
+
     // --color-only is used for interactive.diffFilter (git add -p) and side-by-side cannot be used
     // there (does not emit lines in 1-1 correspondence with raw git output). See #274.
-    if config::user_supplied_option("color_only", arg_matches) {
+    if config::user_supplied_option("color_only", arg_matches) || opt.interactive_filter {
         builtin_features.remove("side-by-side");
     }
 
+    // `side-by-side = auto` enables side-by-side, unless delta is narrower than
+    // side-by-side-min-width. This is decided before the features are gathered, so that the
+    // side-by-side feature does not apply to unified output.
+    if let Some(git_config) = git_config {
+        if !opt.side_by_side
+            && git_config.get::<String>("delta.side-by-side").as_deref() == Some("auto")
+        {
+            opt.side_by_side =
+                !is_narrower_than_side_by_side_min_width(opt, git_config, arg_matches);
+        }
+    }
+
     let features = gather_features(opt, &builtin_features, git_config);
     opt.features = Some(features.join(" "));
 
@@ -126,19 +159,33 @@ pub fn set_options(
 
     set_options!(
         [
+            binary_hexdump,
             blame_code_style,
             blame_format,
+            blame_moved_style,
             blame_separator_format,
             blame_palette,
             blame_separator_style,
             blame_timestamp_format,
             blame_timestamp_output_format,
             color_only,
+            combined_diff_columns,
             config,
+            commit_author_style,
+            commit_branch_style,
+            commit_date_format,
+            commit_date_style,
             commit_decoration_style,
             commit_regex,
+            commit_remote_style,
+            commit_signature_bad_style,
+            commit_signature_good_style,
+            commit_signature_unknown_style,
             commit_style,
+            commit_tag_style,
+            commit_trailer_style,
             default_language,
+            detect_moved,
             diff_stat_align_width,
             file_added_label,
             file_copied_label,
@@ -163,8 +210,10 @@ pub fn set_options(
             hyperlinks,
             hyperlinks_commit_link_format,
             hyperlinks_file_link_format,
+            hyperlinks_signature_link_format,
             inline_hint_style,
             inspect_raw_lines,
+            interactive_filter,
             keep_plus_minus_markers,
             line_buffer_size,
             map_styles,
@@ -181,10 +230,14 @@ pub fn set_options(
             minus_style,
             minus_emph_style,
             minus_empty_line_marker_style,
+            minus_moved_style,
             minus_non_emph_style,
             minus_non_emph_style,
+            moved_min_lines,
             navigate,
             navigate_regex,
+            notes_decoration_style,
+            notes_style,
             line_fill_method,
             line_numbers,
             line_numbers_left_format,
@@ -202,12 +255,15 @@ pub fn set_options(
             plus_style,
             plus_emph_style,
             plus_empty_line_marker_style,
+            plus_moved_style,
             plus_non_emph_style,
             raw,
             relative_paths,
             show_colors,
             show_themes,
             side_by_side,
+            side_by_side_min_width,
+            side_by_side_split,
             wrap_max_lines,
             wrap_right_prefix_symbol,
             wrap_right_percent,
@@ -215,6 +271,7 @@ pub fn set_options(
             wrap_left_symbol,
             tab_width,
             tokenization_regex,
+            tree_summary,
             true_color,
             whitespace_error_style,
             width,
@@ -236,6 +293,10 @@ pub fn set_options(
         cli::InspectRawLines::from_str(&opt.inspect_raw_lines).unwrap();
     opt.computed.paging_mode = parse_paging_mode(&opt.paging_mode);
 
+    if opt.interactive_filter {
+        opt.color_only = true;
+    }
+
     // --color-only is used for interactive.diffFilter (git add -p). side-by-side, and
     // **-decoration-style cannot be used there (does not emit lines in 1-1 correspondence with raw git output).
     // See #274.
@@ -244,6 +305,7 @@ pub fn set_options(
         opt.file_decoration_style = "none".to_string();
         opt.commit_decoration_style = "none".to_string();
         opt.hunk_header_decoration_style = "none".to_string();
+        opt.notes_decoration_style = "none".to_string();
     }
 }
 
@@ -370,7 +432,7 @@ fn gather_features(
     if opt.raw {
         gather_builtin_features_recursively("raw", &mut features, builtin_features, opt);
     }
-    if opt.color_only {
+    if opt.color_only || opt.interactive_filter {
         gather_builtin_features_recursively("color-only", &mut features, builtin_features, opt);
     }
     if opt.diff_highlight {
@@ -599,28 +661,62 @@ fn parse_width_specifier(width_arg: &str, terminal_width: usize) -> Result<usize
 fn set_widths_and_isatty(opt: &mut cli::Opt) {
     let term_stdout = Term::stdout();
     opt.computed.stdout_is_term = term_stdout.is_term();
+    opt.computed.available_terminal_width = get_available_terminal_width(&term_stdout);
 
+    let (decorations_width, background_color_extends_to_terminal_width) =
+        get_decorations_width(opt.width.as_deref(), opt.computed.available_terminal_width);
+    opt.computed.decorations_width = decorations_width;
+    opt.computed.background_color_extends_to_terminal_width =
+        background_color_extends_to_terminal_width;
+}
+
+fn get_available_terminal_width(term_stdout: &Term) -> usize {
     // If one extra character for e.g. `less --status-column` is required use "-1"
     // as an argument, also see #41, #10, #115 and #727.
-    opt.computed.available_terminal_width =
-        crate::utils::workarounds::windows_msys2_width_fix(term_stdout.size(), &term_stdout);
+    crate::utils::workarounds::windows_msys2_width_fix(term_stdout.size(), term_stdout)
+}
 
-    let (decorations_width, background_color_extends_to_terminal_width) = match opt.width.as_deref()
-    {
+fn get_decorations_width(
+    width: Option<&str>,
+    available_terminal_width: usize,
+) -> (cli::Width, bool) {
+    match width {
         Some("variable") => (cli::Width::Variable, false),
         Some(width) => {
-            let width = parse_width_specifier(width, opt.computed.available_terminal_width)
+            let width = parse_width_specifier(width, available_terminal_width)
                 .unwrap_or_else(|err| fatal(format!("Invalid value for width: {err}")));
             (cli::Width::Fixed(width), true)
         }
-        None => (
-            cli::Width::Fixed(opt.computed.available_terminal_width),
-            true,
-        ),
+        None => (cli::Width::Fixed(available_terminal_width), true),
+    }
+}
+
+/// Return whether the width available to delta is less than side-by-side-min-width, as given on
+/// the command line or in the [delta] section of git config, before the options are set.
+fn is_narrower_than_side_by_side_min_width(
+    opt: &cli::Opt,
+    git_config: &GitConfig,
+    arg_matches: &clap::ArgMatches,
+) -> bool {
+    let width = if config::user_supplied_option("width", arg_matches) {
+        opt.width.clone()
+    } else {
+        git_config.get::<String>("delta.width")
     };
-    opt.computed.decorations_width = decorations_width;
-    opt.computed.background_color_extends_to_terminal_width =
-        background_color_extends_to_terminal_width;
+    let min_width = if config::user_supplied_option("side_by_side_min_width", arg_matches) {
+        opt.side_by_side_min_width
+    } else {
+        git_config
+            .get::<usize>("delta.side-by-side-min-width")
+            .unwrap_or(opt.side_by_side_min_width)
+    };
+    let available_terminal_width = get_available_terminal_width(&Term::stdout());
+    let (decorations_width, _) = get_decorations_width(width.as_deref(), available_terminal_width);
+    features::side_by_side::is_narrower_than_min_width(
+        &decorations_width,
+        &available_terminal_width,
+        min_width,
+    )
 }
 
 fn set_true_color(opt: &mut cli::Opt) {
@@ -810,6 +906,41 @@ pub mod tests {
         remove_file(git_config_path).unwrap();
     }
 
+    #[test]
+    fn test_side_by_side_auto_in_git_config() {
+        let git_config_contents = b"
+[delta]
+    side-by-side = auto
+    side-by-side-min-width = 100
+";
+        let git_config_path = "delta__test_side_by_side_auto_in_git_config.gitconfig";
+
+        let opt = integration_test_utils::make_options_from_args_and_git_config(
+            &["--width", "120"],
+            Some(git_config_contents),
+            Some(git_config_path),
+        );
+        assert!(opt.side_by_side);
+        assert_eq!(opt.side_by_side_min_width, 100);
+
+        let opt = integration_test_utils::make_options_from_args_and_git_config(
+            &["--width", "80"],
+            Some(git_config_contents),
+            Some(git_config_path),
+        );
+        assert!(!opt.side_by_side);
+
+        // An explicit --side-by-side is not subject to the minimum width.
+        let opt = integration_test_utils::make_options_from_args_and_git_config(
+            &["--side-by-side", "--width", "80"],
+            Some(git_config_contents),
+            Some(git_config_path),
+        );
+        assert!(opt.side_by_side);
+
+        remove_file(git_config_path).unwrap();
+    }
+
     #[test]
     fn test_parse_width_specifier() {
         use super::parse_width_specifier;
@@ -9,6 +9,7 @@ use syntect::highlighting::Style as SyntectStyle;
 use syntect::parsing::{SyntaxReference, SyntaxSet};
 use unicode_segmentation::UnicodeSegmentation;
 
+use crate::combined;
 use crate::config::{self, delta_unreachable, Config};
 use crate::delta::{DiffType, InMergeConflict, MergeParents, State};
 use crate::features::hyperlinks;
@@ -16,7 +17,9 @@ use crate::features::line_numbers::{self, LineNumbersData};
 use crate::features::side_by_side::ansifill;
 use crate::features::side_by_side::{self, PanelSide};
 use crate::handlers::merge_conflict;
+use crate::handlers::word_diff::WordDiffLine;
 use crate::minusplus::*;
+use crate::moved::{self, MovedLine, MovedLines};
 use crate::paint::superimpose_style_sections::superimpose_style_sections;
 use crate::style::Style;
 use crate::{ansi, style};
@@ -38,6 +41,10 @@ pub struct Painter<'p> {
     pub line_numbers_data: Option<line_numbers::LineNumbersData<'p>>,
     pub merge_conflict_lines: merge_conflict::MergeConflictLines,
     pub merge_conflict_commit_names: merge_conflict::MergeConflictCommitNames,
+    // The sections of the current line of `--word-diff=porcelain` output.
+    pub word_diff_line: WordDiffLine,
+    // With --detect-moved, the moved lines of the commit being handled.
+    pub moved_lines: Option<MovedLines>,
 }
 
 // How the background of a line is filled up to the end
@@ -101,6 +108,8 @@ impl<'p> Painter<'p> {
             line_numbers_data,
             merge_conflict_lines: merge_conflict::MergeConflictLines::new(),
             merge_conflict_commit_names: merge_conflict::MergeConflictCommitNames::new(),
+            word_diff_line: Vec::new(),
+            moved_lines: None,
         }
     }
 
@@ -129,8 +138,22 @@ impl<'p> Painter<'p> {
         if self.minus_lines.is_empty() && self.plus_lines.is_empty() {
             return;
         }
+        let moved_lines = match &mut self.moved_lines {
+            Some(MovedLines::Collecting(collected_lines)) => {
+                // The lines are not painted while the moved lines are being detected.
+                collected_lines[Minus].extend(self.minus_lines.drain(..).map(|(line, _)| line));
+                collected_lines[Plus].extend(self.plus_lines.drain(..).map(|(line, _)| line));
+                return;
+            }
+            Some(MovedLines::Detected(detected_lines)) => MinusPlus::new(
+                moved::take_moved_lines(&mut detected_lines[Minus], &self.minus_lines),
+                moved::take_moved_lines(&mut detected_lines[Plus], &self.plus_lines),
+            ),
+            None => MinusPlus::default(),
+        };
         paint_minus_and_plus_lines(
             MinusPlus::new(&self.minus_lines, &self.plus_lines),
+            &moved_lines,
             &mut self.line_numbers_data,
             &mut self.highlighter,
             &mut self.output_buffer,
@@ -153,7 +176,8 @@ impl<'p> Painter<'p> {
             &[false],
             self.config,
         );
-        if self.config.side_by_side {
+        if self.config.side_by_side && !side_by_side::should_paint_unified(&self.line_numbers_data)
+        {
             // `lines[0].0` so the line has the '\n' already added (as in the +- case)
             side_by_side::paint_zero_lines_side_by_side(
                 &lines[0].0,
@@ -474,7 +498,9 @@ impl<'p> Painter<'p> {
             | State::HunkPlusWrapped
             | State::MergeConflict(_, _)
             | State::SubmoduleLog
-            | State::SubmoduleShort(_) => {
+            | State::SubmoduleShort(_)
+            | State::RangeDiff
+            | State::WordDiff(_) => {
                 panic!(
                     "should_compute_syntax_highlighting is undefined for state {:?}",
                     state
@@ -588,42 +614,32 @@ where
 
 pub fn paint_minus_and_plus_lines(
     lines: MinusPlus<&Vec<(String, State)>>,
+    moved_lines: &MinusPlus<Vec<Option<MovedLine>>>,
     line_numbers_data: &mut Option<LineNumbersData>,
     highlighter: &mut Option<HighlightLines>,
     output_buffer: &mut String,
     config: &config::Config,
 ) {
+    if config.combined_diff_columns
+        && !config.side_by_side
+        && combined::get_n_parents(&lines).is_some()
+    {
+        combined::paint_minus_and_plus_lines_in_parent_columns(
+            lines,
+            line_numbers_data,
+            highlighter,
+            output_buffer,
+            config,
+        );
+        return;
+    }
     let syntax_style_sections = MinusPlus::new(
         get_syntax_style_sections_for_lines(lines[Minus], highlighter.as_mut(), config),
         get_syntax_style_sections_for_lines(lines[Plus], highlighter.as_mut(), config),
     );
-    let (mut diff_style_sections, line_alignment) = get_diff_style_sections(&lines, config);
-    let lines_have_homolog = edits::make_lines_have_homolog(&line_alignment);
-    Painter::update_diff_style_sections(
-        lines[Minus],
-        &mut diff_style_sections[Minus],
-        None,
-        if config.minus_non_emph_style != config.minus_emph_style {
-            Some(config.minus_non_emph_style)
-        } else {
-            None
-        },
-        &lines_have_homolog[Minus],
-        config,
-    );
-    Painter::update_diff_style_sections(
-        lines[Plus],
-        &mut diff_style_sections[Plus],
-        Some(config.whitespace_error_style),
-        if config.plus_non_emph_style != config.plus_emph_style {
-            Some(config.plus_non_emph_style)
-        } else {
-            None
-        },
-        &lines_have_homolog[Plus],
-        config,
-    );
-    if config.side_by_side {
+    let (diff_style_sections, line_alignment, lines_have_homolog) =
+        get_diff_style_sections_and_homologs(&lines, moved_lines, config);
+    if config.side_by_side && !side_by_side::should_paint_unified(line_numbers_data) {
         side_by_side::paint_minus_and_plus_lines_side_by_side(
             lines,
             syntax_style_sections,
@@ -695,10 +711,53 @@ pub fn get_syntax_style_sections_for_lines<'a>(
     line_sections
 }
 
+/// Get background styles to represent diff for minus and plus lines in buffer, the alignment of
+/// the lines, and whether each line has a homolog, with the styles of the lines updated for that.
+#[allow(clippy::type_complexity)]
+pub fn get_diff_style_sections_and_homologs<'a>(
+    lines: &MinusPlus<&'a Vec<(String, State)>>,
+    moved_lines: &'a MinusPlus<Vec<Option<MovedLine>>>,
+    config: &config::Config,
+) -> (
+    MinusPlus<Vec<LineSections<'a, Style>>>,
+    Vec<(Option<usize>, Option<usize>)>,
+    MinusPlus<Vec<bool>>,
+) {
+    let (mut diff_style_sections, line_alignment) =
+        get_diff_style_sections(lines, moved_lines, config);
+    let lines_have_homolog = edits::make_lines_have_homolog(&line_alignment);
+    Painter::update_diff_style_sections(
+        lines[Minus],
+        &mut diff_style_sections[Minus],
+        None,
+        if config.minus_non_emph_style != config.minus_emph_style {
+            Some(config.minus_non_emph_style)
+        } else {
+            None
+        },
+        &lines_have_homolog[Minus],
+        config,
+    );
+    Painter::update_diff_style_sections(
+        lines[Plus],
+        &mut diff_style_sections[Plus],
+        Some(config.whitespace_error_style),
+        if config.plus_non_emph_style != config.plus_emph_style {
+            Some(config.plus_non_emph_style)
+        } else {
+            None
+        },
+        &lines_have_homolog[Plus],
+        config,
+    );
+    (diff_style_sections, line_alignment, lines_have_homolog)
+}
+
 /// Get background styles to represent diff for minus and plus lines in buffer.
 #[allow(clippy::type_complexity)]
 fn get_diff_style_sections<'a>(
     lines: &MinusPlus<&'a Vec<(String, State)>>,
+    moved_lines: &'a MinusPlus<Vec<Option<MovedLine>>>,
     config: &config::Config,
 ) -> (
     MinusPlus<Vec<LineSections<'a, Style>>>,
@@ -712,26 +771,37 @@ fn get_diff_style_sections<'a>(
         .iter()
         .map(|(s, state)| (s.as_str(), *config.get_style(state)))
         .unzip();
-    let (minus_line_diff_style_sections, plus_line_diff_style_sections, line_alignment) =
-        edits::infer_edits(
-            minus_lines,
-            plus_lines,
-            minus_styles,
-            config.minus_emph_style, // FIXME
-            plus_styles,
-            config.plus_emph_style, // FIXME
-            &config.tokenization_regex,
-            config.max_line_distance,
-            config.max_line_distance_for_naively_paired_lines,
-        );
-    let diff_sections = MinusPlus::new(
+    // In a combined diff, the changes relative to each parent are inferred separately.
+    let (minus_line_diff_style_sections, plus_line_diff_style_sections, mut line_alignment) =
+        combined::infer_edits_relative_to_each_parent(lines, config).unwrap_or_else(|| {
+            edits::infer_edits(
+                minus_lines,
+                plus_lines,
+                minus_styles,
+                config.minus_emph_style, // FIXME
+                plus_styles,
+                config.plus_emph_style, // FIXME
+                &config.tokenization_regex,
+                config.max_line_distance,
+                config.max_line_distance_for_naively_paired_lines,
+                config.side_by_side,
+            )
+        });
+    let mut diff_sections = MinusPlus::new(
         minus_line_diff_style_sections,
         plus_line_diff_style_sections,
     );
+    moved::update_diff_style_sections_of_moved_lines(
+        lines,
+        moved_lines,
+        &mut diff_sections,
+        &mut line_alignment,
+        config,
+    );
     (diff_sections, line_alignment)
 }
 
-fn painted_prefix(state: State, config: &config::Config) -> Option<ANSIString> {
+pub fn painted_prefix(state: State, config: &config::Config) -> Option<ANSIString> {
     use DiffType::*;
     use State::*;
     match (state, config.keep_plus_minus_markers) {
@@ -169,6 +169,20 @@ fn make_hunk_styles<'a>(opt: &'a cli::Opt, styles: &'a mut HashMap<&str, StyleRe
         opt.git_config(),
     );
 
+    let minus_moved_style = style_from_str(
+        &opt.minus_moved_style,
+        Some(Style::from_colors(
+            None,
+            Some(color::get_minus_moved_background_color_default(
+                is_light_mode,
+                true_color,
+            )),
+        )),
+        None,
+        true_color,
+        opt.git_config(),
+    );
+
     let zero_style = style_from_str(&opt.zero_style, None, None, true_color, opt.git_config());
 
     let plus_style = style_from_str(
@@ -223,6 +237,20 @@ fn make_hunk_styles<'a>(opt: &'a cli::Opt, styles: &'a mut HashMap<&str, StyleRe
         opt.git_config(),
     );
 
+    let plus_moved_style = style_from_str(
+        &opt.plus_moved_style,
+        Some(Style::from_colors(
+            None,
+            Some(color::get_plus_moved_background_color_default(
+                is_light_mode,
+                true_color,
+            )),
+        )),
+        None,
+        true_color,
+        opt.git_config(),
+    );
+
     let whitespace_error_style = style_from_str(
         &opt.whitespace_error_style,
         None,
@@ -239,11 +267,13 @@ fn make_hunk_styles<'a>(opt: &'a cli::Opt, styles: &'a mut HashMap<&str, StyleRe
             "minus-empty-line-marker-style",
             minus_empty_line_marker_style,
         ),
+        ("minus-moved-style", minus_moved_style),
         ("zero-style", zero_style),
         ("plus-style", plus_style),
         ("plus-emph-style", plus_emph_style),
         ("plus-non-emph-style", plus_non_emph_style),
         ("plus-empty-line-marker-style", plus_empty_line_marker_style),
+        ("plus-moved-style", plus_moved_style),
         ("whitespace-error-style", whitespace_error_style),
     ])
 }
@@ -312,6 +342,96 @@ fn make_commit_file_hunk_header_styles(opt: &cli::Opt, styles: &mut HashMap<&str
                 opt.git_config(),
             ),
         ),
+        (
+            "commit-author-style",
+            style_from_str(
+                &opt.commit_author_style,
+                None,
+                None,
+                true_color,
+                opt.git_config(),
+            ),
+        ),
+        (
+            "commit-branch-style",
+            style_from_str(
+                &opt.commit_branch_style,
+                None,
+                None,
+                true_color,
+                opt.git_config(),
+            ),
+        ),
+        (
+            "commit-date-style",
+            style_from_str(
+                &opt.commit_date_style,
+                None,
+                None,
+                true_color,
+                opt.git_config(),
+            ),
+        ),
+        (
+            "commit-remote-style",
+            style_from_str(
+                &opt.commit_remote_style,
+                None,
+                None,
+                true_color,
+                opt.git_config(),
+            ),
+        ),
+        (
+            "commit-signature-bad-style",
+            style_from_str(
+                &opt.commit_signature_bad_style,
+                None,
+                None,
+                true_color,
+                opt.git_config(),
+            ),
+        ),
+        (
+            "commit-signature-good-style",
+            style_from_str(
+                &opt.commit_signature_good_style,
+                None,
+                None,
+                true_color,
+                opt.git_config(),
+            ),
+        ),
+        (
+            "commit-signature-unknown-style",
+            style_from_str(
+                &opt.commit_signature_unknown_style,
+                None,
+                None,
+                true_color,
+                opt.git_config(),
+            ),
+        ),
+        (
+            "commit-tag-style",
+            style_from_str(
+                &opt.commit_tag_style,
+                None,
+                None,
+                true_color,
+                opt.git_config(),
+            ),
+        ),
+        (
+            "commit-trailer-style",
+            style_from_str(
+                &opt.commit_trailer_style,
+                None,
+                None,
+                true_color,
+                opt.git_config(),
+            ),
+        ),
         (
             "file-style",
             style_from_str_with_handling_of_special_decoration_attributes(
@@ -332,6 +452,16 @@ fn make_commit_file_hunk_header_styles(opt: &cli::Opt, styles: &mut HashMap<&str
                 opt.git_config(),
             ),
         ),
+        (
+            "notes-style",
+            style_from_str_with_handling_of_special_decoration_attributes(
+                &opt.notes_style,
+                None,
+                Some(&opt.notes_decoration_style),
+                true_color,
+                opt.git_config(),
+            ),
+        ),
         (
             "hunk-header-file-style",
             style_from_str_with_handling_of_special_decoration_attributes(
@@ -368,6 +498,18 @@ fn make_blame_styles(opt: &cli::Opt, styles: &mut HashMap<&str, StyleReference>)
             ),
         );
     };
+    if let Some(style_string) = &opt.blame_moved_style {
+        styles.insert(
+            "blame-moved-style",
+            style_from_str(
+                style_string,
+                None,
+                None,
+                opt.computed.true_color,
+                opt.git_config(),
+            ),
+        );
+    };
     if let Some(style_string) = &opt.blame_separator_style {
         styles.insert(
             "blame-separator-style",
@@ -4,7 +4,7 @@ use itertools::Itertools;
 
 use crate::cli;
 use crate::config;
-use crate::features::side_by_side::{Left, Right};
+use crate::features::side_by_side::{Left, PanelSplit, Right};
 use crate::minusplus::*;
 use crate::paint::BgFillMethod;
 use crate::style;
@@ -126,6 +126,8 @@ pub fn show_config(config: &config::Config, writer: &mut dyn Write) -> std::io::
     pager                         = {pager}
     paging                        = {paging_mode}
     side-by-side                  = {side_by_side}
+    side-by-side-min-width        = {side_by_side_min_width}
+    side-by-side-split            = {side_by_side_split}
     syntax-theme                  = {syntax_theme}
     width                         = {width}
     tabs                          = {tab_width}
@@ -149,6 +151,11 @@ pub fn show_config(config: &config::Config, writer: &mut dyn Write) -> std::io::
             PagingMode::QuitIfOneScreen => "auto",
         },
         side_by_side = config.side_by_side,
+        side_by_side_min_width = config.side_by_side_min_width,
+        side_by_side_split = match config.side_by_side_split {
+            PanelSplit::Percent(percent) => percent.to_string(),
+            PanelSplit::Adaptive => "adaptive".to_string(),
+        },
         syntax_theme = config
             .syntax_theme
             .clone()
@@ -124,7 +124,7 @@ mod tests {
         // Change
         assert_eq!(lines.nth(7).unwrap(), "This is different from b");
         // File uniqueness
-        assert_eq!(lines.nth(2).unwrap(), "Only in a/: just_a");
+        assert_eq!(lines.nth(2).unwrap(), "removed: a/just_a");
         // DiffHeader divider
         assert!(lines.next().unwrap().starts_with("───────"));
         // Next hunk
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::path::{Component, Path, PathBuf};
 
 use crate::config::Config;
@@ -38,6 +39,53 @@ pub fn relativize_path_maybe(path: &str, config: &Config) -> Option<PathBuf> {
     }
 }
 
+/// Unquote a path quoted in C style by git (see core.quotePath in git-config(1)) or by GNU diff,
+/// e.g. `"a/\303\251t\303\251.txt"` is `a/été.txt`. A path which is not surrounded by quotes is
+/// returned as it is.
+pub fn unquote_path(path: &str) -> Cow<'_, str> {
+    let quoted = match path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) {
+        Some(quoted) => quoted,
+        None => return Cow::from(path),
+    };
+    let mut bytes = Vec::with_capacity(quoted.len());
+    let mut input = quoted.bytes().peekable();
+    while let Some(byte) = input.next() {
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        let unescaped = match input.next() {
+            Some(b'a') => 0x07,
+            Some(b'b') => 0x08,
+            Some(b't') => b'\t',
+            Some(b'n') => b'\n',
+            Some(b'v') => 0x0b,
+            Some(b'f') => 0x0c,
+            Some(b'r') => b'\r',
+            // An octal escape of a byte, e.g. of one byte of a UTF-8 encoded character.
+            Some(digit @ b'0'..=b'3') => {
+                let mut value = digit - b'0';
+                for _ in 0..2 {
+                    match input.next_if(|c| (b'0'..=b'7').contains(c)) {
+                        Some(digit) => value = value * 8 + (digit - b'0'),
+                        None => break,
+                    }
+                }
+                value
+            }
+            Some(byte @ b'"') | Some(byte @ b'\\') => byte,
+            // Not an escape sequence, e.g. in a Windows path
+            Some(byte) => {
+                bytes.push(b'\\');
+                byte
+            }
+            None => b'\\',
+        };
+        bytes.push(unescaped);
+    }
+    Cow::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
 /// Return current working directory of the user's shell process. I.e. the directory which they are
 /// in when delta exits. This is the directory relative to which the file paths in delta output are
 /// constructed if they are using either (a) delta's relative-paths option or (b) git's --relative
@@ -14,9 +14,10 @@ pub enum CallingProcess {
     GitLog(CommandLine),
     GitReflog(CommandLine),
     GitGrep(CommandLine),
-    OtherGrep, // rg, grep, ag, ack, etc
-    None,      // no matching process could be found
-    Pending,   // calling process is currently being determined
+    OtherGrep,           // rg, grep, ag, ack, etc
+    GitInteractivePatch, // git add -p etc, of which delta is the interactive.diffFilter
+    None,                // no matching process could be found
+    Pending,             // calling process is currently being determined
 }
 // TODO: Git blame is currently handled differently
 
@@ -35,6 +36,7 @@ impl CallingProcess {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct CommandLine {
     pub long_options: HashSet<String>,
+    pub long_option_values: HashMap<String, String>,
     pub short_options: HashSet<String>,
     last_arg: Option<String>,
 }
@@ -114,28 +116,130 @@ pub fn git_blame_filename_extension() -> Option<String> {
     calling_process_cmdline(ProcInfo::new(), guess_git_blame_filename_extension)
 }
 
+pub fn git_blame_filename() -> Option<String> {
+    calling_process_cmdline(ProcInfo::new(), guess_git_blame_filename)
+}
+
 pub fn guess_git_blame_filename_extension(args: &[String]) -> ProcessArgs<String> {
-    let all_args = args.iter().map(|s| s.as_str());
+    match guess_git_blame_filename(args) {
+        ProcessArgs::Args(filename) => match filename.split('.').last() {
+            Some(arg) => ProcessArgs::Args(arg.to_string()),
+            None => ProcessArgs::ArgError,
+        },
+        ProcessArgs::ArgError => ProcessArgs::ArgError,
+        ProcessArgs::OtherProcess => ProcessArgs::OtherProcess,
+    }
+}
+
+pub fn guess_git_blame_filename(args: &[String]) -> ProcessArgs<String> {
+    let (git_args, blame_args) = match args.iter().position(|arg| arg == "blame") {
+        Some(i) => args.split_at(i + 1),
+        None => return ProcessArgs::OtherProcess,
+    };
 
     // See git(1) and git-blame(1). Some arguments separate their parameter with space or '=', e.g.
-    // --date 2015 or --date=2015.
+    // --date 2015 or --date=2015. The parameter of `git blame -C` is not separated, unlike that of
+    // `git -C`.
+    let git_options_with_parameter = "-C -c";
     let git_blame_options_with_parameter =
-        "-C -c -L --since --ignore-rev --ignore-revs-file --contents --reverse --date";
+        "-L --since --ignore-rev --ignore-revs-file --contents --reverse --date";
 
-    let selected_args =
-        skip_uninteresting_args(all_args, git_blame_options_with_parameter.split(' '));
+    let mut selected_args = skip_uninteresting_args(
+        git_args.iter().map(|s| s.as_str()),
+        git_options_with_parameter.split(' '),
+    );
+    selected_args.extend(skip_uninteresting_args(
+        blame_args.iter().map(|s| s.as_str()),
+        git_blame_options_with_parameter.split(' '),
+    ));
 
     match selected_args.as_slice() {
-        [git, "blame", .., last_arg] if is_git_binary(git) => match last_arg.split('.').last() {
-            Some(arg) => ProcessArgs::Args(arg.to_string()),
+        [git, "blame", .., last_arg] if is_git_binary(git) => {
+            ProcessArgs::Args(last_arg.to_string())
+        }
+        [git, "blame"] if is_git_binary(git) => ProcessArgs::ArgError,
+        _ => ProcessArgs::OtherProcess,
+    }
+}
+
+/// The extension of the file whose rejected hunks are the input, as in `cat main.rs.rej | delta`.
+pub fn reject_filename_extension() -> Option<String> {
+    calling_process_cmdline(ProcInfo::new(), guess_reject_filename_extension)
+}
+
+pub fn guess_reject_filename_extension(args: &[String]) -> ProcessArgs<String> {
+    // patch(1) and git-apply(1) write the rejected hunks of e.g. "src/main.rs" to "src/main.rs.rej".
+    match args
+        .iter()
+        .skip(1)
+        .rev()
+        .find_map(|arg| arg.strip_suffix(".rej"))
+    {
+        Some(filename) => match crate::handlers::diff_header::get_extension(filename) {
+            Some(extension) => ProcessArgs::Args(extension.to_string()),
             None => ProcessArgs::ArgError,
         },
-        [git, "blame"] if is_git_binary(git) => ProcessArgs::ArgError,
+        None => ProcessArgs::OtherProcess,
+    }
+}
+
+/// Is delta running as the `interactive.diffFilter` of e.g. `git add -p`? This is found out with
+/// the calling process, so that the processes are only looked at once.
+pub fn git_interactive_patch() -> bool {
+    *calling_process() == CallingProcess::GitInteractivePatch
+}
+
+pub fn describe_git_interactive_patch(args: &[String]) -> ProcessArgs<bool> {
+    let mut args = args.iter().map(|s| s.as_str());
+    match args.next() {
+        // Before git 2.40 `git add -p` was implemented by a perl script.
+        Some(command) if command.ends_with("git-add--interactive") => ProcessArgs::Args(true),
+        Some(command) if is_git_binary(command) => {
+            let (subcommand, args) = match git_subcommand(args) {
+                Some((subcommand, args))
+                    if ["add", "checkout", "commit", "reset", "restore", "stash"]
+                        .contains(&subcommand) =>
+                {
+                    (subcommand, args)
+                }
+                Some(_) => return ProcessArgs::Args(false),
+                None => return ProcessArgs::ArgError,
+            };
+            let mut args = args.peekable();
+            if subcommand == "stash" {
+                // Only `git stash push -p` and `git stash save -p` (or `git stash -p`, short for
+                // the former) are interactive: e.g. `git stash show -p` only shows a patch.
+                match args.peek() {
+                    Some(&"push") | Some(&"save") => {
+                        args.next();
+                    }
+                    Some(arg) if arg.starts_with('-') => {}
+                    _ => return ProcessArgs::Args(false),
+                }
+            }
+            // `-i` is short for `--interactive` only for `git add`: e.g. for `git commit` it is
+            // short for `--include`.
+            let command_line = parse_command_line(args);
+            let is_interactive = ["--patch", "--interactive"]
+                .iter()
+                .any(|option| command_line.long_options.contains(*option))
+                || command_line.short_options.contains("-p")
+                || subcommand == "add" && command_line.short_options.contains("-i");
+            ProcessArgs::Args(is_interactive)
+        }
+        // The perl script may also be run by its interpreter.
+        Some(command) if command.ends_with("perl") => match args.next() {
+            Some(script) if script.ends_with("git-add--interactive") => ProcessArgs::Args(true),
+            _ => ProcessArgs::OtherProcess,
+        },
         _ => ProcessArgs::OtherProcess,
     }
 }
 
 pub fn describe_calling_process(args: &[String]) -> ProcessArgs<CallingProcess> {
+    if describe_git_interactive_patch(args) == ProcessArgs::Args(true) {
+        return ProcessArgs::Args(CallingProcess::GitInteractivePatch);
+    }
     let mut args = args.iter().map(|s| s.as_str());
 
     fn is_any_of<'a, I>(cmd: Option<&str>, others: I) -> bool
@@ -209,6 +313,32 @@ pub fn describe_calling_process(args: &[String]) -> ProcessArgs<CallingProcess>
     }
 }
 
+// Return the subcommand of a git command line, and the arguments following it: the first argument
+// which is not an option of git itself, skipping the values of those options which are given as
+// the next argument.
+fn git_subcommand<'a, I>(mut args: I) -> Option<(&'a str, I)>
+where
+    I: Iterator<Item = &'a str>,
+{
+    let options_with_value = [
+        "-C",
+        "-c",
+        "--config-env",
+        "--git-dir",
+        "--namespace",
+        "--super-prefix",
+        "--work-tree",
+    ];
+    while let Some(arg) = args.next() {
+        if options_with_value.contains(&arg) {
+            args.next();
+        } else if !arg.starts_with('-') {
+            return Some((arg, args));
+        }
+    }
+    None
+}
+
 fn is_git_binary(git: &str) -> bool {
     // Ignore case, for e.g. NTFS or APFS file systems
     Path::new(git)
@@ -251,10 +381,11 @@ where
     }
 }
 
-// Given `--aa val -bc -d val e f -- ...` return
-// ({"--aa"}, {"-b", "-c", "-d"})
+// Given `--aa val --bb=val -bc -d val e f -- ...` return
+// ({"--aa", "--bb"}, {"--bb": "val"}, {"-b", "-c", "-d"})
 fn parse_command_line<'a>(args: impl Iterator<Item = &'a str>) -> CommandLine {
     let mut long_options = HashSet::new();
+    let mut long_option_values = HashMap::new();
     let mut short_options = HashSet::new();
     let mut last_arg = None;
 
@@ -262,7 +393,14 @@ fn parse_command_line<'a>(args: impl Iterator<Item = &'a str>) -> CommandLine {
         if s == "--" {
             break;
         } else if s.starts_with("--") {
-            long_options.insert(s.split('=').next().unwrap().to_owned());
+            let (option, value) = match s.split_once('=') {
+                Some((option, value)) => (option, Some(value)),
+                None => (s, None),
+            };
+            long_options.insert(option.to_owned());
+            if let Some(value) = value {
+                long_option_values.insert(option.to_owned(), value.to_owned());
+            }
         } else if let Some(suffix) = s.strip_prefix('-') {
             short_options.extend(suffix.chars().map(|c| format!("-{c}")));
         } else {
@@ -272,6 +410,7 @@ fn parse_command_line<'a>(args: impl Iterator<Item = &'a str>) -> CommandLine {
 
     CommandLine {
         long_options,
+        long_option_values,
         short_options,
         last_arg: last_arg.map(|s| s.to_string()),
     }
@@ -746,6 +885,94 @@ pub mod tests {
         assert_eq!(guess_git_blame_filename_extension(&args), Args("".into()));
     }
 
+    #[test]
+    fn test_guess_reject_filename_extension() {
+        use ProcessArgs::Args;
+
+        fn make_string_vec(args: &[&str]) -> Vec<String> {
+            args.iter().map(|&x| x.to_owned()).collect::<Vec<String>>()
+        }
+        let args = make_string_vec(&["cat", "src/main.rs.rej"]);
+        assert_eq!(guess_reject_filename_extension(&args), Args("rs".into()));
+
+        let args = make_string_vec(&["less", "-R", "a.py.rej", "Makefile.rej"]);
+        assert_eq!(
+            guess_reject_filename_extension(&args),
+            Args("Makefile".into())
+        );
+
+        let args = make_string_vec(&["cat", "src/main.rs"]);
+        assert_eq!(
+            guess_reject_filename_extension(&args),
+            ProcessArgs::OtherProcess
+        );
+
+        let args = make_string_vec(&["cat", ".rej"]);
+        assert_eq!(
+            guess_reject_filename_extension(&args),
+            ProcessArgs::ArgError
+        );
+    }
+
+    #[test]
+    fn test_guess_git_blame_filename() {
+        use ProcessArgs::Args;
+
+        fn make_string_vec(args: &[&str]) -> Vec<String> {
+            args.iter().map(|&x| x.to_owned()).collect::<Vec<String>>()
+        }
+        let args = make_string_vec(&["git", "blame", "-C", "-M", "src/main.rs"]);
+        assert_eq!(guess_git_blame_filename(&args), Args("src/main.rs".into()));
+
+        let args = make_string_vec(&["git", "-C", "repo", "blame", "-C", "src/main.rs"]);
+        assert_eq!(guess_git_blame_filename(&args), Args("src/main.rs".into()));
+
+        let args = make_string_vec(&["git", "log", "-p"]);
+        assert_eq!(guess_git_blame_filename(&args), ProcessArgs::OtherProcess);
+    }
+
+    #[test]
+    fn test_describe_git_interactive_patch() {
+        use ProcessArgs::Args;
+
+        fn make_string_vec(args: &[&str]) -> Vec<String> {
+            args.iter().map(|&x| x.to_owned()).collect::<Vec<String>>()
+        }
+        for (args, expected) in [
+            (&["git", "add", "-p"][..], Args(true)),
+            (&["git", "-C", "repo", "add", "--patch", "src"], Args(true)),
+            (&["git", "checkout", "-p", "HEAD"], Args(true)),
+            (&["git", "stash", "push", "-p"], Args(true)),
+            (&["git", "stash", "-p"], Args(true)),
+            (&["git", "stash", "show", "-p"], Args(false)),
+            (&["git", "stash", "list", "-p"], Args(false)),
+            (
+                &["/usr/lib/git-core/git-add--interactive", "--patch"],
+                Args(true),
+            ),
+            (&["git", "add", "src/main.rs"], Args(false)),
+            (&["git", "add", "-i"], Args(true)),
+            (&["git", "commit", "-i", "src"], Args(false)),
+            (&["git", "commit", "--interactive"], Args(true)),
+            (
+                &["git", "-c", "add.interactive.useBuiltin=true", "add", "-p"],
+                Args(true),
+            ),
+            (&["git", "--git-dir", "stash", "reset", "-p"], Args(true)),
+            (&["git", "log", "stash", "-p"], Args(false)),
+            (&["git", "show", "add", "-p"], Args(false)),
+            (&["git", "diff"], Args(false)),
+            (&["git", "--no-pager"], ProcessArgs::ArgError),
+            (&["less", "-R"], ProcessArgs::OtherProcess),
+        ] {
+            assert_eq!(
+                describe_git_interactive_patch(&make_string_vec(args)),
+                expected,
+                "{args:?}"
+            );
+        }
+    }
+
     #[derive(Debug)]
     struct FakeProc {
         #[allow(dead_code)]
@@ -1064,6 +1291,7 @@ pub mod tests {
 
         let empty_command_line = CommandLine {
             long_options: [].into(),
+            long_option_values: [].into(),
             short_options: [].into(),
             last_arg: Some("hello.txt".to_string()),
         };
@@ -1109,6 +1337,7 @@ pub mod tests {
 
         let expected_result = Some(CallingProcess::GitGrep(CommandLine {
             long_options: set(&["--function-context", "--show-function", "--foo"]),
+            long_option_values: [("--foo".to_string(), "val".to_string())].into(),
             short_options: set(&["-a", "-b", "-n", "-W"]),
             last_arg: Some("hello.txt".to_string()),
         }));
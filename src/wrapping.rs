@@ -374,6 +374,52 @@ where
     (size_prev, wrapped.len())
 }
 
+/// Call [`wrap_line`] for the `syntax` and the `diff` sections of a line, returning those of
+/// each of the lines it is wrapped into.
+pub fn wrap_line_sections<'a>(
+    config: &'a Config,
+    syntax: LineSections<'a, SyntectStyle>,
+    diff: LineSections<'a, Style>,
+    line_width: usize,
+    fill_style: &Style,
+) -> (
+    Vec<LineSections<'a, SyntectStyle>>,
+    Vec<LineSections<'a, Style>>,
+) {
+    let (mut wrapped_syntax, mut wrapped_diff) = (Vec::new(), Vec::new());
+    wrap_if_too_long(
+        config,
+        &mut wrapped_syntax,
+        syntax,
+        true,
+        line_width,
+        &config.null_syntect_style,
+        &Some(config.wrap_config.inline_hint_syntect_style),
+    );
+    // TODO: Why is the background color set to white when
+    // ansi_term_style.background is None?
+    let inline_hint_style = if config
+        .inline_hint_style
+        .ansi_term_style
+        .background
+        .is_some()
+    {
+        Some(config.inline_hint_style)
+    } else {
+        None
+    };
+    wrap_if_too_long(
+        config,
+        &mut wrapped_diff,
+        diff,
+        true,
+        line_width,
+        fill_style,
+        &inline_hint_style,
+    );
+    (wrapped_syntax, wrapped_diff)
+}
+
 /// Call [`wrap_line`] for the `syntax` and the `diff` lines if `wrapinfo` says
 /// a specific line was longer than `line_width`. Return an adjusted `alignment`
 /// with regard to the added wrapped lines.
@@ -588,9 +634,9 @@ pub fn wrap_zero_block<'c: 'a, 'a>(
     Vec<LineSections<'a, SyntectStyle>>,
     Vec<LineSections<'a, Style>>,
 ) {
-    // The width is the minimum of the left/right side. The panels should be equally sized,
-    // but in rare cases the remaining panel width might differ due to the space the line
-    // numbers take up.
+    // The width is the minimum of the left/right side. The panels differ in size with
+    // --side-by-side-split, and the remaining panel width might also differ due to the space
+    // the line numbers take up.
     let line_width = if let Some(line_numbers_data) = line_numbers_data {
         let width = available_line_width(config, line_numbers_data);
         std::cmp::min(width[Left], width[Right])